@@ -0,0 +1,42 @@
+@group(0) @binding(0)
+var texture: texture_storage_2d<rgba8unorm, read_write>;
+
+struct Ball {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    radius: f32,
+};
+
+struct Balls {
+    count: u32,
+    items: array<Ball>,
+};
+
+@group(1) @binding(0)
+var<storage, read> balls: Balls;
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let dimensions = textureDimensions(texture);
+    if (any(invocation_id.xy >= dimensions)) {
+        return;
+    }
+
+    let location = vec2<i32>(invocation_id.xy);
+    let p = vec2<f32>(invocation_id.xy) + 0.5;
+
+    // sum of the fields of all the balls
+    var field = 0.0;
+    for (var i = 0u; i < balls.count; i++) {
+        let ball = balls.items[i];
+        let d = p - ball.position;
+        field += ball.radius * ball.radius / max(dot(d, d), 0.0001);
+    }
+
+    var color = vec3<f32>(0.05, 0.05, 0.1);
+    if (field > 1.0) {
+        color = mix(vec3<f32>(0.9, 0.3, 0.1), vec3<f32>(1.0, 0.9, 0.4), clamp(field - 1.0, 0.0, 1.0));
+    }
+
+    textureStore(texture, location, vec4<f32>(color, 1.0));
+}
@@ -0,0 +1,32 @@
+@group(0) @binding(0)
+var texture: texture_storage_2d<rgba8unorm, read_write>;
+
+struct ParamsUniforms {
+    time: f32,
+    speed: f32,
+    color: vec4<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> params: ParamsUniforms;
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let dimensions = textureDimensions(texture);
+    if (any(invocation_id.xy >= dimensions)) {
+        return;
+    }
+
+    let location = vec2<i32>(invocation_id.xy);
+    let uv = vec2<f32>(invocation_id.xy) / vec2<f32>(dimensions);
+    let t = params.time * params.speed;
+
+    var v = sin(uv.x * 10.0 + t);
+    v += sin((uv.y * 10.0 + t) * 0.5);
+    v += sin((uv.x * 10.0 + uv.y * 10.0 + t) * 0.5);
+    let c = uv * 10.0 + vec2<f32>(sin(t / 3.0), cos(t / 2.0)) * 5.0;
+    v += sin(sqrt(c.x * c.x + c.y * c.y + 1.0) + t);
+    let intensity = 0.5 + 0.5 * sin(v * 3.14159);
+
+    textureStore(texture, location, vec4<f32>(params.color.rgb * intensity, 1.0));
+}
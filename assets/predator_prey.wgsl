@@ -0,0 +1,85 @@
+// previous state, layer 0 is the prey and layer 1 the predators
+@group(0) @binding(2)
+var input: texture_2d_array<f32>;
+
+@group(0) @binding(0)
+var output: texture_storage_2d_array<rgba8unorm, read_write>;
+
+@group(1) @binding(0)
+var view: texture_storage_2d<rgba8unorm, write>;
+
+struct Params {
+    frame: u32,
+};
+
+@group(1) @binding(1)
+var<uniform> params: Params;
+
+const PREY: i32 = 0;
+const PREDATORS: i32 = 1;
+
+fn random(location: vec2<i32>, layer: u32) -> f32 {
+    // pcg hash
+    let state = u32(location.x) * 747796405u + u32(location.y) * 2891336453u + layer * 277803737u + params.frame * 1103515245u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return f32((word >> 22u) ^ word) / 4294967295.0;
+}
+
+fn cell(location: vec2<i32>, layer: i32) -> bool {
+    let dimensions = vec2<i32>(textureDimensions(input));
+    // wraps around the edges
+    let wrapped = (location + dimensions) % dimensions;
+    return textureLoad(input, wrapped, layer, 0).r > 0.5;
+}
+
+fn neighbours(location: vec2<i32>, layer: i32) -> i32 {
+    var count = 0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            if ((x != 0 || y != 0) && cell(location + vec2<i32>(x, y), layer)) {
+                count += 1;
+            }
+        }
+    }
+    return count;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let dimensions = vec2<i32>(textureDimensions(output));
+    let location = vec2<i32>(invocation_id.xy);
+    if (any(location >= dimensions)) {
+        return;
+    }
+    let layer = i32(invocation_id.z);
+    let roll = random(location, invocation_id.z);
+
+    let prey = cell(location, PREY);
+    let predator = cell(location, PREDATORS);
+    var alive: bool;
+    if (layer == PREY) {
+        // the prey spreads to its neighbours, and is eaten by a predator in the same cell
+        let spreads = roll < 0.04 * f32(neighbours(location, PREY)) || roll < 0.00002;
+        alive = !predator && (prey || spreads);
+    } else {
+        // the predators hunt prey next to them and starve without it
+        let fed = prey || neighbours(location, PREY) > 0;
+        if (predator) {
+            alive = fed && roll > 0.05;
+        } else {
+            alive = prey && neighbours(location, PREDATORS) > 0 && roll < 0.3;
+        }
+    }
+    textureStore(output, location, layer, vec4<f32>(f32(alive)));
+
+    // the first layer draws the previous state of both fields
+    if (layer == PREY) {
+        var color = vec3<f32>(0.05, 0.05, 0.08);
+        if (predator) {
+            color = vec3<f32>(0.9, 0.2, 0.15);
+        } else if (prey) {
+            color = vec3<f32>(0.3, 0.8, 0.3);
+        }
+        textureStore(view, location, vec4<f32>(color, 1.0));
+    }
+}
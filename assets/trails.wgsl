@@ -0,0 +1,60 @@
+@group(0) @binding(0)
+var output: texture_storage_2d<rgba8unorm, read_write>;
+
+// previous state, sampled
+@group(0) @binding(2)
+var input: texture_2d<f32>;
+
+@group(0) @binding(4)
+var input_sampler: sampler;
+
+struct Params {
+    time: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> params: Params;
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let dimensions = vec2<i32>(textureDimensions(output));
+    let location = vec2<i32>(invocation_id.xy);
+    if (any(location >= dimensions)) {
+        return;
+    }
+    let size = vec2<f32>(dimensions);
+    let uv = (vec2<f32>(location) + 0.5) / size;
+
+    // the previous state slowly swirls around the center
+    let from_center = uv - 0.5;
+    let angle = 0.01;
+    let rotated = vec2<f32>(
+        from_center.x * cos(angle) - from_center.y * sin(angle),
+        from_center.x * sin(angle) + from_center.y * cos(angle),
+    );
+    let advected = textureSampleLevel(input, input_sampler, rotated * 0.995 + 0.5, 0.0).rgb;
+
+    // blur and fade
+    var sum = advected;
+    for (var i = 0; i < 4; i++) {
+        let offset = array<vec2<i32>, 4>(vec2(1, 0), vec2(-1, 0), vec2(0, 1), vec2(0, -1))[i];
+        let neighbour = clamp(location + offset, vec2<i32>(0), dimensions - 1);
+        sum += textureLoad(input, neighbour, 0).rgb;
+    }
+    var color = sum / 5.0 * 0.98;
+
+    // moving emitters
+    let t = params.time;
+    let emitters = array<vec2<f32>, 2>(
+        size * (0.5 + 0.4 * vec2<f32>(cos(t * 1.3), sin(t * 1.7))),
+        size * (0.5 + 0.4 * vec2<f32>(sin(t * 0.9), cos(t * 1.1))),
+    );
+    let colors = array<vec3<f32>, 2>(vec3<f32>(1.0, 0.4, 0.1), vec3<f32>(0.1, 0.6, 1.0));
+    for (var i = 0; i < 2; i++) {
+        if (distance(vec2<f32>(location), emitters[i]) < 4.0) {
+            color = colors[i];
+        }
+    }
+
+    textureStore(output, location, vec4<f32>(color, 1.0));
+}
@@ -30,6 +30,7 @@ fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
             pixel_buffer: PixelBuffer {
                 size,
                 fill: Fill::none(),
+                ..Default::default()
             },
             sprite: Sprite {
                 image,
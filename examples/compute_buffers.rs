@@ -0,0 +1,99 @@
+//! Compute shader that reads the previous state from one image and writes the next one
+//! into another, with the images swapped manually every frame.
+//!
+//! The image that is read is bound as a sampled texture, see `assets/trails.wgsl`.
+//!
+//! Hold space to stop swapping, the shader keeps reading the same image.
+
+use bevy::{
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+use bevy_pixel_buffer::{pixel_buffer::create_image, prelude::*};
+
+const SIZE: UVec2 = UVec2::new(320, 240);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PixelBufferPlugin,
+            ComputeShaderPlugin::<TrailsShader>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (update_time, swap_buffers))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut cs: ResMut<Assets<TrailsShader>>,
+) {
+    let other = images.add(create_image(SIZE.into()));
+
+    let mut pb = PixelBufferBuilder::new()
+        .with_size(PixelBufferSize {
+            size: SIZE,
+            pixel_size: UVec2::new(3, 3),
+        })
+        .spawn(&mut commands, &mut images);
+    let image = pb.image();
+    pb.entity().insert((
+        cs.add(TrailsShader::default()),
+        // the sprite displays the image that is written
+        ComputeBuffers::new(other, image),
+    ));
+}
+
+fn update_time(
+    pb: Query<&Handle<TrailsShader>>,
+    mut cs: ResMut<Assets<TrailsShader>>,
+    time: Res<Time>,
+) {
+    cs.get_mut(pb.single()).unwrap().params.time = time.elapsed_secs();
+}
+
+/// The image written this frame is read in the next one
+fn swap_buffers(
+    mut pb: Query<(&PixelBuffer, &mut Sprite, &mut ComputeBuffers)>,
+    images: Res<Assets<Image>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.pressed(KeyCode::Space) {
+        return;
+    }
+
+    let (pb, mut sprite, mut buffers) = pb.single_mut();
+    buffers.swap();
+    pb.swap_image(&mut sprite, &images, buffers.write.clone())
+        .unwrap();
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+#[type_path = "example::trails_shader"]
+struct TrailsShader {
+    #[uniform(0)]
+    params: Params,
+}
+
+#[derive(ShaderType, Clone, Debug, Default)]
+struct Params {
+    time: f32,
+}
+
+impl ComputeShader for TrailsShader {
+    fn shader() -> ShaderRef {
+        "trails.wgsl".into()
+    }
+
+    fn entry_point() -> std::borrow::Cow<'static, str> {
+        "update".into()
+    }
+
+    fn workgroups(texture_size: UVec2) -> UVec2 {
+        // round up, the shader checks the bounds
+        (texture_size + 7) / 8
+    }
+}
@@ -0,0 +1,148 @@
+//! Animated compute shader with its parameters updated every frame from the CPU.
+//!
+//! Press space to change the color.
+
+use bevy::{
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+use bevy_pixel_buffer::{compute_shader::update_shader, prelude::*};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PixelBufferPlugin,
+            ComputeShaderPlugin::<PlasmaShader>::default(), // add a plugin to handle our shader
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, update_params)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut cs: ResMut<Assets<PlasmaShader>>,
+) {
+    PixelBufferBuilder::new()
+        .with_size(PixelBufferSize {
+            size: UVec2::new(320, 240),
+            pixel_size: UVec2::new(3, 3),
+        })
+        .spawn(&mut commands, &mut images)
+        .entity()
+        // insert the shader handle
+        .insert(cs.add(PlasmaShader::default()));
+}
+
+/// The shader is an asset, modifying it with `update_shader` updates its uniforms in the GPU.
+fn update_params(
+    pb: Query<&Handle<PlasmaShader>>,
+    mut cs: ResMut<Assets<PlasmaShader>>,
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    update_shader(&mut cs, pb.single(), |shader| {
+        let params = &mut shader.params;
+        params.time = time.elapsed_secs();
+        if keyboard_input.just_pressed(KeyCode::Space) {
+            params.color = Color::hsl(rand::random::<f32>() * 360.0, 0.8, 0.6)
+                .to_linear()
+                .to_vec4();
+        }
+    });
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+#[type_path = "example::plasma_shader"]
+struct PlasmaShader {
+    #[uniform(0)]
+    params: Params,
+}
+
+#[derive(ShaderType, Clone, Debug)]
+struct Params {
+    time: f32,
+    speed: f32,
+    color: Vec4,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            speed: 1.5,
+            color: Vec4::new(0.2, 0.6, 1.0, 1.0),
+        }
+    }
+}
+
+impl ComputeShader for PlasmaShader {
+    fn shader() -> ShaderRef {
+        "plasma.wgsl".into()
+    }
+
+    fn entry_point() -> std::borrow::Cow<'static, str> {
+        "update".into()
+    }
+
+    fn workgroups(texture_size: UVec2) -> UVec2 {
+        // round up, the shader checks the bounds
+        (texture_size + 7) / 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::window::ExitCondition;
+
+    use super::*;
+
+    /// Runs the example without a window until the shader has been dispatched.
+    ///
+    /// Run it with `cargo test --example compute_shader -- --ignored`.
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn pipeline_reaches_update() {
+        let mut app = App::new();
+        app.add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .disable::<bevy::winit::WinitPlugin>(),
+            PixelBufferPlugin,
+            ComputeShaderPlugin::<PlasmaShader>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, update_params);
+        app.finish();
+        app.cleanup();
+
+        // the shader is loaded and the pipeline compiled in the background
+        for _ in 0..500 {
+            app.update();
+            if app
+                .world()
+                .resource::<ComputeShaderStatus<PlasmaShader>>()
+                .is_ready()
+            {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let status = app.world().resource::<ComputeShaderStatus<PlasmaShader>>();
+        assert!(
+            status.is_ready(),
+            "the pipeline did not reach State::Update"
+        );
+        assert!(status.dispatches() > 0);
+    }
+}
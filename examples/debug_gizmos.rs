@@ -0,0 +1,86 @@
+//! Debug overlays drawn from multiple systems with `PixelGizmos` into a transparent
+//! buffer on top of the main one.
+
+use bevy::prelude::*;
+use bevy_pixel_buffer::prelude::*;
+
+const SIZE: PixelBufferSize = PixelBufferSize {
+    size: UVec2 { x: 64, y: 48 },
+    pixel_size: UVec2 { x: 10, y: 10 },
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, PixelBufferPlugin))
+        .insert_resource(Ball {
+            position: Vec2::new(20.0, 20.0),
+            velocity: Vec2::new(25.0, 15.0),
+        })
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_ball, draw_ball_debug, draw_bounds_debug))
+        .run();
+}
+
+#[derive(Resource)]
+struct Ball {
+    position: Vec2,
+    velocity: Vec2,
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    // main buffer, with a camera
+    PixelBufferBuilder::new()
+        .with_size(SIZE)
+        .spawn(&mut commands, &mut images)
+        .edit_frame(|frame| frame.per_pixel(|p, _| Pixel::from([p.x as u8 * 4, 0, 64, 255])));
+
+    // debug layer on top
+    PixelBufferBuilder::new()
+        .with_size(SIZE)
+        .with_render(RenderConfig::sprite())
+        .spawn(&mut commands, &mut images)
+        .entity()
+        .insert((Transform::from_xyz(0.0, 0.0, 1.0), PixelGizmosTarget));
+}
+
+fn move_ball(mut ball: ResMut<Ball>, time: Res<Time>) {
+    let max = SIZE.size.as_vec2() - 1.0;
+    let velocity = ball.velocity;
+    ball.position += velocity * time.delta_secs();
+    if !(0.0..=max.x).contains(&ball.position.x) {
+        ball.velocity.x = -ball.velocity.x;
+    }
+    if !(0.0..=max.y).contains(&ball.position.y) {
+        ball.velocity.y = -ball.velocity.y;
+    }
+    ball.position = ball.position.clamp(Vec2::ZERO, max);
+}
+
+/// Position and velocity of the ball
+fn draw_ball_debug(ball: Res<Ball>, mut gizmos: PixelGizmos) {
+    let position = ball.position.as_ivec2();
+    gizmos.circle(position, 3, Pixel::WHITE);
+    gizmos.line(
+        position,
+        (ball.position + ball.velocity * 0.3).as_ivec2(),
+        Pixel::GREEN,
+    );
+    gizmos.point(position, Pixel::RED);
+}
+
+/// Bounds of the area and the quadrant of the ball
+fn draw_bounds_debug(ball: Res<Ball>, mut gizmos: PixelGizmos) {
+    let max = SIZE.size.as_ivec2() - 1;
+    gizmos.rect(
+        IRect::from_corners(IVec2::ZERO, max),
+        Pixel::from([255, 255, 0, 128]),
+    );
+
+    let half = SIZE.size.as_ivec2() / 2;
+    let quadrant = ball.position.as_ivec2() / half;
+    let min = quadrant * half;
+    gizmos.rect(
+        IRect::from_corners(min + 2, min + half - 3),
+        Pixel::from([0, 255, 255, 160]),
+    );
+}
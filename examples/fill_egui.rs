@@ -17,7 +17,7 @@ fn main() {
         .run();
 }
 
-fn update(mut egui_context: EguiContexts, mut pb: QueryPixelBuffer) {
+fn update(mut egui_context: EguiContexts, mut pb: QueryPixelBuffer, mut grid: Local<PixelGrid>) {
     // update the frame
     pb.frame().per_pixel(|_, _| Pixel::random());
 
@@ -25,7 +25,8 @@ fn update(mut egui_context: EguiContexts, mut pb: QueryPixelBuffer) {
     let ctx = egui_context.ctx_mut();
     egui::SidePanel::left("left_panel").show(ctx, |ui| {
         ui.heading("My controls");
-        ui.label("Look! Pixels!!")
+        ui.label("Look! Pixels!!");
+        ui.checkbox(&mut grid.enabled, "Pixel grid");
     });
     egui::CentralPanel::default().show(ctx, |ui| {
         // update Fill component with available size
@@ -35,6 +36,13 @@ fn update(mut egui_context: EguiContexts, mut pb: QueryPixelBuffer) {
         let texture = pb.egui_texture();
 
         // show it
-        ui.image(egui::load::SizedTexture::new(texture.id, texture.size));
+        let response = ui.image(egui::load::SizedTexture::new(texture.id, texture.size));
+
+        // paint the grid on top, the pixels are not modified
+        grid.paint(
+            ui.painter(),
+            response.rect,
+            pb.single().pixel_buffer.size.size,
+        );
     });
 }
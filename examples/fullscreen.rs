@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use bevy_pixel_buffer::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, PixelBufferPlugin))
+        // one buffer pixel per screen pixel, following the window size
+        .add_systems(Startup, PixelBufferBuilder::fullscreen().setup())
+        .add_systems(Update, update)
+        .run();
+}
+
+fn update(mut pb: QueryPixelBuffer, time: Res<Time>) {
+    let t = time.elapsed_secs();
+    pb.frame().per_pixel(|pos, _| {
+        // single pixel lines only look sharp with a pixel per physical pixel
+        let line = (pos.x + pos.y + (t * 60.0) as u32) % 4 == 0;
+        if line {
+            Pixel::WHITE
+        } else {
+            Pixel::BLACK
+        }
+    });
+}
@@ -0,0 +1,106 @@
+//! Compute shader simulating two coupled fields, stored as the layers of a texture array.
+//!
+//! Layer 0 holds the prey and layer 1 the predators, both updated in the same dispatch
+//! with the layer in `global_invocation_id.z`, see `assets/predator_prey.wgsl`. The state is
+//! read from one layered image and written into another, swapped every frame, and the
+//! shader draws both fields into the sprite image through the user bind group.
+
+use bevy::{
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+use bevy_pixel_buffer::{
+    compute_shader::workgroups_for, pixel_buffer::create_layered_image, prelude::*,
+};
+
+const SIZE: UVec2 = UVec2::new(320, 240);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PixelBufferPlugin,
+            ComputeShaderPlugin::<PredatorPreyShader>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, step)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut cs: ResMut<Assets<PredatorPreyShader>>,
+) {
+    // two layers, prey and predators, with a few of each to start
+    let mut state = create_layered_image(SIZE.into(), 2);
+    let layer_len = (SIZE.x * SIZE.y * 4) as usize;
+    for (i, cell) in state.data.chunks_exact_mut(4).enumerate() {
+        let chance = if i * 4 < layer_len { 0.2 } else { 0.01 };
+        if rand::random::<f32>() < chance {
+            cell.copy_from_slice(&[255; 4]);
+        }
+    }
+    let next = images.add(create_layered_image(SIZE.into(), 2));
+    let state = images.add(state);
+
+    let mut pb = PixelBufferBuilder::new()
+        .with_size(PixelBufferSize {
+            size: SIZE,
+            pixel_size: UVec2::new(3, 3),
+        })
+        .spawn(&mut commands, &mut images);
+    let view = pb.image();
+    pb.entity().insert((
+        cs.add(PredatorPreyShader {
+            view,
+            params: Params::default(),
+        }),
+        ComputeBuffers::new(state, next),
+    ));
+}
+
+/// The state written this frame is read in the next one
+fn step(
+    mut pb: Query<(&Handle<PredatorPreyShader>, &mut ComputeBuffers)>,
+    mut cs: ResMut<Assets<PredatorPreyShader>>,
+) {
+    let (shader, mut buffers) = pb.single_mut();
+    buffers.swap();
+    // seeds the random numbers of the shader
+    cs.get_mut(shader).unwrap().params.frame += 1;
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+#[type_path = "example::predator_prey_shader"]
+struct PredatorPreyShader {
+    /// Image of the sprite, the fields are drawn into it
+    #[storage_texture(0, image_format = Rgba8Unorm, access = WriteOnly)]
+    view: Handle<Image>,
+    #[uniform(1)]
+    params: Params,
+}
+
+#[derive(ShaderType, Clone, Debug, Default)]
+struct Params {
+    frame: u32,
+}
+
+impl ComputeShader for PredatorPreyShader {
+    fn shader() -> ShaderRef {
+        "predator_prey.wgsl".into()
+    }
+
+    fn entry_point() -> std::borrow::Cow<'static, str> {
+        "update".into()
+    }
+
+    fn workgroups(texture_size: UVec2) -> UVec2 {
+        workgroups_for(texture_size, UVec2::splat(8))
+    }
+
+    fn layered() -> bool {
+        true
+    }
+}
@@ -0,0 +1,156 @@
+//! Metaballs rendered by a compute shader, with the positions of the balls sent
+//! every frame in a variable length array.
+//!
+//! Click to add a ball, right click to remove all of them.
+
+use bevy::{
+    prelude::*,
+    reflect::TypePath,
+    render::{
+        render_resource::{AsBindGroup, ShaderRef, ShaderType},
+        storage::ShaderStorageBuffer,
+    },
+};
+use bevy_pixel_buffer::prelude::*;
+
+const SIZE: UVec2 = UVec2::new(320, 240);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PixelBufferPlugin,
+            ComputeShaderPlugin::<MetaballsShader>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (edit_balls, move_balls).chain())
+        .run();
+}
+
+/// Balls in the CPU, they are copied into the storage buffer every frame
+#[derive(Resource)]
+struct Balls(ComputePoints<Ball>);
+
+#[derive(ShaderType, Clone, Debug)]
+struct Ball {
+    position: Vec2,
+    velocity: Vec2,
+    radius: f32,
+}
+
+impl Ball {
+    fn random(position: Vec2) -> Self {
+        Self {
+            position,
+            velocity: Vec2::from_angle(rand::random::<f32>() * std::f32::consts::TAU) * 60.0,
+            radius: 10.0 + rand::random::<f32>() * 20.0,
+        }
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut cs: ResMut<Assets<MetaballsShader>>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+) {
+    let balls = ComputePoints::new(
+        (0..8)
+            .map(|_| Ball::random(Vec2::new(rand::random(), rand::random()) * SIZE.as_vec2()))
+            .collect(),
+    );
+
+    PixelBufferBuilder::new()
+        .with_size(PixelBufferSize {
+            size: SIZE,
+            pixel_size: UVec2::new(3, 3),
+        })
+        .spawn(&mut commands, &mut images)
+        .entity()
+        .insert(cs.add(MetaballsShader {
+            balls: buffers.add(ShaderStorageBuffer::from(balls.clone())),
+        }));
+
+    commands.insert_resource(Balls(balls));
+}
+
+/// Adds a ball on click, removes all of them on right click
+fn edit_balls(
+    mut balls: ResMut<Balls>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    pb: Single<&GlobalTransform, With<PixelBuffer>>,
+) {
+    if mouse.just_pressed(MouseButton::Right) {
+        balls.0.clear();
+    }
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let (camera, camera_transform) = *camera;
+    let Some(world) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+    // from world coordinates to pixels, the buffer is centered and y goes down
+    let local = pb
+        .affine()
+        .inverse()
+        .transform_point3(world.extend(0.0))
+        .truncate();
+    let pixel = Vec2::new(local.x, -local.y) / 3.0 + SIZE.as_vec2() / 2.0;
+    balls.0.push(Ball::random(pixel));
+}
+
+/// Moves the balls, bouncing on the edges, and uploads them
+fn move_balls(
+    mut balls: ResMut<Balls>,
+    pb: Single<&Handle<MetaballsShader>>,
+    cs: Res<Assets<MetaballsShader>>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    time: Res<Time>,
+) {
+    let size = SIZE.as_vec2();
+    for ball in balls.0.items_mut() {
+        ball.position += ball.velocity * time.delta_secs();
+        if !(0.0..size.x).contains(&ball.position.x) {
+            ball.velocity.x = -ball.velocity.x;
+        }
+        if !(0.0..size.y).contains(&ball.position.y) {
+            ball.velocity.y = -ball.velocity.y;
+        }
+        ball.position = ball.position.clamp(Vec2::ZERO, size);
+    }
+
+    let shader = cs.get(*pb).unwrap();
+    buffers
+        .get_mut(&shader.balls)
+        .unwrap()
+        .set_data(balls.0.clone());
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Clone, Debug)]
+#[type_path = "example::metaballs_shader"]
+struct MetaballsShader {
+    #[storage(0, read_only)]
+    balls: Handle<ShaderStorageBuffer>,
+}
+
+impl ComputeShader for MetaballsShader {
+    fn shader() -> ShaderRef {
+        "metaballs.wgsl".into()
+    }
+
+    fn entry_point() -> std::borrow::Cow<'static, str> {
+        "update".into()
+    }
+
+    fn workgroups(texture_size: UVec2) -> UVec2 {
+        // round up, the shader checks the bounds
+        (texture_size + 7) / 8
+    }
+}
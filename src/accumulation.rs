@@ -0,0 +1,181 @@
+//! Floating point buffers to accumulate samples before displaying them.
+//!
+//! Pixel buffers are 8 bits per channel, which is not enough precision to add up many
+//! samples, like in a progressive path tracer or any Monte Carlo style render. An
+//! [AccumulationBuffer] keeps a [Vec4] per pixel on the CPU, without range limits, and
+//! is copied into a [Frame] to be displayed.
+//!
+//! # Example
+//! ```
+//! # use bevy::math::{UVec2, Vec4};
+//! # use bevy_pixel_buffer::prelude::*;
+//! # let mut pixels = vec![Pixel::BLACK; 10*10];
+//! # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+//! let mut accumulation = AccumulationBuffer::new(frame.size());
+//! let samples = 16;
+//! for _ in 0..samples {
+//!     accumulation.accumulate((5, 5), Vec4::new(0.5, 0.25, 1.0, 1.0)).unwrap();
+//! }
+//! // average of the samples
+//! accumulation.divide(samples as f32);
+//! accumulation.copy_to_frame(&mut frame).unwrap();
+//! assert_eq!(frame.raw()[55], Pixel::from([128, 64, 255, 255]));
+//! ```
+
+use bevy::math::{UVec2, Vec4};
+
+use crate::{
+    blend::{blend, BlendMode},
+    frame::{Frame, FrameError, FrameResult},
+    pixel::Pixel,
+};
+
+/// Floating point RGBA buffer, see the [module documentation](crate::accumulation).
+///
+/// Channels are in straight alpha, `0.0..=1.0` maps to `0..=255` when copied to a
+/// [Frame] but values out of that range are kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccumulationBuffer {
+    pixels: Vec<Vec4>,
+    size: UVec2,
+}
+
+impl AccumulationBuffer {
+    /// Creates a buffer with all the values set to zero
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            pixels: vec![Vec4::ZERO; size.x as usize * size.y as usize],
+            size,
+        }
+    }
+
+    /// Gets the buffer size
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Access the values directly
+    pub fn raw(&self) -> &[Vec4] {
+        &self.pixels
+    }
+
+    /// Access the values directly mutable
+    pub fn raw_mut(&mut self) -> &mut [Vec4] {
+        &mut self.pixels
+    }
+
+    /// Gets a value, `None` if the location is outside of the buffer
+    pub fn get(&self, location: impl Into<UVec2>) -> Option<Vec4> {
+        let location = location.into();
+        self.index(location).ok().map(|i| self.pixels[i])
+    }
+
+    /// Sets a value
+    pub fn set(&mut self, location: impl Into<UVec2>, value: Vec4) -> FrameResult {
+        let index = self.index(location.into())?;
+        self.pixels[index] = value;
+        Ok(())
+    }
+
+    /// Blends a value over the existing one, see [BlendMode].
+    ///
+    /// The blend is computed in floating point and not clamped, except for the alpha
+    /// that has to be in `0.0..=1.0` for the blend to make sense.
+    pub fn blend(
+        &mut self,
+        location: impl Into<UVec2>,
+        value: Vec4,
+        mode: BlendMode,
+    ) -> FrameResult {
+        let index = self.index(location.into())?;
+        self.pixels[index] = blend(self.pixels[index], value, mode);
+        Ok(())
+    }
+
+    /// Adds a value to the existing one
+    pub fn accumulate(&mut self, location: impl Into<UVec2>, value: Vec4) -> FrameResult {
+        let index = self.index(location.into())?;
+        self.pixels[index] += value;
+        Ok(())
+    }
+
+    /// Divides all the values, to normalize the buffer after accumulating `n` samples.
+    pub fn divide(&mut self, n: f32) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel /= n;
+        }
+    }
+
+    /// Sets all the values to zero, to start accumulating again
+    pub fn clear(&mut self) {
+        self.pixels.fill(Vec4::ZERO);
+    }
+
+    /// Copies the values into a frame, clamping them to the `0.0..=1.0` range.
+    ///
+    /// The frame has to be the same size as the buffer.
+    pub fn copy_to_frame(&self, frame: &mut Frame) -> FrameResult {
+        self.copy_to_frame_with(frame, Pixel::from_vec4_rounded)
+    }
+
+    /// Copies the values into a frame converting them with a function, for example a
+    /// tonemapping operator.
+    ///
+    /// The frame has to be the same size as the buffer.
+    pub fn copy_to_frame_with(&self, frame: &mut Frame, f: impl Fn(Vec4) -> Pixel) -> FrameResult {
+        frame.check_same_size(self.size)?;
+        for (dst, src) in frame.raw_mut().iter_mut().zip(&self.pixels) {
+            *dst = f(*src);
+        }
+        Ok(())
+    }
+
+    fn index(&self, location: UVec2) -> Result<usize, FrameError> {
+        if location.x >= self.size.x || location.y >= self.size.y {
+            return Err(FrameError::LocationOutOfBounds {
+                location,
+                size: self.size,
+            });
+        }
+        Ok(location.x as usize + location.y as usize * self.size.x as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulate_and_normalize() {
+        let size = UVec2::new(2, 1);
+        let mut accumulation = AccumulationBuffer::new(size);
+        for i in 0..4 {
+            let sample = if i % 2 == 0 { Vec4::ONE } else { Vec4::W };
+            accumulation.accumulate((0, 0), sample).unwrap();
+            accumulation.accumulate((1, 0), Vec4::splat(2.0)).unwrap();
+        }
+        assert!(accumulation.accumulate((2, 0), Vec4::ONE).is_err());
+        assert_eq!(
+            accumulation.get((0, 0)),
+            Some(Vec4::new(2.0, 2.0, 2.0, 4.0))
+        );
+
+        accumulation.divide(4.0);
+        assert_eq!(
+            accumulation.get((0, 0)),
+            Some(Vec4::new(0.5, 0.5, 0.5, 1.0))
+        );
+
+        let mut pixels = vec![Pixel::TRANSPARENT; 2];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        accumulation.copy_to_frame(&mut frame).unwrap();
+        // values over 1.0 are clamped
+        assert_eq!(
+            frame.raw(),
+            &[Pixel::from([128, 128, 128, 255]), Pixel::WHITE]
+        );
+
+        accumulation.clear();
+        assert!(accumulation.raw().iter().all(|v| *v == Vec4::ZERO));
+    }
+}
@@ -0,0 +1,455 @@
+//! Blending of pixels and images into a [Frame].
+//!
+//! The blend modes follow the
+//! [W3C compositing specification](https://www.w3.org/TR/compositing-1/): the source is
+//! blended with the backdrop with the [BlendMode] function and then composited over it.
+//! Pixels are in straight (not premultiplied) alpha.
+
+use bevy::{
+    math::{URect, UVec2, Vec3, Vec4},
+    prelude::Image,
+};
+
+use crate::{
+    frame::{Frame, FrameError, FrameResult},
+    pixel::Pixel,
+};
+
+/// How the colors of a source and a backdrop are mixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// The source color, regular alpha compositing
+    #[default]
+    Normal,
+    /// Multiplies the colors, the result is always darker
+    Multiply,
+    /// Inverse of multiplying the inverse colors, the result is always lighter
+    Screen,
+    /// [BlendMode::Multiply] or [BlendMode::Screen] depending on the backdrop
+    Overlay,
+    /// Adds the colors, clamped
+    Add,
+    /// Subtracts the source from the backdrop, clamped
+    Subtract,
+    /// The darkest of each channel
+    Darken,
+    /// The lightest of each channel
+    Lighten,
+    /// Absolute difference of each channel, black where the colors are the same
+    Difference,
+}
+
+impl BlendMode {
+    fn mix(self, backdrop: Vec3, source: Vec3) -> Vec3 {
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => backdrop * source,
+            BlendMode::Screen => backdrop + source - backdrop * source,
+            BlendMode::Overlay => Vec3::select(
+                backdrop.cmple(Vec3::splat(0.5)),
+                2.0 * backdrop * source,
+                1.0 - 2.0 * (1.0 - backdrop) * (1.0 - source),
+            ),
+            BlendMode::Add => (backdrop + source).min(Vec3::ONE),
+            BlendMode::Subtract => (backdrop - source).max(Vec3::ZERO),
+            BlendMode::Darken => backdrop.min(source),
+            BlendMode::Lighten => backdrop.max(source),
+            BlendMode::Difference => (backdrop - source).abs(),
+        }
+    }
+}
+
+impl Pixel {
+    /// Blends a pixel over this one.
+    ///
+    /// `self` is the backdrop and `over` the source. The colors are mixed with the
+    /// [BlendMode] where both pixels are opaque, and composited with the regular "over"
+    /// operator with the alpha of `over`:
+    /// - A transparent `over` leaves the pixel unchanged.
+    /// - An opaque `over` replaces the pixel with the mixed color, and it is fully opaque.
+    /// - Over a transparent backdrop, the result is `over`, the blend mode has no effect.
+    ///
+    /// Both pixels are in straight alpha. This is the same operation as [Frame::blend].
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// let gray = Pixel::from([128, 128, 128, 255]);
+    /// assert_eq!(Pixel::RED.blend(gray, BlendMode::Multiply), Pixel::from([128, 0, 0, 255]));
+    /// assert_eq!(Pixel::TRANSPARENT.blend(gray, BlendMode::Multiply), gray);
+    /// ```
+    pub fn blend(self, over: Pixel, mode: BlendMode) -> Pixel {
+        Pixel::from_vec4_rounded(blend(self.as_vec4(), over.as_vec4(), mode))
+    }
+}
+
+/// Blends a source over a backdrop, both with channels in `0.0..=1.0`.
+pub(crate) fn blend(backdrop: Vec4, source: Vec4, mode: BlendMode) -> Vec4 {
+    let (cb, ab) = (backdrop.truncate(), backdrop.w);
+    let (cs, a_s) = (source.truncate(), source.w);
+
+    let alpha = a_s + ab * (1.0 - a_s);
+    if alpha <= 0.0 {
+        return Vec4::ZERO;
+    }
+
+    let mixed = (1.0 - ab) * cs + ab * mode.mix(cb, cs);
+    let color = a_s * mixed + (1.0 - a_s) * ab * cb;
+    (color / alpha).extend(alpha)
+}
+
+/// Pixels of an image.
+///
+/// # Panics
+/// If the image is not in the [Pixel::FORMAT] format.
+pub(crate) fn image_pixels(image: &Image) -> &[Pixel] {
+    assert_eq!(
+        image.texture_descriptor.format,
+        Pixel::FORMAT,
+        "image format of a frame"
+    );
+    bytemuck::cast_slice(&image.data)
+}
+
+impl<'a> Frame<'a> {
+    /// Checks that an image can be blended into the frame
+    fn check_layer(&self, image: &Image) -> FrameResult {
+        let format = image.texture_descriptor.format;
+        if format != Pixel::FORMAT {
+            return Err(FrameError::FormatMismatch {
+                expected: Pixel::FORMAT,
+                found: format,
+            });
+        }
+        self.check_same_size(image.size())
+    }
+
+    /// Blends an image over the frame.
+    ///
+    /// `opacity` multiplies the alpha of the image. The image has to be the same size as
+    /// the frame and in the [Pixel::FORMAT] format, otherwise [FrameError::SizeMismatch]
+    /// or [FrameError::FormatMismatch] is returned.
+    pub fn blend(&mut self, image: &Image, mode: BlendMode, opacity: f32) -> FrameResult {
+        self.check_layer(image)?;
+        blend_pixels(self.raw_mut(), image_pixels(image), mode, opacity);
+        Ok(())
+    }
+
+    /// Blends another frame over this one, like merging two layers of a compositor.
+    ///
+    /// Same as [Frame::blend] but with the pixels of a frame, for example the
+    /// [snapshot](Frame::snapshot) of another buffer. Both frames have to be the same size,
+    /// otherwise [FrameError::SizeMismatch] is returned.
+    pub fn blend_buffer(&mut self, over: &Frame, mode: BlendMode, opacity: f32) -> FrameResult {
+        self.check_same_size(over.size())?;
+        blend_pixels(self.raw_mut(), over.raw(), mode, opacity);
+        Ok(())
+    }
+
+    /// Replaces the frame with the absolute difference of each channel with an image, to
+    /// see where two images differ, like the outputs of two generators or shaders.
+    ///
+    /// Identical pixels become black. This is [Frame::blend] with [BlendMode::Difference]
+    /// and full opacity, so for opaque images the result is exactly the difference and
+    /// opaque. Translucent pixels are composited as in any other blend. The image has to
+    /// be the same size as the frame and in the [Pixel::FORMAT] format, otherwise
+    /// [FrameError::SizeMismatch] or [FrameError::FormatMismatch] is returned.
+    pub fn difference(&mut self, image: &Image) -> FrameResult {
+        self.blend(image, BlendMode::Difference, 1.0)
+    }
+
+    /// Composites a stack of layers over the frame, from bottom to top.
+    ///
+    /// Each layer is an image, its blend mode and its opacity, see [Frame::blend]. All
+    /// the images have to be the same size as the frame and in the [Pixel::FORMAT]
+    /// format, it is checked before anything is modified.
+    pub fn compose(&mut self, layers: &[(&Image, BlendMode, f32)]) -> FrameResult {
+        for (image, _, _) in layers {
+            self.check_layer(image)?;
+        }
+        for (image, mode, opacity) in layers {
+            blend_pixels(self.raw_mut(), image_pixels(image), *mode, *opacity);
+        }
+        Ok(())
+    }
+
+    /// Blends a color into a region with a different opacity for each pixel, like the
+    /// stroke of a soft brush.
+    ///
+    /// `mask` has the opacity of every pixel of `rect`, row by row, in the `0.0..=1.0`
+    /// range. It multiplies the alpha of the color, which is blended with
+    /// [BlendMode::Normal] as in [Pixel::blend]. The mask has to have the length of the
+    /// area of the rect, otherwise [FrameError::LengthMismatch] is returned. The region is
+    /// clipped to the frame, `rect.max` is exclusive.
+    pub fn blend_masked(&mut self, color: Pixel, mask: &[f32], rect: URect) -> FrameResult {
+        let size = rect.size();
+        let expected = size.x as usize * size.y as usize;
+        if mask.len() != expected {
+            return Err(FrameError::LengthMismatch {
+                expected,
+                found: mask.len(),
+            });
+        }
+
+        let color = color.as_vec4();
+        let clipped = rect.intersect(URect::from_corners(UVec2::ZERO, self.size()));
+        for y in clipped.min.y..clipped.max.y {
+            let mask_row = (y - rect.min.y) as usize * size.x as usize;
+            let row = self.row_mut(y).expect("row inside the frame");
+            for x in clipped.min.x..clipped.max.x {
+                let opacity = mask[mask_row + (x - rect.min.x) as usize].clamp(0.0, 1.0);
+                if opacity <= 0.0 {
+                    continue;
+                }
+                let mut source = color;
+                source.w *= opacity;
+                let dst = &mut row[x as usize];
+                *dst = Pixel::from_vec4_rounded(blend(dst.as_vec4(), source, BlendMode::Normal));
+            }
+        }
+        Ok(())
+    }
+
+    /// Composites another frame over this one, with its top left corner at `dst`.
+    ///
+    /// The source is premultiplied on the fly and composited with the "over" operator,
+    /// then converted back to straight alpha. The color of a translucent source pixel is
+    /// not darkened by its alpha, so antialiased edges do not get a dark halo, even over
+    /// transparent pixels. The source is clipped to the frame.
+    pub fn blit_premultiplied(&mut self, src: &Frame, dst: UVec2) {
+        let size = src.size().min(self.size().saturating_sub(dst));
+        for y in 0..size.y {
+            let src_row = &src.row(y).expect("row inside the source")[..size.x as usize];
+            let dst_row = &mut self.row_mut(dst.y + y).expect("row inside the frame")
+                [dst.x as usize..(dst.x + size.x) as usize];
+            for (dst, src) in dst_row.iter_mut().zip(src_row) {
+                *dst = over_premultiplied(*dst, *src);
+            }
+        }
+    }
+}
+
+/// Source over backdrop, both in straight alpha, computed in premultiplied alpha
+fn over_premultiplied(backdrop: Pixel, source: Pixel) -> Pixel {
+    let (backdrop, source) = (backdrop.as_vec4(), source.as_vec4());
+    let backdrop = (backdrop.truncate() * backdrop.w).extend(backdrop.w);
+    let source = (source.truncate() * source.w).extend(source.w);
+
+    let result = source + backdrop * (1.0 - source.w);
+    if result.w <= 0.0 {
+        return Pixel::TRANSPARENT;
+    }
+    Pixel::from_vec4_rounded((result.truncate() / result.w).extend(result.w))
+}
+
+fn blend_pixels(dst: &mut [Pixel], src: &[Pixel], mode: BlendMode, opacity: f32) {
+    for (dst, src) in dst.iter_mut().zip(src) {
+        let mut source = src.as_vec4();
+        source.w *= opacity;
+        *dst = Pixel::from_vec4_rounded(blend(dst.as_vec4(), source, mode));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{frame::GetFrame, pixel_buffer::create_image};
+    use bevy::render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    fn image(size: UVec2, pixel: Pixel) -> Image {
+        let mut image = create_image(size.into());
+        image.frame().per_pixel(|_, _| pixel);
+        image
+    }
+
+    #[test]
+    fn compose_layers() {
+        let size = UVec2::new(2, 2);
+        let mut base = image(size, Pixel::BLUE);
+        let red = image(size, Pixel::RED);
+        let gray = image(size, Pixel::from([128, 128, 128, 255]));
+
+        base.frame()
+            .compose(&[
+                (&red, BlendMode::Normal, 0.5),
+                (&gray, BlendMode::Multiply, 1.0),
+            ])
+            .unwrap();
+
+        let expected = Pixel::from([64, 0, 64, 255]);
+        assert!(base.frame().raw().iter().all(|p| *p == expected));
+    }
+
+    #[test]
+    fn blend_buffer() {
+        let mut pixels = vec![Pixel::BLUE; 4];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 2));
+        let mut red_pixels = vec![Pixel::from([255, 0, 0, 128]); 4];
+        let red = Frame::from_raw_parts(&mut red_pixels, UVec2::new(2, 2));
+
+        frame.blend_buffer(&red, BlendMode::Normal, 1.0).unwrap();
+        assert_eq!(frame.raw()[3], Pixel::from([128, 0, 127, 255]));
+
+        let mut small_pixels = vec![Pixel::RED; 2];
+        let small = Frame::from_raw_parts(&mut small_pixels, UVec2::new(2, 1));
+        assert!(matches!(
+            frame.blend_buffer(&small, BlendMode::Normal, 1.0),
+            Err(FrameError::SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn blit_premultiplied() {
+        let mut pixels = vec![Pixel::TRANSPARENT, Pixel::WHITE, Pixel::BLUE];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 1));
+        let edge = Pixel::from([255, 255, 255, 64]);
+        let mut src_pixels = vec![edge, edge, Pixel::from([255, 0, 0, 128]), Pixel::RED];
+        let src = Frame::from_raw_parts(&mut src_pixels, UVec2::new(2, 2));
+
+        frame.blit_premultiplied(&src, UVec2::ZERO);
+        // the translucent white edge stays white, over transparent and opaque pixels
+        assert_eq!(frame.raw(), &[edge, Pixel::WHITE, Pixel::BLUE]);
+
+        frame.blit_premultiplied(&src, UVec2::new(2, 0));
+        assert_eq!(frame.raw()[2], Pixel::from([64, 64, 255, 255]));
+    }
+
+    #[test]
+    fn blend_masked() {
+        let mut pixels = vec![Pixel::BLUE; 3 * 2];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 2));
+
+        let rect = URect::new(1, 0, 3, 2);
+        frame
+            .blend_masked(Pixel::RED, &[1.0, 0.5, 0.0, 0.25], rect)
+            .unwrap();
+        assert_eq!(
+            frame.raw(),
+            &[
+                Pixel::BLUE,
+                Pixel::RED,
+                Pixel::from([128, 0, 128, 255]),
+                Pixel::BLUE,
+                Pixel::BLUE,
+                Pixel::from([64, 0, 191, 255]),
+            ]
+        );
+
+        // clipped to the frame
+        frame
+            .blend_masked(Pixel::RED, &[1.0; 4], URect::new(2, 1, 4, 3))
+            .unwrap();
+        assert_eq!(frame.raw()[5], Pixel::RED);
+
+        let result = frame.blend_masked(Pixel::RED, &[1.0; 3], rect);
+        assert!(matches!(
+            result,
+            Err(FrameError::LengthMismatch {
+                expected: 4,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn blend_pixel() {
+        let half_red = Pixel::from([255, 0, 0, 128]);
+        assert_eq!(
+            Pixel::BLUE.blend(Pixel::TRANSPARENT, BlendMode::Screen),
+            Pixel::BLUE
+        );
+        assert_eq!(
+            Pixel::BLUE.blend(half_red, BlendMode::Normal),
+            Pixel::from([128, 0, 127, 255])
+        );
+        assert_eq!(
+            Pixel::BLUE.blend(Pixel::RED, BlendMode::Screen),
+            Pixel::from([255, 0, 255, 255])
+        );
+        assert_eq!(
+            Pixel::TRANSPARENT.blend(half_red, BlendMode::Darken),
+            half_red
+        );
+    }
+
+    #[test]
+    fn difference() {
+        let size = UVec2::new(2, 1);
+        let mut base = image(size, Pixel::from([200, 50, 0, 255]));
+        let other = image(size, Pixel::from([100, 100, 0, 255]));
+
+        base.frame().difference(&other).unwrap();
+        let expected = Pixel::from([100, 50, 0, 255]);
+        assert!(base.frame().raw().iter().all(|p| *p == expected));
+
+        let same = base.clone();
+        base.frame().difference(&same).unwrap();
+        assert!(base.frame().raw().iter().all(|p| *p == Pixel::BLACK));
+
+        let small = image(UVec2::ONE, Pixel::RED);
+        assert!(matches!(
+            base.frame().difference(&small),
+            Err(FrameError::SizeMismatch { .. })
+        ));
+
+        // same size and bytes, in another format
+        let mut srgb = same.clone();
+        srgb.texture_descriptor.format = TextureFormat::Rgba8UnormSrgb;
+        assert!(matches!(
+            base.frame().difference(&srgb),
+            Err(FrameError::FormatMismatch {
+                found: TextureFormat::Rgba8UnormSrgb,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn compose_size_mismatch() {
+        let mut base = image(UVec2::new(2, 2), Pixel::BLUE);
+        let red = image(UVec2::new(2, 2), Pixel::RED);
+        let small = image(UVec2::new(1, 1), Pixel::RED);
+
+        let result = base.frame().compose(&[
+            (&red, BlendMode::Normal, 1.0),
+            (&small, BlendMode::Normal, 1.0),
+        ]);
+        assert!(matches!(result, Err(FrameError::SizeMismatch { .. })));
+        // nothing was modified
+        assert!(base.frame().raw().iter().all(|p| *p == Pixel::BLUE));
+    }
+
+    #[test]
+    fn compose_format_mismatch() {
+        let mut base = image(UVec2::new(2, 2), Pixel::BLUE);
+        let red = image(UVec2::new(2, 2), Pixel::RED);
+        // 2x1 pixels of 8 bytes, the same length as the 2x2 frame
+        let float = Image::new_fill(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0; 8],
+            TextureFormat::Rgba16Float,
+            RenderAssetUsages::all(),
+        );
+
+        let result = base.frame().compose(&[
+            (&red, BlendMode::Normal, 1.0),
+            (&float, BlendMode::Normal, 1.0),
+        ]);
+        assert!(matches!(
+            result,
+            Err(FrameError::FormatMismatch {
+                found: TextureFormat::Rgba16Float,
+                ..
+            })
+        ));
+        assert!(base.frame().raw().iter().all(|p| *p == Pixel::BLUE));
+    }
+}
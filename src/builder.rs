@@ -7,8 +7,10 @@
 
 use crate::{
     bundle::PixelBufferBundle,
-    pixel_buffer::{create_image, Fill, PixelBuffer, PixelBufferSize},
-    prelude::{Frame, FrameEditExtension, GetFrame},
+    pixel::Pixel,
+    pixel_buffer::{create_image, fill_buffer_size, Fill, FillKind, PixelBuffer, PixelBufferSize},
+    prelude::{AddressMode, Frame, FrameEditExtension, GetFrame},
+    query::PixelBuffersReadOnlyItem,
 };
 use bevy::{ecs::system::EntityCommands, prelude::*, sprite::Anchor};
 
@@ -140,6 +142,15 @@ pub struct PixelBufferBuilder {
     pub fill: Fill,
     /// Set up rendering
     pub render: Option<RenderConfig>,
+    /// Initial pixels, row by row. Transparent if [None] or if the length doesn't match
+    /// the size.
+    pub initial_data: Option<Vec<Pixel>>,
+    /// Default edge mode of the frames, see [PixelBuffer::edge_mode]
+    pub edge_mode: AddressMode,
+    /// Color that tints the sprite, see [PixelBufferBuilder::with_tint]
+    pub tint: Option<Color>,
+    /// Draw order of the sprite, see [PixelBufferBuilder::with_layer]
+    pub layer: Option<u32>,
 }
 
 impl Default for PixelBufferBuilder {
@@ -148,6 +159,10 @@ impl Default for PixelBufferBuilder {
             size: Default::default(),
             fill: Default::default(),
             render: Some(RenderConfig::sprite_and_camera()),
+            initial_data: None,
+            edge_mode: AddressMode::default(),
+            tint: None,
+            layer: None,
         }
     }
 }
@@ -159,18 +174,108 @@ impl PixelBufferBuilder {
         Self::default()
     }
 
+    /// Creates a builder for a buffer that always fills the primary window, with one
+    /// buffer pixel per physical pixel of the screen.
+    ///
+    /// This is [Fill::window] with [physical pixels](Fill::with_physical_pixels) and
+    /// [stretch](Fill::with_stretch), a pixel size of `1x1`, and a 2D camera and a sprite
+    /// like [PixelBufferBuilder::new]. If the app already has a camera, use
+    /// `.with_render(RenderConfig::sprite())`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// App::new()
+    ///     .add_plugins((DefaultPlugins, PixelBufferPlugin))
+    ///     .add_systems(Startup, PixelBufferBuilder::fullscreen().setup());
+    /// ```
+    pub fn fullscreen() -> Self {
+        Self::new()
+            .with_size(PixelBufferSize::pixel_size((1, 1)))
+            .with_fill(Fill::window().with_stretch(true).with_physical_pixels(true))
+    }
+
     /// Use a custom size for the buffer. The size can be given in many ways, as it's exemplified in [PixelBufferSize].
     pub fn with_size(mut self, size: impl Into<PixelBufferSize>) -> Self {
         self.size = size.into();
         self
     }
 
+    /// Creates a builder with the size and the pixels of an image.
+    ///
+    /// The image is converted to 8 bit RGBA. Requires the `image` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    ///     let img = image::open("assets/level.png").unwrap();
+    ///     PixelBufferBuilder::from_dynamic_image(img).spawn(&mut commands, &mut images);
+    /// }
+    /// ```
+    #[cfg(feature = "image")]
+    pub fn from_dynamic_image(img: image::DynamicImage) -> Self {
+        let img = img.into_rgba8();
+        let mut builder = Self::new();
+        builder.size.size = UVec2::new(img.width(), img.height());
+        builder.initial_data = Some(bytemuck::cast_slice(img.as_raw()).to_vec());
+        builder
+    }
+
     /// Set the [Fill] mode.
     pub fn with_fill(mut self, fill: impl Into<Fill>) -> Self {
         self.fill = fill.into();
         self
     }
 
+    /// Set the size of the buffer to fill a window, usually the primary window.
+    ///
+    /// The size is the window size minus the [margin](Fill::with_margin), divided by the
+    /// pixel size, truncated to the
+    /// [scaling multiple](Fill::with_scaling_multiple) of the fill, the same as
+    /// [Fill::window] does every frame. So the pixel size and fill have to be set before.
+    ///
+    /// Without a window, for example in a headless app, or if the window is too small
+    /// the size is not changed.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::{prelude::*, window::PrimaryWindow};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// fn full_window_buffer(
+    ///     mut commands: Commands,
+    ///     mut images: ResMut<Assets<Image>>,
+    ///     window: Query<&Window, With<PrimaryWindow>>,
+    /// ) {
+    ///     PixelBufferBuilder::new()
+    ///         .with_size(PixelBufferSize::pixel_size((4, 4)))
+    ///         .fill_window(window.get_single().ok())
+    ///         .spawn(&mut commands, &mut images);
+    /// }
+    /// # bevy::ecs::system::assert_is_system(full_window_buffer);
+    /// ```
+    pub fn with_window_size(mut self, window: Option<&Window>) -> Self {
+        if let Some(window) = window {
+            let area = Vec2::new(window.width(), window.height());
+            let area = self.fill.margin().inner_area(area) * self.fill.scale(window.scale_factor());
+            let size = fill_buffer_size(area, &self.size, &self.fill);
+            if size.cmpgt(UVec2::ZERO).all() {
+                self.size.size = size;
+            }
+        }
+        self
+    }
+
+    /// Same as [PixelBufferBuilder::with_window_size] and also keeps the buffer filling
+    /// the primary window afterwards with [Fill::window], keeping the stretch and
+    /// scaling multiple of the current fill.
+    pub fn fill_window(mut self, window: Option<&Window>) -> Self {
+        self.fill.kind = FillKind::Window;
+        self.with_window_size(window)
+    }
+
     /// Set wether and how to render the pixel buffer using the bevy 2D renderer.
     ///
     /// The type [RenderConfigBuilder] allows for some ergnomics to build the [RenderConfig].
@@ -179,6 +284,50 @@ impl PixelBufferBuilder {
         self
     }
 
+    /// Set the color that tints the sprite, see [Sprite::color].
+    ///
+    /// The sprite color multiplies the pixels when they are displayed, so it can be used
+    /// for global color effects without modifying the pixel data. The default is white,
+    /// which means no tint. To change it later, see
+    /// [PixelBufferTint](crate::query::PixelBufferTint).
+    ///
+    /// The tint replaces the color of the sprite of the [RenderConfig].
+    pub fn with_tint(mut self, color: impl Into<Color>) -> Self {
+        self.tint = Some(color.into());
+        self
+    }
+
+    /// Set the draw order of the sprite when stacking several pixel buffers.
+    ///
+    /// The layer is the Z of the sprite [Transform], sprites with a higher layer are drawn
+    /// on top, so for example a HUD on layer 1 is drawn over a background on layer 0. The
+    /// default 2D camera sees up to a Z of 1000, so the layer should be below that.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    ///     PixelBufferBuilder::new()
+    ///         .with_layer(0)
+    ///         .spawn(&mut commands, &mut images);
+    ///     PixelBufferBuilder::new()
+    ///         .with_layer(1)
+    ///         .with_render(RenderConfig::sprite())
+    ///         .spawn(&mut commands, &mut images);
+    /// }
+    /// ```
+    pub fn with_layer(mut self, layer: u32) -> Self {
+        self.layer = Some(layer);
+        self
+    }
+
+    /// Default edge mode of the frames of the buffer, see [PixelBuffer::edge_mode].
+    pub fn with_edge_mode(mut self, edge_mode: AddressMode) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
     /// Spawns a new entity and inserts a pixel buffer with the builder's configuration to it.
     pub fn spawn<'a>(
         self,
@@ -186,7 +335,7 @@ impl PixelBufferBuilder {
         images: &'a mut Assets<Image>,
     ) -> PixelBufferCommands<'a> {
         let entity = commands.spawn(());
-        create_pixel_buffer(entity, images, self.size, self.fill, self.render)
+        create_pixel_buffer(entity, images, self)
     }
 
     /// Inserts a new pixel buffer with the builder's configuration into an existing entity.
@@ -197,7 +346,7 @@ impl PixelBufferBuilder {
         entity: Entity,
     ) -> PixelBufferCommands<'a> {
         let entity = commands.entity(entity);
-        create_pixel_buffer(entity, images, self.size, self.fill, self.render)
+        create_pixel_buffer(entity, images, self)
     }
 
     /// Returns a system that spawns a pixel buffer with the builder's configuration.
@@ -242,11 +391,31 @@ impl PixelBufferBuilder {
 fn create_pixel_buffer<'a>(
     mut entity: EntityCommands<'a>,
     images: &'a mut Assets<Image>,
-    size: PixelBufferSize,
-    fill: Fill,
-    render: Option<RenderConfig>,
+    builder: PixelBufferBuilder,
 ) -> PixelBufferCommands<'a> {
-    let image = images.add(create_image(size.size.into()));
+    let PixelBufferBuilder {
+        size,
+        fill,
+        render,
+        initial_data,
+        edge_mode,
+        tint,
+        layer,
+    } = builder;
+
+    let mut image = create_image(size.size.into());
+    if let Some(data) = initial_data {
+        let mut frame = Frame::get(&mut image);
+        if frame.raw().len() == data.len() {
+            frame.raw_mut().copy_from_slice(&data);
+        } else {
+            warn!(
+                "Ignoring the initial data of the pixel buffer, its length doesn't match the size"
+            );
+        }
+    }
+    let image = images.add(image);
+    let mut sprite = Sprite::from_image(image.clone());
 
     if let Some(render) = render {
         match render {
@@ -259,11 +428,8 @@ fn create_pixel_buffer<'a>(
                     entity.commands().spawn(Camera2d::default());
                 }
 
-                // Add a sprite with the image as texture
-
-                // this also adds a image_handle, but just replacing the existing one
-                // which is the same handle
-                let sprite_bundle = Sprite {
+                // Customize the sprite with the image as texture
+                sprite = Sprite {
                     custom_size: Some(size.screen_size().as_vec2()),
                     color: sprite_bundle.sprite.color,
                     flip_x: sprite_bundle.sprite.flip_x,
@@ -274,20 +440,31 @@ fn create_pixel_buffer<'a>(
                     image_mode: SpriteImageMode::Auto,
                     image: image.clone(),
                 };
-                entity.insert(sprite_bundle);
             }
         }
     }
+    if let Some(tint) = tint {
+        sprite.color = tint;
+    }
+    if let Some(layer) = layer {
+        entity.insert(Transform::from_xyz(0.0, 0.0, layer as f32));
+    }
 
     entity.insert(PixelBufferBundle {
-        pixel_buffer: PixelBuffer { size, fill },
-        sprite: Sprite::from_image(image.clone()),
+        pixel_buffer: PixelBuffer {
+            size,
+            fill,
+            edge_mode,
+            ..Default::default()
+        },
+        sprite,
     });
 
     PixelBufferCommands {
         images,
         image_handle: image.clone_weak(),
         entity_commands: entity,
+        edge_mode,
     }
 }
 
@@ -327,12 +504,60 @@ pub fn pixel_buffer_setup(
     }
 }
 
+/// Spawns a copy of a pixel buffer, with its own image.
+///
+/// The image of the source is copied, so the new buffer can be edited independently, for
+/// example to duplicate a layer in an editor. The new entity has the same [PixelBuffer]
+/// and [Sprite] configuration, but no other components: a
+/// [compute shader](crate::compute_shader) or a [Transform] of the source have to be
+/// inserted again if needed.
+///
+/// If the image of the source is not loaded, the new image is empty.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_buffer::{builder::duplicate_buffer, prelude::*};
+/// fn duplicate_all(
+///     mut commands: Commands,
+///     mut images: ResMut<Assets<Image>>,
+///     pixel_buffers: Query<PixelBuffers>,
+/// ) {
+///     for source in pixel_buffers.iter() {
+///         duplicate_buffer(&mut commands, &mut images, &source);
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(duplicate_all);
+/// ```
+pub fn duplicate_buffer(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    source: &PixelBuffersReadOnlyItem,
+) -> Entity {
+    let pixel_buffer = *source.pixel_buffer;
+    let image = images
+        .get(&source.sprite.image)
+        .cloned()
+        .unwrap_or_else(|| create_image(pixel_buffer.size.size.into()));
+    let sprite = Sprite {
+        image: images.add(image),
+        ..source.sprite.clone()
+    };
+    commands
+        .spawn(PixelBufferBundle {
+            pixel_buffer,
+            sprite,
+        })
+        .id()
+}
+
 /// Struct returned from creating a pixel buffer with [PixelBufferBuilder]
 /// allowing to work with the new buffer.
 pub struct PixelBufferCommands<'a> {
     images: &'a mut Assets<Image>,
     image_handle: Handle<Image>,
     entity_commands: EntityCommands<'a>,
+    edge_mode: AddressMode,
 }
 
 impl<'a> PixelBufferCommands<'a> {
@@ -365,6 +590,211 @@ impl<'a> PixelBufferCommands<'a> {
 
 impl<'a> GetFrame for PixelBufferCommands<'a> {
     fn frame(&mut self) -> Frame<'_> {
-        Frame::extract(self.images, &self.image_handle)
+        Frame::extract(self.images, &self.image_handle).with_edge_mode(self.edge_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::query::PixelBuffers;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default());
+        app
+    }
+
+    #[test]
+    fn window_size() {
+        let mut window = Window::default();
+        window.resolution.set(200.0, 100.0);
+
+        let builder = PixelBufferBuilder::new()
+            .with_size(PixelBufferSize::pixel_size((2, 2)))
+            .fill_window(Some(&window));
+        assert_eq!(builder.size.size, UVec2::new(100, 50));
+        assert_eq!(builder.fill, Fill::window());
+
+        // headless
+        let builder = PixelBufferBuilder::new()
+            .with_size((10, 10))
+            .fill_window(None);
+        assert_eq!(builder.size.size, UVec2::new(10, 10));
+    }
+
+    #[test]
+    fn fullscreen() {
+        let mut app = test_app();
+        app.add_systems(Startup, PixelBufferBuilder::fullscreen().setup());
+        app.update();
+
+        let pb = app.world_mut().query::<&PixelBuffer>().single(app.world());
+        assert_eq!(pb.size.pixel_size, UVec2::ONE);
+        assert_eq!(pb.fill.kind(), FillKind::Window);
+        assert!(pb.fill.is_stretched());
+        assert!(pb.fill.uses_physical_pixels());
+        let cameras = app
+            .world_mut()
+            .query::<&Camera2d>()
+            .iter(app.world())
+            .count();
+        assert_eq!(cameras, 1);
+
+        // HiDPI
+        let mut window = Window::default();
+        window.resolution.set_scale_factor_override(Some(2.0));
+        window.resolution.set(200.0, 100.0);
+        let builder = PixelBufferBuilder::fullscreen().with_window_size(Some(&window));
+        assert_eq!(builder.size.size, UVec2::new(400, 200));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn from_dynamic_image() {
+        let mut img = image::RgbImage::new(3, 2);
+        img.put_pixel(2, 1, image::Rgb([10, 20, 30]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut png, image::ImageFormat::Png).unwrap();
+        let decoded = image::load_from_memory(png.get_ref()).unwrap();
+
+        let mut app = test_app();
+        app.add_systems(
+            Startup,
+            PixelBufferBuilder::from_dynamic_image(decoded)
+                .with_render(false)
+                .setup(),
+        );
+        app.update();
+
+        let (pb, sprite) = app
+            .world_mut()
+            .query::<(&PixelBuffer, &Sprite)>()
+            .single(app.world());
+        assert_eq!(pb.size.size, UVec2::new(3, 2));
+        let image = sprite.image.clone();
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let frame = Frame::extract(&mut images, &image);
+        assert_eq!(frame.raw()[5], Pixel::from([10, 20, 30, 255]));
+        assert_eq!(frame.raw()[0], Pixel::BLACK);
+    }
+
+    #[test]
+    fn sprite_tint() {
+        let mut app = test_app();
+        let tint = Color::srgb(1.0, 0.5, 0.0);
+
+        app.add_systems(
+            Startup,
+            (
+                PixelBufferBuilder::new()
+                    .with_render(RenderConfig::sprite())
+                    .with_tint(tint)
+                    .setup(),
+                // the order doesn't matter and it works without render
+                PixelBufferBuilder::new()
+                    .with_tint(tint)
+                    .with_render(RenderConfig::sprite())
+                    .setup(),
+                PixelBufferBuilder::new()
+                    .with_tint(tint)
+                    .with_render(false)
+                    .setup(),
+            ),
+        );
+        app.update();
+
+        let colors: Vec<Color> = app
+            .world_mut()
+            .query_filtered::<&Sprite, With<PixelBuffer>>()
+            .iter(app.world())
+            .map(|sprite| sprite.color)
+            .collect();
+        assert_eq!(colors, [tint; 3]);
+    }
+
+    #[test]
+    fn sprite_layers() {
+        let mut app = test_app();
+        app.add_systems(
+            Startup,
+            (
+                PixelBufferBuilder::new().with_layer(0).setup(),
+                PixelBufferBuilder::new()
+                    .with_render(RenderConfig::sprite())
+                    .with_layer(3)
+                    .setup(),
+                PixelBufferBuilder::new()
+                    .with_layer(2)
+                    .with_render(RenderConfig::sprite())
+                    .setup(),
+            ),
+        );
+        app.update();
+
+        let mut layers: Vec<f32> = app
+            .world_mut()
+            .query_filtered::<&Transform, With<PixelBuffer>>()
+            .iter(app.world())
+            .map(|transform| transform.translation.z)
+            .collect();
+        layers.sort_by(f32::total_cmp);
+        assert_eq!(layers, [0.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn duplicate() {
+        let mut app = test_app();
+        app.add_systems(
+            Startup,
+            |mut commands: Commands, mut images: ResMut<Assets<Image>>| {
+                PixelBufferBuilder::new()
+                    .with_size((2, 2))
+                    .with_render(false)
+                    .spawn(&mut commands, &mut images)
+                    .edit_frame(|frame| frame.per_pixel(|_, _| Pixel::RED));
+            },
+        );
+        app.update();
+
+        let source = app
+            .world_mut()
+            .query_filtered::<Entity, With<PixelBuffer>>()
+            .single(app.world());
+        app.world_mut()
+            .run_system_once(
+                move |mut commands: Commands,
+                      mut images: ResMut<Assets<Image>>,
+                      pixel_buffers: Query<PixelBuffers>| {
+                    let source = pixel_buffers.get(source).unwrap();
+                    duplicate_buffer(&mut commands, &mut images, &source)
+                },
+            )
+            .unwrap();
+
+        let mut pixel_buffers = app.world_mut().query::<(&PixelBuffer, &Sprite)>();
+        let [(a, a_sprite), (b, b_sprite)] = pixel_buffers
+            .iter(app.world())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a_sprite.image, b_sprite.image);
+        let (a, b) = (a_sprite.image.clone(), b_sprite.image.clone());
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        assert!(Frame::extract(&mut images, &b)
+            .raw()
+            .iter()
+            .all(|p| *p == Pixel::RED));
+        Frame::extract(&mut images, &b).per_pixel(|_, _| Pixel::BLUE);
+        assert!(Frame::extract(&mut images, &a)
+            .raw()
+            .iter()
+            .all(|p| *p == Pixel::RED));
     }
 }
@@ -3,23 +3,64 @@
 //!
 //! This allows for fast buffer updates with functions that are
 //! relatively expensive to perform, as it is done on the GPU.
-use std::{borrow::Cow, marker::PhantomData};
+//!
+//! # Latency
+//! The shaders are dispatched in the render graph before the cameras, so the frame that is
+//! displayed always shows the dispatch of that same frame, with the data extracted from the
+//! main world at the end of its update. There is no extra frame of latency.
+//!
+//! Changes to the shader asset, like with [update_shader], are used by the dispatch of the
+//! next frame, as its bind group is prepared after the dispatches of a frame are queued.
+//!
+//! A buffer is not dispatched in a frame, and shows its previous contents, while:
+//! - The pipeline of the shader is loading, see [ComputeShaderStatus].
+//! - The shader asset or the image are being prepared, which only takes a frame after
+//!   they are created or modified, like when the buffer is resized.
+//! - It's [Culled], or the plugins are paused with [PixelBufferActive].
+//!
+//! For lockstep or recording, systems can wait for the first dispatch with the
+//! [compute_shader_ready] run condition, and the [PresentAfterCompute] component hides
+//! the buffer in the frames where it could not be dispatched.
+//!
+//! To run CPU logic after the GPU work, read the [ComputeShaderDispatched] event, or use it
+//! as a run condition with [on_event]. The render world runs in parallel with the next
+//! update of the main world, so the event arrives in the main world one frame after the
+//! dispatch, or two if the render world is slower.
+//!
+//! # Attaching and detaching
+//! A compute shader is dispatched for every pixel buffer with a [Handle] to it. The
+//! [ComputeShaderCommands] attach and detach them at runtime, for example to freeze the
+//! result of a GPU effect. The GPU resources of a buffer are released the frame after it
+//! is detached, the shader asset itself is kept while there is a handle to it.
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use bevy::{
     asset::Asset,
+    ecs::system::{EntityCommands, StaticSystemParam, SystemParamItem},
     prelude::*,
     render::{
         render_asset::RenderAssets,
         render_graph::{self, RenderGraph, RenderLabel},
         render_resource::*,
-        renderer::RenderDevice,
+        renderer::{RenderDevice, RenderQueue},
         texture::{FallbackImage, GpuImage},
         Extract, Render, RenderApp, RenderSet,
     },
+    sprite::{queue_sprites, ExtractedSprites},
     utils::{HashMap, HashSet},
 };
 
-use crate::pixel_buffer::PixelBuffer;
+use crate::{
+    pixel_buffer::{PixelBuffer, PixelBufferActive},
+    visibility::Culled,
+};
 
 #[allow(unused)] // doc link
 use crate::pixel_buffer::Fill;
@@ -30,7 +71,7 @@ use crate::pixel_buffer::Fill;
 /// ```no_run
 /// # use bevy::prelude::*;
 /// # use bevy::reflect::{TypePath};
-/// # use bevy_pixel_buffer::compute_shader::ComputeShader;
+/// # use bevy_pixel_buffer::compute_shader::{workgroups_for, ComputeShader};
 /// # use bevy::render::render_resource::{ShaderRef, AsBindGroup};
 ///
 /// #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
@@ -47,7 +88,7 @@ use crate::pixel_buffer::Fill;
 ///     }
 ///
 ///     fn workgroups(texture_size: UVec2) -> UVec2 {
-///         texture_size / 8
+///         workgroups_for(texture_size, UVec2::splat(8))
 ///     }
 /// }
 /// ```
@@ -64,9 +105,15 @@ use crate::pixel_buffer::Fill;
 /// Notice that with a [Fill] configuration that updates the size automatically, this can be a problem if the size
 /// is not a multiple of a desired number, in our example, `8`. In this example, we would have to use
 /// [Fill::with_scaling_multiple] to ensure that the size is a multiple of our workgroup size.
+/// Otherwise, [workgroups_for] rounds up to cover the whole texture, and the shader has to skip
+/// the invocations outside of it.
 ///
 /// # About the bindings in the shader
-/// The bind group 0 is set up with the texture in binding 0. The bind group 1 is the user bind group. The user bind
+/// The bind group 0 is set up with the texture in binding 0, the [ComputeRegion] uniform in binding 1
+/// and the texture to read from in binding 2 with its sampler in binding 4, see [ComputeBuffers].
+/// If [ComputeShader::wants_size] returns `true`, binding 3 is a uniform with the size of the texture.
+/// With [ComputeShader::layered], the textures in bindings 0 and 2 are texture arrays.
+/// The bind group 1 is the user bind group. The user bind
 /// groups is provided by the implementation of the [AsBindGroup] trait, probably derivind it.
 pub trait ComputeShader:
     Asset + AsBindGroup + Send + Sync + Clone + Asset + Default + Sized + 'static
@@ -77,6 +124,54 @@ pub trait ComputeShader:
     fn entry_point() -> Cow<'static, str>;
     /// Number of workgroups.
     fn workgroups(texture_size: UVec2) -> UVec2;
+    /// Shader stages that can access the bind group 0, with the textures and the [ComputeRegion].
+    ///
+    /// Defaults to [ShaderStages::COMPUTE]. Adding other stages allows to share the same
+    /// bind group layout with other pipelines, for example a fragment shader that uses the
+    /// same effect module. Read-write storage textures in a fragment stage need support
+    /// from the device.
+    fn visibility() -> ShaderStages {
+        ShaderStages::COMPUTE
+    }
+    /// If the shader receives the size of the texture in the binding 3 of the bind group 0.
+    ///
+    /// Defaults to `false`. The uniform is kept up to date when the buffer is resized:
+    /// ```wgsl
+    /// struct TextureSize {
+    ///     size: vec2<u32>,
+    ///     // 1.0 / size, to compute UVs
+    ///     inv_size: vec2<f32>,
+    /// }
+    ///
+    /// @group(0) @binding(3)
+    /// var<uniform> texture_size: TextureSize;
+    /// ```
+    fn wants_size() -> bool {
+        false
+    }
+    /// If the textures in bindings 0 and 2 are bound as 2D texture arrays.
+    ///
+    /// Defaults to `false`. With `true` the shader is dispatched once per layer of the
+    /// texture in binding 0, with the layer in the `z` of the invocation id, so the
+    /// workgroup size in `z` has to be 1. Layered images can be created with
+    /// [create_layered_image](crate::pixel_buffer::create_layered_image) and bound with
+    /// [ComputeBuffers], a plain image is a single layer:
+    /// ```wgsl
+    /// @group(0) @binding(0)
+    /// var output: texture_storage_2d_array<rgba8unorm, read_write>;
+    ///
+    /// @group(0) @binding(2)
+    /// var input: texture_2d_array<f32>;
+    ///
+    /// @compute @workgroup_size(8, 8, 1)
+    /// fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+    ///     let other = textureLoad(input, id.xy, 1 - id.z, 0);
+    ///     textureStore(output, id.xy, id.z, other);
+    /// }
+    /// ```
+    fn layered() -> bool {
+        false
+    }
 }
 
 /// Plugin added to register a shader
@@ -94,16 +189,510 @@ impl<S: ComputeShader> Default for ComputeShaderPlugin<S> {
     }
 }
 
+/// Limits the compute shader dispatch of a pixel buffer to a region of the texture.
+///
+/// Without this component the whole texture is dispatched. With it, the number of
+/// workgroups is computed with [ComputeShader::workgroups] from the size of the region
+/// instead of the size of the texture, and the shader receives the region in a uniform
+/// so it can offset the invocation ids:
+/// ```wgsl
+/// struct ComputeRegion {
+///     offset: vec2<u32>,
+///     size: vec2<u32>,
+/// }
+///
+/// @group(0) @binding(1)
+/// var<uniform> region: ComputeRegion;
+///
+/// @compute @workgroup_size(8, 8, 1)
+/// fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+///     if any(id.xy >= region.size) {
+///         return;
+///     }
+///     let location = vec2<i32>(region.offset + id.xy);
+///     // ...
+/// }
+/// ```
+/// Without this component the uniform still exists, with a zero offset and the size of the
+/// texture. The region is clipped to the texture and `max` is exclusive, an empty region
+/// is not dispatched at all.
+///
+/// Texels outside of the region are untouched, as long as the shader does not write
+/// outside of `offset + size`. The workgroups can cover more than the region when its size
+/// is not a multiple of the workgroup size, so the shader has to check the bounds.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut)]
+pub struct ComputeRegion(pub URect);
+
+/// Separate images for the compute shader to read from and write to.
+///
+/// By default the shader reads and writes the image of the pixel buffer sprite. With this
+/// component, the `write` image is bound in binding 0 as a storage texture, and the `read`
+/// image in binding 2 as a sampled texture, with the sampler of the image in binding 4:
+/// ```wgsl
+/// @group(0) @binding(0)
+/// var output: texture_storage_2d<rgba8unorm, read_write>;
+///
+/// @group(0) @binding(2)
+/// var input: texture_2d<f32>;
+///
+/// @group(0) @binding(4)
+/// var input_sampler: sampler;
+/// ```
+/// The input can be read with `textureLoad(input, location, 0)` or sampled with
+/// `textureSampleLevel(input, input_sampler, uv, 0.0)`, the sampler is set with
+/// [Image::sampler]. Without this component, binding 2 is a 1x1 placeholder texture,
+/// because a texture can't be bound both as storage and sampled.
+///
+/// The images are used as they are in the component every frame, so the user decides which
+/// one is the source, for example swapping them every frame with [ComputeBuffers::swap] for
+/// a simulation that needs the previous state. The sprite still displays its own image, set
+/// it to `write` to display the result, see [PixelBuffer::swap_image]. Both images should
+/// have the size of the pixel buffer, the workgroups are computed from the `write` image.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct ComputeBuffers {
+    /// Image bound in binding 2, and its sampler in binding 4
+    pub read: Handle<Image>,
+    /// Image bound in binding 0
+    pub write: Handle<Image>,
+}
+
+impl ComputeBuffers {
+    /// Creates the component
+    pub fn new(read: Handle<Image>, write: Handle<Image>) -> Self {
+        Self { read, write }
+    }
+
+    /// Exchanges the `read` and `write` images
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.read, &mut self.write);
+    }
+}
+
+/// Shows a pixel buffer only in the frames where its compute shader is dispatched.
+///
+/// By default a buffer that can't be dispatched in a frame, because the pipeline is loading
+/// or the image is being prepared, shows its previous contents, see the
+/// [latency](crate::compute_shader#latency). With this component its [Sprite] is hidden
+/// in those frames instead, so the displayed pixels always come from the dispatch of that
+/// same frame. Buffers that are [Culled] or paused with [PixelBufferActive] are not
+/// dispatched on purpose and are still shown.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PresentAfterCompute;
+
+/// [ComputeRegion] as it's passed to the shader
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuComputeRegion {
+    offset: UVec2,
+    size: UVec2,
+}
+
+pub use points::ComputePoints;
+
+// The `ShaderType` derive generates a `check` function for each field of a generic struct
+// that is never used, and the warning can only be allowed for the whole module.
+mod points {
+    #![allow(dead_code)]
+
+    use super::*;
+
+    /// Variable length array of small structs, like lights or points, for a compute shader.
+    ///
+    /// It's passed to the shader as a storage buffer with the number of elements followed by the
+    /// elements. The number of elements is always in sync with the array. Store it in a
+    /// [ShaderStorageBuffer](bevy::render::storage::ShaderStorageBuffer) and use it in the
+    /// user bind group with the `storage` attribute of [AsBindGroup]:
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy::render::{render_resource::{AsBindGroup, ShaderType}, storage::ShaderStorageBuffer};
+    /// # use bevy_pixel_buffer::compute_shader::ComputePoints;
+    /// #[derive(ShaderType, Clone, Debug)]
+    /// struct Light {
+    ///     position: Vec2,
+    ///     radius: f32,
+    /// }
+    ///
+    /// #[derive(Asset, AsBindGroup, TypePath, Clone, Debug)]
+    /// struct LightsShader {
+    ///     #[storage(0, read_only)]
+    ///     lights: Handle<ShaderStorageBuffer>,
+    /// }
+    ///
+    /// fn setup(mut buffers: ResMut<Assets<ShaderStorageBuffer>>) {
+    ///     let lights = ComputePoints::new(vec![Light {
+    ///         position: Vec2::ZERO,
+    ///         radius: 10.0,
+    ///     }]);
+    ///     let shader = LightsShader {
+    ///         lights: buffers.add(ShaderStorageBuffer::from(lights)),
+    ///     };
+    /// }
+    /// # bevy::ecs::system::assert_is_system(setup);
+    /// ```
+    /// To change the elements later, write them again with
+    /// [set_data](bevy::render::storage::ShaderStorageBuffer::set_data).
+    ///
+    /// And in the shader:
+    /// ```wgsl
+    /// struct Light {
+    ///     position: vec2<f32>,
+    ///     radius: f32,
+    /// }
+    ///
+    /// struct Lights {
+    ///     count: u32,
+    ///     items: array<Light>,
+    /// }
+    ///
+    /// @group(1) @binding(0)
+    /// var<storage, read> lights: Lights;
+    /// ```
+    /// See the `metaballs` example.
+    #[derive(ShaderType, Clone, Debug, PartialEq)]
+    pub struct ComputePoints<T: ShaderType + ShaderSize> {
+        pub(super) count: u32,
+        #[size(runtime)]
+        pub(super) items: Vec<T>,
+    }
+}
+
+impl<T: ShaderType + ShaderSize> ComputePoints<T> {
+    /// New array with some elements
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            count: items.len() as u32,
+            items,
+        }
+    }
+
+    /// Adds an element at the end
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        self.count += 1;
+    }
+
+    /// Removes all the elements
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.count = 0;
+    }
+
+    /// Replaces all the elements
+    pub fn set(&mut self, items: Vec<T>) {
+        *self = Self::new(items);
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// If there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The elements
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The elements mutable, they can be modified but not added or removed
+    pub fn items_mut(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+}
+
+impl<T: ShaderType + ShaderSize> Default for ComputePoints<T> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<T: ShaderType + ShaderSize> From<Vec<T>> for ComputePoints<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self::new(items)
+    }
+}
+
+/// Number of workgroups to cover a texture, rounding up.
+///
+/// Dividing the size by the workgroup size truncates, so the last pixels are not processed
+/// when the size is not a multiple of it. This rounds up instead, for
+/// [ComputeShader::workgroups]. The last workgroups can go past the edge of the texture, so
+/// the shader has to check the bounds:
+/// ```wgsl
+/// @compute @workgroup_size(8, 8, 1)
+/// fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+///     if any(id.xy >= textureDimensions(texture)) {
+///         return;
+///     }
+///     // ...
+/// }
+/// ```
+///
+/// # Example
+/// ```
+/// # use bevy::math::UVec2;
+/// # use bevy_pixel_buffer::compute_shader::workgroups_for;
+/// assert_eq!(workgroups_for(UVec2::new(100, 64), UVec2::new(8, 8)), UVec2::new(13, 8));
+/// ```
+pub fn workgroups_for(texture_size: UVec2, workgroup_size: UVec2) -> UVec2 {
+    let workgroup_size = workgroup_size.max(UVec2::ONE);
+    UVec2::new(
+        texture_size.x.div_ceil(workgroup_size.x),
+        texture_size.y.div_ceil(workgroup_size.y),
+    )
+}
+
+/// Status of the compute shader `S` in the render world, available in the main world.
+///
+/// Inserted by the [ComputeShaderPlugin]. The render world updates it while the main world
+/// runs the next update, so it can be one frame behind.
+#[derive(Resource)]
+pub struct ComputeShaderStatus<S: ComputeShader> {
+    ready: Arc<AtomicBool>,
+    dispatches: Arc<AtomicU64>,
+    marker: PhantomData<S>,
+}
+
+impl<S: ComputeShader> ComputeShaderStatus<S> {
+    /// If the pipeline has loaded and the shader has been dispatched at least once.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Total number of dispatches, one per buffer every frame.
+    pub fn dispatches(&self) -> u64 {
+        self.dispatches.load(Ordering::Acquire)
+    }
+}
+
+impl<S: ComputeShader> Default for ComputeShaderStatus<S> {
+    fn default() -> Self {
+        Self {
+            ready: Default::default(),
+            dispatches: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Shares the same status
+impl<S: ComputeShader> Clone for ComputeShaderStatus<S> {
+    fn clone(&self) -> Self {
+        Self {
+            ready: self.ready.clone(),
+            dispatches: self.dispatches.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Event sent in the main world once per frame in which the compute shader `S` was
+/// dispatched, at the start of the frame.
+///
+/// It arrives one frame after the dispatch, see the
+/// [module documentation](crate::compute_shader#latency).
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+/// # use bevy_pixel_buffer::prelude::*;
+/// # #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+/// # struct MyShader {}
+/// # impl ComputeShader for MyShader {
+/// #     fn shader() -> ShaderRef { "my_shader.wgsl".into() }
+/// #     fn entry_point() -> std::borrow::Cow<'static, str> { "update".into() }
+/// #     fn workgroups(texture_size: UVec2) -> UVec2 { texture_size / 8 }
+/// # }
+/// fn count_steps(
+///     mut steps: Local<u64>,
+///     mut dispatched: EventReader<ComputeShaderDispatched<MyShader>>,
+/// ) {
+///     for event in dispatched.read() {
+///         *steps += event.dispatches;
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(count_steps);
+/// ```
+#[derive(Event)]
+pub struct ComputeShaderDispatched<S: ComputeShader> {
+    /// Number of dispatches since the previous event, one per buffer every frame
+    pub dispatches: u64,
+    marker: PhantomData<fn() -> S>,
+}
+
+/// Sends [ComputeShaderDispatched] when the dispatch count of the status increases
+fn send_dispatched<S: ComputeShader>(
+    status: Res<ComputeShaderStatus<S>>,
+    mut last_dispatches: Local<u64>,
+    mut dispatched: EventWriter<ComputeShaderDispatched<S>>,
+) {
+    let dispatches = status.dispatches();
+    if dispatches > *last_dispatches {
+        dispatched.send(ComputeShaderDispatched {
+            dispatches: dispatches - *last_dispatches,
+            marker: PhantomData,
+        });
+        *last_dispatches = dispatches;
+    }
+}
+
+/// Run condition that is `true` once the compute shader `S` has been dispatched,
+/// see [ComputeShaderStatus::is_ready].
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+/// # use bevy_pixel_buffer::prelude::*;
+/// # use bevy_pixel_buffer::compute_shader::compute_shader_ready;
+/// # #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+/// # struct MyShader {}
+/// # impl ComputeShader for MyShader {
+/// #     fn shader() -> ShaderRef { "my_shader.wgsl".into() }
+/// #     fn entry_point() -> std::borrow::Cow<'static, str> { "update".into() }
+/// #     fn workgroups(texture_size: UVec2) -> UVec2 { texture_size / 8 }
+/// # }
+/// fn start_recording(mut commands: Commands, pb: Single<Entity, With<PixelBuffer>>) {
+///     // ...
+/// }
+///
+/// App::new().add_systems(
+///     Update,
+///     start_recording.run_if(compute_shader_ready::<MyShader>.and(run_once)),
+/// );
+/// ```
+pub fn compute_shader_ready<S: ComputeShader>(status: Option<Res<ComputeShaderStatus<S>>>) -> bool {
+    status.is_some_and(|status| status.is_ready())
+}
+
+/// Modifies a compute shader, for example its uniforms, so the change reaches the GPU.
+///
+/// The shader asset is mutated through [Assets::get_mut], which marks it as modified. The
+/// shader is extracted again at the end of the frame and its user bind group recreated,
+/// so every buffer that uses it sees the change in the next dispatch. Mutating a clone of
+/// the shader, or a shader that is not in the assets, does nothing.
+///
+/// Returns `false` if the shader is not in the assets, then `f` is not called. As the
+/// bind group is recreated, only call it when something changed.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+/// # use bevy_pixel_buffer::prelude::*;
+/// # use bevy_pixel_buffer::compute_shader::update_shader;
+/// #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+/// struct MyShader {
+///     #[uniform(0)]
+///     time: f32,
+/// }
+/// # impl ComputeShader for MyShader {
+/// #     fn shader() -> ShaderRef { "my_shader.wgsl".into() }
+/// #     fn entry_point() -> std::borrow::Cow<'static, str> { "update".into() }
+/// #     fn workgroups(texture_size: UVec2) -> UVec2 { texture_size / 8 }
+/// # }
+///
+/// fn update_time(
+///     pb: Single<&Handle<MyShader>>,
+///     mut shaders: ResMut<Assets<MyShader>>,
+///     time: Res<Time>,
+/// ) {
+///     update_shader(&mut shaders, &pb, |shader| shader.time = time.elapsed_secs());
+/// }
+/// # bevy::ecs::system::assert_is_system(update_time);
+/// ```
+pub fn update_shader<S: ComputeShader>(
+    shaders: &mut Assets<S>,
+    handle: &Handle<S>,
+    f: impl FnOnce(&mut S),
+) -> bool {
+    match shaders.get_mut(handle) {
+        Some(shader) => {
+            f(shader);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Attach and detach compute shaders from pixel buffers with [EntityCommands].
+///
+/// The buffers that use a shader can be enumerated with a regular query, like
+/// `Query<Entity, (With<PixelBuffer>, With<Handle<MyShader>>)>`.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+/// # use bevy_pixel_buffer::prelude::*;
+/// # #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+/// # struct MyShader {}
+/// # impl ComputeShader for MyShader {
+/// #     fn shader() -> ShaderRef { "my_shader.wgsl".into() }
+/// #     fn entry_point() -> std::borrow::Cow<'static, str> { "update".into() }
+/// #     fn workgroups(texture_size: UVec2) -> UVec2 { texture_size / 8 }
+/// # }
+/// #[derive(Resource)]
+/// struct Effect(Handle<MyShader>);
+///
+/// fn toggle_effect(
+///     mut commands: Commands,
+///     keys: Res<ButtonInput<KeyCode>>,
+///     effect: Res<Effect>,
+///     pixel_buffer: Single<(Entity, Has<Handle<MyShader>>), With<PixelBuffer>>,
+/// ) {
+///     let (entity, attached) = *pixel_buffer;
+///     if keys.just_pressed(KeyCode::Space) {
+///         let mut entity = commands.entity(entity);
+///         if attached {
+///             entity.detach_compute_shader::<MyShader>();
+///         } else {
+///             entity.attach_compute_shader(effect.0.clone());
+///         }
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(toggle_effect);
+/// ```
+pub trait ComputeShaderCommands {
+    /// Starts dispatching a compute shader for the pixel buffer, replacing the previous
+    /// shader of the same type.
+    fn attach_compute_shader<S: ComputeShader>(&mut self, shader: Handle<S>) -> &mut Self;
+
+    /// Stops dispatching the compute shader `S` for the pixel buffer. The image keeps the
+    /// result of the last dispatch.
+    fn detach_compute_shader<S: ComputeShader>(&mut self) -> &mut Self;
+}
+
+impl ComputeShaderCommands for EntityCommands<'_> {
+    fn attach_compute_shader<S: ComputeShader>(&mut self, shader: Handle<S>) -> &mut Self {
+        self.insert(shader)
+    }
+
+    fn detach_compute_shader<S: ComputeShader>(&mut self) -> &mut Self {
+        self.remove::<Handle<S>>()
+    }
+}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
 struct UserCs;
 
 impl<S: ComputeShader> Plugin for ComputeShaderPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.init_asset::<S>();
+        let status = ComputeShaderStatus::<S>::default();
+        app.init_asset::<S>()
+            .insert_resource(status.clone())
+            .add_event::<ComputeShaderDispatched<S>>()
+            .add_systems(First, send_dispatched::<S>);
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
+                .insert_resource(status)
                 .init_resource::<ExtractedShaders<S>>()
+                .init_resource::<ExtractedBuffers<S>>()
                 .init_resource::<PreparedShaders<S>>()
                 .init_resource::<PreparedImages<S>>()
                 .add_systems(ExtractSchedule, cs_extract::<S>)
@@ -111,7 +700,15 @@ impl<S: ComputeShader> Plugin for ComputeShaderPlugin<S> {
                     Render,
                     (prepare_images::<S>, prepare_shaders::<S>).in_set(RenderSet::Prepare),
                 )
-                .add_systems(Render, cs_queue_bind_group::<S>.in_set(RenderSet::Queue));
+                .add_systems(
+                    Render,
+                    (
+                        cs_queue_bind_group::<S>,
+                        hide_undispatched::<S>.before(queue_sprites),
+                    )
+                        .chain()
+                        .in_set(RenderSet::Queue),
+                );
             let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
             render_graph.add_node(UserCs, ComputeShaderNode::<S>::default());
             render_graph.add_node_edge(UserCs, bevy::render::graph::CameraDriverLabel);
@@ -147,19 +744,8 @@ impl<S: ComputeShader> FromWorld for ComputeShaderPipeline<S> {
         };
         let entry_point = S::entry_point();
 
-        let texture_bind_group_layout = device.create_bind_group_layout(
-            None,
-            &[BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::COMPUTE,
-                ty: BindingType::StorageTexture {
-                    access: StorageTextureAccess::ReadWrite,
-                    format: TextureFormat::Rgba8Unorm,
-                    view_dimension: TextureViewDimension::D2,
-                },
-                count: None,
-            }],
-        );
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(None, &texture_bind_group_layout_entries::<S>());
 
         let user_bind_group_layout = S::bind_group_layout(device);
 
@@ -188,6 +774,89 @@ impl<S: ComputeShader> FromWorld for ComputeShaderPipeline<S> {
     }
 }
 
+/// Texture size as it's passed to the shader, see [ComputeShader::wants_size]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuTextureSize {
+    size: UVec2,
+    inv_size: Vec2,
+}
+
+impl GpuTextureSize {
+    fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            inv_size: 1.0 / size.as_vec2(),
+        }
+    }
+}
+
+/// Entries of the bind group 0 layout
+fn texture_bind_group_layout_entries<S: ComputeShader>() -> Vec<BindGroupLayoutEntry> {
+    let visibility = S::visibility();
+    let view_dimension = texture_view_dimension::<S>();
+    let mut entries = vec![
+        BindGroupLayoutEntry {
+            binding: 0,
+            visibility,
+            ty: BindingType::StorageTexture {
+                access: StorageTextureAccess::ReadWrite,
+                format: TextureFormat::Rgba8Unorm,
+                view_dimension,
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: 1,
+            visibility,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: BufferSize::new(size_of::<GpuComputeRegion>() as u64),
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: 2,
+            visibility,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension,
+                multisampled: false,
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: 4,
+            visibility,
+            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+            count: None,
+        },
+    ];
+    if S::wants_size() {
+        entries.push(BindGroupLayoutEntry {
+            binding: 3,
+            visibility,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: BufferSize::new(size_of::<GpuTextureSize>() as u64),
+            },
+            count: None,
+        });
+    }
+    entries
+}
+
+/// View dimension of the textures in bindings 0 and 2
+fn texture_view_dimension<S: ComputeShader>() -> TextureViewDimension {
+    if S::layered() {
+        TextureViewDimension::D2Array
+    } else {
+        TextureViewDimension::D2
+    }
+}
+
 #[derive(Resource)]
 struct InvalidatedImages<S: ComputeShader> {
     invalid: HashSet<AssetId<Image>>,
@@ -218,28 +887,102 @@ impl<S: ComputeShader> Default for ExtractedShaders<S> {
     }
 }
 
+/// Pixel buffer that uses the shader `S`
+struct ExtractedBuffer<S: ComputeShader> {
+    /// Main world entity
+    entity: Entity,
+    /// If it has [PresentAfterCompute]
+    present_after_compute: bool,
+    write: AssetId<Image>,
+    /// [None] without [ComputeBuffers]
+    read: Option<AssetId<Image>>,
+    shader: AssetId<S>,
+    region: Option<URect>,
+}
+
+impl<S: ComputeShader> ExtractedBuffer<S> {
+    /// Key of the prepared bind group 0
+    fn images(&self) -> ImagesKey {
+        (self.write, self.read)
+    }
+
+    /// Views of the images as they are now, [None] if they are not prepared
+    fn views(&self, images: &RenderAssets<GpuImage>) -> Option<BoundViews> {
+        let write = images.get(self.write)?;
+        let read = match self.read {
+            Some(read) => Some(images.get(read)?),
+            None => None,
+        };
+        Some(bound_views(write, read))
+    }
+}
+
+/// Pixel buffers that use the shader `S`, extracted every frame.
+///
+/// This is a resource instead of render world entities so buffers that are
+/// despawned, or that no longer have the shader, stop being tracked right away.
+#[derive(Resource)]
+struct ExtractedBuffers<S: ComputeShader> {
+    buffers: Vec<ExtractedBuffer<S>>,
+}
+
+impl<S: ComputeShader> Default for ExtractedBuffers<S> {
+    fn default() -> Self {
+        Self {
+            buffers: Default::default(),
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn cs_extract<S: ComputeShader>(
     mut commands: Commands,
     mut previous_len: Local<usize>,
-    buffers: Extract<Query<(Entity, &Sprite, &Handle<S>), With<PixelBuffer>>>,
+    buffers: Extract<
+        Query<
+            (
+                Entity,
+                &Sprite,
+                &Handle<S>,
+                Option<&ComputeRegion>,
+                Option<&ComputeBuffers>,
+                Has<PresentAfterCompute>,
+            ),
+            (With<PixelBuffer>, Without<Culled>),
+        >,
+    >,
     mut shader_events: Extract<EventReader<AssetEvent<S>>>,
     shader_assets: Extract<Res<Assets<S>>>,
     mut image_events: Extract<EventReader<AssetEvent<Image>>>,
+    active: Extract<Option<Res<PixelBufferActive>>>,
 ) {
     let mut buffer_images = HashSet::with_capacity(*previous_len);
+    let active = active.as_deref().is_none_or(|active| active.0);
 
-    // Extract the entities to apply shaders
-    let mut values = Vec::with_capacity(*previous_len);
-    for (entity, image_handle, shader_handle) in buffers.iter() {
-        values.push((
+    // Extract the buffers to apply shaders, none if paused
+    let mut extracted_buffers = Vec::with_capacity(*previous_len);
+    for (entity, sprite, shader_handle, region, compute_buffers, present_after_compute) in
+        buffers.iter().filter(|_| active)
+    {
+        let (write, read) = match compute_buffers {
+            Some(compute_buffers) => (compute_buffers.write.id(), Some(compute_buffers.read.id())),
+            None => (sprite.image.id(), None),
+        };
+        extracted_buffers.push(ExtractedBuffer {
             entity,
-            (image_handle.clone_weak(), shader_handle.clone_weak()),
-        ));
-        buffer_images.insert(image_handle.id());
+            present_after_compute,
+            write,
+            read,
+            shader: shader_handle.id(),
+            region: region.map(|region| region.0),
+        });
+        buffer_images.insert(write);
+        buffer_images.extend(read);
     }
-    *previous_len = values.len();
-    commands.insert_or_spawn_batch(values);
+    *previous_len = extracted_buffers.len();
+    commands.insert_resource(ExtractedBuffers {
+        buffers: extracted_buffers,
+    });
 
     // Update the shader cache
     let mut changed = HashSet::default();
@@ -291,60 +1034,186 @@ fn cs_extract<S: ComputeShader>(
 
 struct PreparedImage<S> {
     texture_bind_group: BindGroup,
+    region_buffer: Buffer,
+    /// Only with [ComputeShader::wants_size], the bind group keeps it alive
+    _size_buffer: Option<Buffer>,
+    region: GpuComputeRegion,
     marker: PhantomData<S>,
+    /// Size of the write texture that is bound
     size: UVec2,
+    /// Array layers of the write texture, 1 if the shader is not [layered](ComputeShader::layered)
+    layers: u32,
+    /// Write and read views that are bound, see [bound_views]
+    views: BoundViews,
 }
 
+/// Write and read texture views, [None] for the fallback read texture
+type BoundViews = (TextureViewId, Option<TextureViewId>);
+
+/// Views of the images as they are now in the render world.
+///
+/// A resized image gets a new texture, but the [AssetEvent] that invalidates the bind
+/// group can arrive before the [GpuImage] is prepared again, so comparing the views
+/// catches bind groups that still point to the old texture.
+fn bound_views(write: &GpuImage, read: Option<&GpuImage>) -> BoundViews {
+    (
+        write.texture_view.id(),
+        read.map(|read| read.texture_view.id()),
+    )
+}
+
+/// Region uniform clipped to the size of the texture, so the workgroups always match
+/// the texture that is bound
+fn clipped_region(region: Option<URect>, size: UVec2) -> GpuComputeRegion {
+    let full = URect::from_corners(UVec2::ZERO, size);
+    let region = region.map_or(full, |region| region.intersect(full));
+    GpuComputeRegion {
+        offset: region.min,
+        size: region.size(),
+    }
+}
+
+/// Write and read images of a buffer
+type ImagesKey = (AssetId<Image>, Option<AssetId<Image>>);
+
+/// Prepared bind groups 0 by the write and read images
 #[derive(Resource, Default, Deref, DerefMut)]
-struct PreparedImages<S>(HashMap<AssetId<Image>, PreparedImage<S>>);
+struct PreparedImages<S>(HashMap<ImagesKey, PreparedImage<S>>);
 
+#[allow(clippy::too_many_arguments)]
 fn prepare_images<S: ComputeShader>(
     mut previous_len: Local<usize>,
-    buffers: Query<&Sprite, With<Handle<S>>>,
+    buffers: Res<ExtractedBuffers<S>>,
     render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
     pipeline: Res<ComputeShaderPipeline<S>>,
     images: Res<RenderAssets<GpuImage>>,
+    fallback_image: Res<FallbackImage>,
     invalid_images: Res<InvalidatedImages<S>>,
     mut prepared_images: ResMut<PreparedImages<S>>,
 ) {
     // remove invalid prepared images
-    prepared_images.retain(|id, _| !invalid_images.invalid.contains(id));
+    prepared_images.retain(|(write, read), _| {
+        !invalid_images.invalid.contains(write)
+            && !read.is_some_and(|read| invalid_images.invalid.contains(&read))
+    });
     let mut buffer_images = HashSet::with_capacity(*previous_len);
     // iterate over all the buffers
-    for image_handle in buffers.iter() {
-        let image_handle_id = image_handle.id();
-
-        buffer_images.insert(image_handle_id);
-
-        // if the image is not prepared, do it
-        if !prepared_images.contains_key(&image_handle_id) {
-            if let Some(view) = images.get(image_handle_id) {
-                let texture_bind_group = render_device.create_bind_group(
-                    None,
-                    &pipeline.texture_bind_group_layout,
-                    &[BindGroupEntry {
-                        binding: 0,
-                        resource: BindingResource::TextureView(&view.texture_view),
-                    }],
-                );
+    for buffer in buffers.buffers.iter() {
+        let key = buffer.images();
+        buffer_images.insert(key);
 
-                prepared_images.insert(
-                    image_handle_id,
-                    PreparedImage {
-                        texture_bind_group,
-                        size: view.size,
-                        marker: PhantomData::<S>,
-                    },
-                );
+        let read = match buffer.read {
+            Some(read) => images.get(read).map(Some),
+            None => Some(None),
+        };
+        let (Some(view), Some(read)) = (images.get(buffer.write), read) else {
+            continue;
+        };
+        let views = bound_views(view, read);
+        let read = read.unwrap_or(if S::layered() {
+            &fallback_image.d2_array
+        } else {
+            &fallback_image.d2
+        });
+
+        // if the images are not prepared, or the textures changed since, do it
+        if prepared_images
+            .get(&key)
+            .is_none_or(|prepared| prepared.views != views)
+        {
+            let region = clipped_region(None, view.size);
+            let region_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::bytes_of(&region),
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            });
+
+            // the image is prepared again when it's resized, the size never changes
+            let size_buffer = S::wants_size().then(|| {
+                render_device.create_buffer_with_data(&BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::bytes_of(&GpuTextureSize::new(view.size)),
+                    usage: BufferUsages::UNIFORM,
+                })
+            });
+
+            // the views of the images depend on their descriptor, arrays need their own
+            let (write_view, read_view, layers) = if S::layered() {
+                let array_view = |image: &GpuImage| {
+                    image.texture.create_view(&TextureViewDescriptor {
+                        dimension: Some(TextureViewDimension::D2Array),
+                        ..Default::default()
+                    })
+                };
+                (
+                    array_view(view),
+                    array_view(read),
+                    view.texture.depth_or_array_layers(),
+                )
+            } else {
+                (view.texture_view.clone(), read.texture_view.clone(), 1)
+            };
+
+            let mut entries = vec![
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&write_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: region_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(&read_view),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::Sampler(&read.sampler),
+                },
+            ];
+            if let Some(size_buffer) = &size_buffer {
+                entries.push(BindGroupEntry {
+                    binding: 3,
+                    resource: size_buffer.as_entire_binding(),
+                });
+            }
+            let texture_bind_group = render_device.create_bind_group(
+                None,
+                &pipeline.texture_bind_group_layout,
+                &entries,
+            );
+
+            prepared_images.insert(
+                key,
+                PreparedImage {
+                    texture_bind_group,
+                    region_buffer,
+                    _size_buffer: size_buffer,
+                    region,
+                    size: view.size,
+                    layers,
+                    views,
+                    marker: PhantomData::<S>,
+                },
+            );
+        }
+
+        // update the region uniform, the bind group uses the same buffer
+        if let Some(prepared) = prepared_images.get_mut(&key) {
+            let region = clipped_region(buffer.region, prepared.size);
+            if prepared.region != region {
+                prepared.region = region;
+                render_queue.write_buffer(&prepared.region_buffer, 0, bytemuck::bytes_of(&region));
             }
         }
     }
     *previous_len = buffer_images.len();
 
-    // remove untracked images
-    if prepared_images.len() != buffer_images.len() {
-        prepared_images.retain(|h, _| buffer_images.contains(h));
-    }
+    // remove untracked images, comparing the lengths is not enough because an
+    // image that could not be prepared yet would hide a stale one
+    prepared_images.retain(|h, _| buffer_images.contains(h));
 }
 
 struct PreparedShader<S> {
@@ -372,13 +1241,12 @@ fn prepare_shaders<S: ComputeShader>(
     mut extracted_assets: ResMut<ExtractedShaders<S>>,
     mut render_materials: ResMut<PreparedShaders<S>>,
     render_device: Res<RenderDevice>,
-    images: Res<RenderAssets<GpuImage>>,
-    fallback_image: Res<FallbackImage>,
     pipeline: Res<ComputeShaderPipeline<S>>,
+    mut param: StaticSystemParam<<S as AsBindGroup>::Param>,
 ) {
     let mut queued_assets = std::mem::take(&mut prepare_next_frame.assets);
     for (handle_id, shader) in queued_assets.drain(..) {
-        match prepare_shader(&shader, &render_device, &images, &fallback_image, &pipeline) {
+        match prepare_shader(&shader, &render_device, &mut param, &pipeline) {
             Ok(prepared_asset) => {
                 render_materials.insert(handle_id, prepared_asset);
             }
@@ -393,10 +1261,11 @@ fn prepare_shaders<S: ComputeShader>(
 
     for removed in std::mem::take(&mut extracted_assets.removed) {
         render_materials.remove(&removed);
+        prepare_next_frame.assets.retain(|(id, _)| *id != removed);
     }
 
     for (handle_id, shader) in std::mem::take(&mut extracted_assets.extracted) {
-        match prepare_shader(&shader, &render_device, &images, &fallback_image, &pipeline) {
+        match prepare_shader(&shader, &render_device, &mut param, &pipeline) {
             Ok(prepared_asset) => {
                 render_materials.insert(handle_id, prepared_asset);
             }
@@ -411,18 +1280,12 @@ fn prepare_shaders<S: ComputeShader>(
 }
 
 fn prepare_shader<S: ComputeShader>(
-    shaader: &S,
+    shader: &S,
     render_device: &RenderDevice,
-    images: &RenderAssets<GpuImage>,
-    fallback_image: &FallbackImage,
+    param: &mut SystemParamItem<<S as AsBindGroup>::Param>,
     pipeline: &ComputeShaderPipeline<S>,
 ) -> Result<PreparedShader<S>, AsBindGroupError> {
-    let prepared = shaader.as_bind_group(
-        &pipeline.user_bind_group_layout,
-        render_device,
-        images,
-        // fallback_image,
-    )?;
+    let prepared = shader.as_bind_group(&pipeline.user_bind_group_layout, render_device, param)?;
     Ok(PreparedShader {
         user_bind_group: prepared.bind_group,
         marker: PhantomData,
@@ -432,28 +1295,41 @@ fn prepare_shader<S: ComputeShader>(
 #[derive(Resource)]
 struct ComputeShaderQueue<S: ComputeShader>(Vec<ComputeShaderInfo>, PhantomData<S>);
 struct ComputeShaderInfo {
+    /// Main world entity of the buffer
+    entity: Entity,
+    /// Views bound in the texture bind group
+    views: BoundViews,
     texture_bind_group: BindGroup,
     user_bind_group: BindGroup,
     workgroups: UVec2,
+    /// Workgroups in `z`
+    layers: u32,
 }
 
 fn cs_queue_bind_group<S: ComputeShader>(
     mut commands: Commands,
-    buffers: Query<(&Sprite, &Handle<S>)>,
+    buffers: Res<ExtractedBuffers<S>>,
     prepared_shaders: Res<PreparedShaders<S>>,
     prepared_images: Res<PreparedImages<S>>,
     mut previous_len: Local<usize>,
 ) {
     let mut shaders = Vec::with_capacity(*previous_len);
-    for (image_handle, shader_handle) in buffers.iter() {
+    for buffer in buffers.buffers.iter() {
         if let (Some(prepared_image), Some(prepared_shader)) = (
-            prepared_images.get(&image_handle.id()),
-            prepared_shaders.get(&shader_handle.id()),
+            prepared_images.get(&buffer.images()),
+            prepared_shaders.get(&buffer.shader),
         ) {
+            let region_size = prepared_image.region.size;
+            if region_size.cmpeq(UVec2::ZERO).any() {
+                continue;
+            }
             shaders.push(ComputeShaderInfo {
+                entity: buffer.entity,
+                views: prepared_image.views,
                 texture_bind_group: prepared_image.texture_bind_group.clone(),
                 user_bind_group: prepared_shader.user_bind_group.clone(),
-                workgroups: S::workgroups(prepared_image.size),
+                workgroups: S::workgroups(region_size),
+                layers: prepared_image.layers,
             });
         }
     }
@@ -461,6 +1337,52 @@ fn cs_queue_bind_group<S: ComputeShader>(
     commands.insert_resource(ComputeShaderQueue::<S>(shaders, Default::default()));
 }
 
+/// Hides the sprites of the [PresentAfterCompute] buffers that are not dispatched in this
+/// frame into the texture that is displayed
+fn hide_undispatched<S: ComputeShader>(
+    buffers: Res<ExtractedBuffers<S>>,
+    shader_queue: Res<ComputeShaderQueue<S>>,
+    images: Res<RenderAssets<GpuImage>>,
+    pipeline: Res<ComputeShaderPipeline<S>>,
+    pipeline_cache: Res<PipelineCache>,
+    extracted_sprites: Option<ResMut<ExtractedSprites>>,
+) {
+    let Some(mut extracted_sprites) = extracted_sprites else {
+        return;
+    };
+    // the pipeline is only in the cache once its creation started
+    let loaded = pipeline_cache
+        .pipelines()
+        .nth(pipeline.pipeline_id.id())
+        .is_some_and(|cached| matches!(cached.state, CachedPipelineState::Ok(_)));
+    // a bind group prepared before a resize still points to the old texture
+    let dispatched = |buffer: &ExtractedBuffer<S>| {
+        let views = buffer.views(&images);
+        shader_queue.0.iter().any(|info| {
+            info.entity == buffer.entity
+                && can_dispatch(info.workgroups)
+                && Some(info.views) == views
+        })
+    };
+    let hidden: HashSet<Entity> = buffers
+        .buffers
+        .iter()
+        .filter(|buffer| buffer.present_after_compute && !(loaded && dispatched(buffer)))
+        .map(|buffer| buffer.entity)
+        .collect();
+    if !hidden.is_empty() {
+        extracted_sprites
+            .sprites
+            .retain(|(_, main_entity), _| !hidden.contains(&main_entity.id()));
+    }
+}
+
+/// If a dispatch with these workgroups would run the shader at all, a buffer resized to
+/// less than a workgroup can have 0 workgroups in one dimension
+fn can_dispatch(workgroups: UVec2) -> bool {
+    workgroups.cmpgt(UVec2::ZERO).all()
+}
+
 struct ComputeShaderNode<S: ComputeShader> {
     state: State,
     marker: PhantomData<S>,
@@ -512,8 +1434,17 @@ impl<S: ComputeShader> render_graph::Node for ComputeShaderNode<S> {
             .begin_compute_pass(&ComputePassDescriptor::default());
 
         let shader_queue = world.resource::<ComputeShaderQueue<S>>();
+        let status = world.resource::<ComputeShaderStatus<S>>();
 
         for shader in shader_queue.0.iter() {
+            if !can_dispatch(shader.workgroups) {
+                debug!(
+                    "Skipping the dispatch of {}, the workgroup count is {}",
+                    std::any::type_name::<S>(),
+                    shader.workgroups
+                );
+                continue;
+            }
             // index 0 is texture
             pass.set_bind_group(0, &shader.texture_bind_group, &[]);
             // index 1 is user bind group
@@ -524,7 +1455,9 @@ impl<S: ComputeShader> render_graph::Node for ComputeShaderNode<S> {
             if let Some(update_pipeline) = pipeline_cache.get_compute_pipeline(pipeline.pipeline_id)
             {
                 pass.set_pipeline(update_pipeline);
-                pass.dispatch_workgroups(shader.workgroups.x, shader.workgroups.y, 1);
+                pass.dispatch_workgroups(shader.workgroups.x, shader.workgroups.y, shader.layers);
+                status.dispatches.fetch_add(1, Ordering::AcqRel);
+                status.ready.store(true, Ordering::Release);
             } else {
                 error!("Could not retrieve compute shader pipeline from pipeline cache even after checking the state is not Loading.")
             }
@@ -533,3 +1466,880 @@ impl<S: ComputeShader> render_graph::Node for ComputeShaderNode<S> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_buffer::{create_image, PixelBufferSize};
+    use bevy::{ecs::system::RunSystemOnce, render::MainWorld, window::ExitCondition};
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+    struct TestShader {}
+
+    impl ComputeShader for TestShader {
+        fn shader() -> ShaderRef {
+            "test.wgsl".into()
+        }
+
+        fn entry_point() -> Cow<'static, str> {
+            "update".into()
+        }
+
+        fn workgroups(texture_size: UVec2) -> UVec2 {
+            texture_size / 8
+        }
+    }
+
+    /// Shader with a uniform, to modify it
+    #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+    struct UniformShader {
+        #[uniform(0)]
+        time: f32,
+    }
+
+    impl ComputeShader for UniformShader {
+        fn shader() -> ShaderRef {
+            "uniform.wgsl".into()
+        }
+
+        fn entry_point() -> Cow<'static, str> {
+            "update".into()
+        }
+
+        fn workgroups(texture_size: UVec2) -> UVec2 {
+            texture_size / 8
+        }
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+    struct SharedShader {}
+
+    impl ComputeShader for SharedShader {
+        fn shader() -> ShaderRef {
+            "shared.wgsl".into()
+        }
+
+        fn entry_point() -> Cow<'static, str> {
+            "update".into()
+        }
+
+        fn workgroups(texture_size: UVec2) -> UVec2 {
+            texture_size / 8
+        }
+
+        fn visibility() -> ShaderStages {
+            ShaderStages::COMPUTE | ShaderStages::FRAGMENT
+        }
+
+        fn wants_size() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn status_is_shared() {
+        let mut world = World::new();
+        assert!(!world
+            .run_system_once(compute_shader_ready::<TestShader>)
+            .unwrap());
+
+        let status = ComputeShaderStatus::<TestShader>::default();
+        world.insert_resource(status.clone());
+        assert!(!world
+            .run_system_once(compute_shader_ready::<TestShader>)
+            .unwrap());
+
+        // as the render world does
+        status.dispatches.fetch_add(1, Ordering::AcqRel);
+        status.ready.store(true, Ordering::Release);
+        assert!(world
+            .run_system_once(compute_shader_ready::<TestShader>)
+            .unwrap());
+        assert_eq!(
+            world
+                .resource::<ComputeShaderStatus<TestShader>>()
+                .dispatches(),
+            1
+        );
+    }
+
+    #[test]
+    fn dispatched_event() {
+        let status = ComputeShaderStatus::<TestShader>::default();
+        let mut app = App::new();
+        app.insert_resource(status.clone())
+            .add_event::<ComputeShaderDispatched<TestShader>>()
+            .add_systems(First, send_dispatched::<TestShader>);
+
+        let mut frame = |dispatches: u64| {
+            // as the render world does, one per buffer
+            status.dispatches.fetch_add(dispatches, Ordering::AcqRel);
+            app.update();
+            app.world_mut()
+                .resource_mut::<Events<ComputeShaderDispatched<TestShader>>>()
+                .drain()
+                .map(|event| event.dispatches)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(frame(1), vec![1]);
+        assert_eq!(frame(0), vec![]);
+        assert_eq!(frame(2), vec![2]);
+        assert_eq!(frame(1), vec![1]);
+    }
+
+    #[test]
+    fn region_follows_resize() {
+        let region = Some(URect::new(8, 0, 64, 64));
+        // before and after the buffer is resized
+        for size in [UVec2::new(64, 64), UVec2::new(32, 16)] {
+            let clipped = clipped_region(region, size);
+            assert!((clipped.offset + clipped.size).cmple(size).all());
+            assert_eq!(
+                TestShader::workgroups(clipped.size),
+                (size - UVec2::new(8, 0)) / 8
+            );
+        }
+        assert_eq!(
+            clipped_region(None, UVec2::new(32, 16)),
+            GpuComputeRegion {
+                offset: UVec2::ZERO,
+                size: UVec2::new(32, 16)
+            }
+        );
+        // a region outside of the smaller texture is empty and not dispatched
+        let outside = clipped_region(Some(URect::new(40, 0, 64, 64)), UVec2::new(32, 16));
+        assert_eq!(outside.size.x, 0);
+    }
+
+    #[test]
+    fn skip_zero_workgroups() {
+        // smaller than a workgroup of `TestShader` horizontally
+        let workgroups = TestShader::workgroups(UVec2::new(4, 64));
+        assert_eq!(workgroups, UVec2::new(0, 8));
+        assert!(!can_dispatch(workgroups));
+        assert!(!can_dispatch(UVec2::ZERO));
+        assert!(can_dispatch(TestShader::workgroups(UVec2::new(8, 64))));
+    }
+
+    #[test]
+    fn workgroups_cover_texture() {
+        let wg = UVec2::new(8, 4);
+        assert_eq!(workgroups_for(UVec2::new(64, 64), wg), UVec2::new(8, 16));
+        assert_eq!(workgroups_for(UVec2::new(65, 61), wg), UVec2::new(9, 16));
+        assert_eq!(workgroups_for(UVec2::new(1, 1), wg), UVec2::ONE);
+        assert_eq!(workgroups_for(UVec2::ZERO, wg), UVec2::ZERO);
+        for x in 1..40 {
+            let groups = workgroups_for(UVec2::new(x, x), wg);
+            assert!((groups * wg).cmpge(UVec2::new(x, x)).all());
+            assert!(((groups - 1) * wg).cmplt(UVec2::new(x, x)).all());
+        }
+    }
+
+    #[test]
+    fn texture_size() {
+        let size = GpuTextureSize::new(UVec2::new(4, 8));
+        assert_eq!(size.inv_size, Vec2::new(0.25, 0.125));
+        assert_eq!(size_of::<GpuTextureSize>(), 16);
+    }
+
+    #[test]
+    fn points_layout() {
+        let mut points = ComputePoints::default();
+        points.push(Vec2::new(1.0, 2.0));
+        points.push(Vec2::new(3.0, 4.0));
+        assert_eq!(points.len(), 2);
+
+        let mut buffer = encase::StorageBuffer::new(Vec::new());
+        buffer.write(&points).unwrap();
+        let bytes = buffer.into_inner();
+        // count, padding to the alignment of the array and the items
+        assert_eq!(bytemuck::pod_read_unaligned::<u32>(&bytes[0..4]), 2);
+        assert_eq!(
+            bytemuck::cast_slice::<u8, f32>(&bytes[8..24]),
+            &[1.0, 2.0, 3.0, 4.0]
+        );
+
+        points.clear();
+        let mut buffer = encase::StorageBuffer::new(Vec::new());
+        buffer.write(&points).unwrap();
+        assert_eq!(
+            bytemuck::pod_read_unaligned::<u32>(&buffer.into_inner()[0..4]),
+            0
+        );
+    }
+
+    #[test]
+    fn layout_visibility() {
+        let entries = texture_bind_group_layout_entries::<TestShader>();
+        assert_eq!(entries.len(), 4);
+        for entry in entries {
+            assert_eq!(entry.visibility, ShaderStages::COMPUTE);
+        }
+        let entries = texture_bind_group_layout_entries::<SharedShader>();
+        // with the size uniform
+        assert!(entries.iter().any(|entry| entry.binding == 3));
+        for entry in entries {
+            assert_eq!(
+                entry.visibility,
+                ShaderStages::COMPUTE | ShaderStages::FRAGMENT
+            );
+        }
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+    struct LayeredShader {}
+
+    impl ComputeShader for LayeredShader {
+        fn shader() -> ShaderRef {
+            "layered.wgsl".into()
+        }
+
+        fn entry_point() -> Cow<'static, str> {
+            "update".into()
+        }
+
+        fn workgroups(texture_size: UVec2) -> UVec2 {
+            texture_size / 8
+        }
+
+        fn layered() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn layout_layered() {
+        let dimensions = |entries: Vec<BindGroupLayoutEntry>| {
+            entries
+                .into_iter()
+                .filter_map(|entry| match entry.ty {
+                    BindingType::StorageTexture { view_dimension, .. }
+                    | BindingType::Texture { view_dimension, .. } => Some(view_dimension),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            dimensions(texture_bind_group_layout_entries::<TestShader>()),
+            vec![TextureViewDimension::D2; 2]
+        );
+        assert_eq!(
+            dimensions(texture_bind_group_layout_entries::<LayeredShader>()),
+            vec![TextureViewDimension::D2Array; 2]
+        );
+    }
+
+    fn main_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<Assets<TestShader>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Events<AssetEvent<TestShader>>>();
+        world
+    }
+
+    fn extract(main_world: &mut World, render_world: &mut World) {
+        extract_shader::<TestShader>(main_world, render_world);
+    }
+
+    fn extract_shader<S: ComputeShader>(main_world: &mut World, render_world: &mut World) {
+        let mut extract_world = render_world.resource_mut::<MainWorld>();
+        std::mem::swap(main_world, &mut extract_world);
+        render_world.run_system_once(cs_extract::<S>).unwrap();
+        let mut extract_world = render_world.resource_mut::<MainWorld>();
+        std::mem::swap(main_world, &mut extract_world);
+    }
+
+    #[test]
+    fn despawned_buffers_are_not_tracked() {
+        let mut main_world = main_world();
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+
+        let shader = main_world
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let buffers: Vec<_> = (0..3)
+            .map(|_| {
+                let image = main_world
+                    .resource_mut::<Assets<Image>>()
+                    .add(create_image(UVec2::new(8, 8).into()));
+                main_world
+                    .spawn((
+                        PixelBuffer {
+                            size: PixelBufferSize::size((8, 8)),
+                            fill: Fill::none(),
+                            ..Default::default()
+                        },
+                        Sprite::from_image(image),
+                        shader.clone(),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert_eq!(extracted.buffers.len(), 3);
+
+        for entity in buffers {
+            main_world.despawn(entity);
+        }
+
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert!(extracted.buffers.is_empty());
+        // nothing is left behind in the render world
+        assert_eq!(render_world.entities().len(), 0);
+    }
+
+    /// Shader of [DispatchShader], that writes white to the texture
+    const DISPATCH_SHADER: Handle<Shader> = Handle::weak_from_u128(0x5a1f_3c2e_9d47_4b60);
+
+    /// Shader that compiles, to run the node
+    #[derive(Asset, AsBindGroup, TypePath, Clone, Debug, Default)]
+    struct DispatchShader {}
+
+    impl ComputeShader for DispatchShader {
+        fn shader() -> ShaderRef {
+            DISPATCH_SHADER.into()
+        }
+
+        fn entry_point() -> Cow<'static, str> {
+            "update".into()
+        }
+
+        fn workgroups(texture_size: UVec2) -> UVec2 {
+            texture_size / 8
+        }
+    }
+
+    /// App with the renderer and no window, the tests that use it need a GPU adapter
+    fn render_app<S: ComputeShader>() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .disable::<bevy::winit::WinitPlugin>()
+                .disable::<bevy::log::LogPlugin>(),
+            ComputeShaderPlugin::<S>::default(),
+        ));
+        app.world_mut().resource_mut::<Assets<Shader>>().insert(
+            &DISPATCH_SHADER,
+            Shader::from_wgsl(
+                r"
+                @group(0) @binding(0) var texture: texture_storage_2d<rgba8unorm, read_write>;
+
+                @compute @workgroup_size(8, 8, 1)
+                fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+                    textureStore(texture, vec2<i32>(id.xy), vec4<f32>(1.0));
+                }
+                ",
+                file!(),
+            ),
+        );
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn despawned_buffers_are_not_prepared() {
+        let mut app = render_app::<TestShader>();
+        let shader = app
+            .world_mut()
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let buffers: Vec<_> = (0..3)
+            .map(|_| {
+                let image = app
+                    .world_mut()
+                    .resource_mut::<Assets<Image>>()
+                    .add(create_image(UVec2::new(8, 8).into()));
+                app.world_mut()
+                    .spawn((
+                        PixelBuffer {
+                            size: PixelBufferSize::size((8, 8)),
+                            fill: Fill::none(),
+                            ..Default::default()
+                        },
+                        Sprite::from_image(image),
+                        shader.clone(),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        app.update();
+        let render_world = app.sub_app(RenderApp).world();
+        assert_eq!(
+            render_world.resource::<PreparedImages<TestShader>>().len(),
+            3
+        );
+        assert_eq!(
+            render_world.resource::<PreparedShaders<TestShader>>().len(),
+            1
+        );
+
+        for entity in buffers {
+            app.world_mut().despawn(entity);
+        }
+        drop(shader);
+        app.update();
+        app.update();
+        let render_world = app.sub_app(RenderApp).world();
+        assert!(render_world
+            .resource::<PreparedImages<TestShader>>()
+            .is_empty());
+        assert!(render_world
+            .resource::<PreparedShaders<TestShader>>()
+            .is_empty());
+    }
+
+    #[test]
+    fn attach_and_detach() {
+        let mut main_world = main_world();
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+
+        let shader = main_world
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let image = main_world
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(8, 8).into()));
+        let entity = main_world
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((8, 8)),
+                    ..Default::default()
+                },
+                Sprite::from_image(image),
+            ))
+            .id();
+
+        for _ in 0..3 {
+            main_world
+                .commands()
+                .entity(entity)
+                .attach_compute_shader(shader.clone());
+            main_world.flush();
+            extract(&mut main_world, &mut render_world);
+            let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+            assert_eq!(extracted.buffers.len(), 1);
+
+            main_world
+                .commands()
+                .entity(entity)
+                .detach_compute_shader::<TestShader>();
+            main_world.flush();
+            assert!(!main_world.entity(entity).contains::<Handle<TestShader>>());
+            extract(&mut main_world, &mut render_world);
+            let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+            assert!(extracted.buffers.is_empty());
+        }
+        // the shader asset is still alive to be attached again
+        assert!(main_world
+            .resource::<Assets<TestShader>>()
+            .contains(&shader));
+        assert_eq!(render_world.entities().len(), 0);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn attach_and_detach_prepared() {
+        let mut app = render_app::<TestShader>();
+        let shader = app
+            .world_mut()
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(8, 8).into()));
+        let entity = app
+            .world_mut()
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((8, 8)),
+                    ..Default::default()
+                },
+                Sprite::from_image(image),
+            ))
+            .id();
+
+        for _ in 0..3 {
+            app.world_mut()
+                .commands()
+                .entity(entity)
+                .attach_compute_shader(shader.clone());
+            // queued a frame after it's prepared, the queue set runs before the prepare set
+            app.update();
+            app.update();
+            let render_world = app.sub_app(RenderApp).world();
+            assert_eq!(
+                render_world.resource::<PreparedImages<TestShader>>().len(),
+                1
+            );
+            assert_eq!(
+                render_world
+                    .resource::<ComputeShaderQueue<TestShader>>()
+                    .0
+                    .len(),
+                1
+            );
+
+            app.world_mut()
+                .commands()
+                .entity(entity)
+                .detach_compute_shader::<TestShader>();
+            app.update();
+            let render_world = app.sub_app(RenderApp).world();
+            assert!(render_world
+                .resource::<PreparedImages<TestShader>>()
+                .is_empty());
+            assert!(render_world
+                .resource::<ComputeShaderQueue<TestShader>>()
+                .0
+                .is_empty());
+            // the shader is kept prepared to be attached again
+            assert!(render_world
+                .resource::<PreparedShaders<TestShader>>()
+                .contains_key(&shader.id()));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn present_after_compute() {
+        let mut app = render_app::<DispatchShader>();
+        let shader = app
+            .world_mut()
+            .resource_mut::<Assets<DispatchShader>>()
+            .add(DispatchShader::default());
+        // sprites are only extracted when a camera sees them
+        let mut target = create_image(UVec2::new(16, 16).into());
+        target.texture_descriptor.format = TextureFormat::Rgba8UnormSrgb;
+        target.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::RENDER_ATTACHMENT;
+        let target = app.world_mut().resource_mut::<Assets<Image>>().add(target);
+        app.world_mut().spawn((
+            Camera2d,
+            Camera {
+                target: bevy::render::camera::RenderTarget::Image(target),
+                ..default()
+            },
+        ));
+        let [strict, relaxed] = [true, false].map(|present_after_compute| {
+            let image = app
+                .world_mut()
+                .resource_mut::<Assets<Image>>()
+                .add(create_image(UVec2::new(16, 16).into()));
+            let mut entity = app.world_mut().spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((16, 16)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image.clone()),
+                shader.clone(),
+            ));
+            if present_after_compute {
+                entity.insert(PresentAfterCompute);
+            }
+            (entity.id(), image)
+        });
+
+        let shown = |app: &App, entity: Entity| {
+            app.sub_app(RenderApp)
+                .world()
+                .resource::<ExtractedSprites>()
+                .sprites
+                .keys()
+                .any(|(_, main_entity)| main_entity.id() == entity)
+        };
+        app.update();
+        assert!(!shown(&app, strict.0));
+        assert!(shown(&app, relaxed.0));
+
+        for _ in 0..500 {
+            app.update();
+            if shown(&app, strict.0) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(shown(&app, strict.0));
+        assert!(app
+            .world()
+            .resource::<ComputeShaderStatus<DispatchShader>>()
+            .is_ready());
+
+        // the frame of the resize dispatches into the old texture
+        for (_, image) in [&strict, &relaxed] {
+            app.world_mut()
+                .resource_mut::<Assets<Image>>()
+                .get_mut(image)
+                .unwrap()
+                .resize(Extent3d {
+                    width: 32,
+                    height: 8,
+                    depth_or_array_layers: 1,
+                });
+        }
+        app.update();
+        assert!(!shown(&app, strict.0));
+        assert!(shown(&app, relaxed.0));
+        app.update();
+        assert!(shown(&app, strict.0));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn zero_workgroups_are_not_dispatched() {
+        let mut app = render_app::<DispatchShader>();
+        let shader = app
+            .world_mut()
+            .resource_mut::<Assets<DispatchShader>>()
+            .add(DispatchShader::default());
+        let spawn_buffer = |app: &mut App, size: UVec2| {
+            let image = app
+                .world_mut()
+                .resource_mut::<Assets<Image>>()
+                .add(create_image(size.into()));
+            app.world_mut().spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size(size),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image),
+                shader.clone(),
+            ));
+        };
+        // smaller than a workgroup horizontally
+        spawn_buffer(&mut app, UVec2::new(4, 64));
+
+        let pipeline_ready = |app: &App| {
+            let render_world = app.sub_app(RenderApp).world();
+            let pipeline = render_world.resource::<ComputeShaderPipeline<DispatchShader>>();
+            matches!(
+                render_world
+                    .resource::<PipelineCache>()
+                    .get_compute_pipeline_state(pipeline.pipeline_id),
+                CachedPipelineState::Ok(_)
+            )
+        };
+        for _ in 0..500 {
+            app.update();
+            if pipeline_ready(&app) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(pipeline_ready(&app));
+
+        // the node is running, but has nothing to dispatch
+        app.update();
+        app.update();
+        let status = app
+            .world()
+            .resource::<ComputeShaderStatus<DispatchShader>>();
+        assert_eq!(status.dispatches(), 0);
+        assert!(!status.is_ready());
+
+        spawn_buffer(&mut app, UVec2::new(8, 64));
+        app.update();
+        app.update();
+        let status = app
+            .world()
+            .resource::<ComputeShaderStatus<DispatchShader>>();
+        assert!(status.dispatches() > 0);
+        assert!(status.is_ready());
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn resize_between_passes() {
+        let mut app = render_app::<TestShader>();
+        let shader = app
+            .world_mut()
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(16, 16).into()));
+        app.world_mut().spawn((
+            PixelBuffer {
+                size: PixelBufferSize::size((16, 16)),
+                fill: Fill::none(),
+                ..Default::default()
+            },
+            Sprite::from_image(image.clone()),
+            shader.clone(),
+        ));
+
+        let dispatch = |app: &App| {
+            let render_world = app.sub_app(RenderApp).world();
+            let queue = render_world.resource::<ComputeShaderQueue<TestShader>>();
+            let prepared = render_world.resource::<PreparedImages<TestShader>>();
+            let prepared_size = prepared.values().next().map(|prepared| prepared.size);
+            (queue.0.first().map(|info| info.workgroups), prepared_size)
+        };
+        app.update();
+        app.update();
+        assert_eq!(
+            dispatch(&app),
+            (Some(UVec2::new(2, 2)), Some(UVec2::new(16, 16)))
+        );
+
+        app.world_mut()
+            .resource_mut::<Assets<Image>>()
+            .get_mut(&image)
+            .unwrap()
+            .resize(Extent3d {
+                width: 32,
+                height: 8,
+                depth_or_array_layers: 1,
+            });
+        app.update();
+        app.update();
+        assert_eq!(
+            dispatch(&app),
+            (Some(UVec2::new(4, 1)), Some(UVec2::new(32, 8)))
+        );
+    }
+
+    #[test]
+    fn region_is_extracted() {
+        let mut main_world = main_world();
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+
+        let shader = main_world
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let image = main_world
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(8, 8).into()));
+        let region = URect::new(2, 2, 6, 4);
+        let entity = main_world
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((8, 8)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image),
+                shader.clone(),
+                ComputeRegion(region),
+            ))
+            .id();
+
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert_eq!(extracted.buffers[0].region, Some(region));
+
+        main_world.entity_mut(entity).remove::<ComputeRegion>();
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert_eq!(extracted.buffers[0].region, None);
+    }
+
+    #[test]
+    fn updated_shader_is_extracted() {
+        let mut main_world = main_world();
+        main_world.init_resource::<Assets<UniformShader>>();
+        main_world.init_resource::<Events<AssetEvent<UniformShader>>>();
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        // only the events of this frame, as the extract system reader is new every time
+        let flush_events = |world: &mut World| {
+            world
+                .resource_mut::<Events<AssetEvent<UniformShader>>>()
+                .clear();
+            world
+                .run_system_once(Assets::<UniformShader>::asset_events)
+                .unwrap();
+        };
+
+        let shader = main_world
+            .resource_mut::<Assets<UniformShader>>()
+            .add(UniformShader::default());
+        flush_events(&mut main_world);
+        extract_shader::<UniformShader>(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedShaders<UniformShader>>();
+        assert_eq!(extracted.extracted.len(), 1);
+
+        // nothing changed
+        flush_events(&mut main_world);
+        extract_shader::<UniformShader>(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedShaders<UniformShader>>();
+        assert!(extracted.extracted.is_empty());
+
+        let mut shaders = main_world.resource_mut::<Assets<UniformShader>>();
+        assert!(update_shader(&mut shaders, &shader, |shader| shader.time = 2.0));
+        flush_events(&mut main_world);
+        extract_shader::<UniformShader>(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedShaders<UniformShader>>();
+        assert_eq!(extracted.extracted.len(), 1);
+        assert_eq!(extracted.extracted[0].0, shader.id());
+        assert_eq!(extracted.extracted[0].1.time, 2.0);
+
+        let mut shaders = main_world.resource_mut::<Assets<UniformShader>>();
+        assert!(!update_shader(&mut shaders, &Handle::default(), |_| {
+            unreachable!()
+        }));
+    }
+
+    #[test]
+    fn compute_buffers_binding_order() {
+        let mut main_world = main_world();
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+
+        let shader = main_world
+            .resource_mut::<Assets<TestShader>>()
+            .add(TestShader {});
+        let [sprite_image, a, b] = [(); 3].map(|_| {
+            main_world
+                .resource_mut::<Assets<Image>>()
+                .add(create_image(UVec2::new(8, 8).into()))
+        });
+        let entity = main_world
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((8, 8)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(sprite_image.clone()),
+                shader.clone(),
+            ))
+            .id();
+
+        // without the component, the sprite image is written
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert_eq!(extracted.buffers[0].images(), (sprite_image.id(), None));
+
+        main_world
+            .entity_mut(entity)
+            .insert(ComputeBuffers::new(a.clone(), b.clone()));
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert_eq!(extracted.buffers[0].images(), (b.id(), Some(a.id())));
+
+        main_world.get_mut::<ComputeBuffers>(entity).unwrap().swap();
+        extract(&mut main_world, &mut render_world);
+        let extracted = render_world.resource::<ExtractedBuffers<TestShader>>();
+        assert_eq!(extracted.buffers[0].images(), (a.id(), Some(b.id())));
+    }
+}
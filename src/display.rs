@@ -0,0 +1,178 @@
+//! How pixel buffers are shown on screen.
+//!
+//! The pixels of a buffer live in an [Image], and a display component shows it. The
+//! [PixelBufferPlugin](crate::pixel_buffer::PixelBufferPlugin) supports [Sprite], the
+//! display created by the [builder](crate::builder) and the [bundles](crate::bundle). The
+//! `egui` integration shows the image of the sprite.
+//!
+//! Other presentations, like a mesh material or a render target, can implement
+//! [PixelBufferDisplay] for their component and add a [PixelBufferDisplayPlugin] for it.
+//! The plugin resizes the image when the
+//! [PixelBuffer](crate::pixel_buffer::PixelBuffer) size changes, for example with a
+//! [Fill](crate::pixel_buffer::Fill), and keeps the display size in sync with it.
+//!
+//! The rest of the crate, such as [compute shaders](crate::compute_shader) and the
+//! [queries](crate::query), finds the image of a buffer through its [Sprite]. To use them
+//! with a custom display, also insert a [Sprite] with the same image, hidden with
+//! [Visibility::Hidden], and set
+//! [auto_display_size](crate::pixel_buffer::PixelBuffer::auto_display_size) to `false`
+//! so the sprite is not resized.
+//!
+//! # Example
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::{display::{PixelBufferDisplay, PixelBufferDisplayPlugin}, prelude::*};
+//! #[derive(Component)]
+//! struct Billboard {
+//!     image: Handle<Image>,
+//!     size: Option<Vec2>,
+//! }
+//!
+//! impl PixelBufferDisplay for Billboard {
+//!     fn image(&self) -> &Handle<Image> {
+//!         &self.image
+//!     }
+//!
+//!     fn display_size(&self) -> Option<Vec2> {
+//!         self.size
+//!     }
+//!
+//!     fn set_display_size(&mut self, size: Vec2) {
+//!         self.size = Some(size);
+//!     }
+//! }
+//!
+//! App::new().add_plugins((
+//!     DefaultPlugins,
+//!     PixelBufferPlugin,
+//!     PixelBufferDisplayPlugin::<Billboard>::default(),
+//! ));
+//! ```
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::pixel_buffer::{display_size, fill, pixel_buffer_active, resize};
+
+/// Component that shows the image of a pixel buffer.
+///
+/// See the [module documentation](crate::display).
+pub trait PixelBufferDisplay: Component {
+    /// Image with the pixels of the buffer
+    fn image(&self) -> &Handle<Image>;
+
+    /// Size the image is shown at, in logical pixels, [None] if it's the image size
+    fn display_size(&self) -> Option<Vec2>;
+
+    /// Changes the size the image is shown at, in logical pixels.
+    ///
+    /// It's the [screen size](crate::pixel_buffer::PixelBufferSize::screen_size), or the
+    /// fill area with [Fill::with_stretch](crate::pixel_buffer::Fill::with_stretch). It's
+    /// only called when the size changes.
+    fn set_display_size(&mut self, size: Vec2);
+}
+
+impl PixelBufferDisplay for Sprite {
+    fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    fn display_size(&self) -> Option<Vec2> {
+        self.custom_size
+    }
+
+    fn set_display_size(&mut self, size: Vec2) {
+        self.custom_size = Some(size);
+    }
+}
+
+/// Plugin that resizes the image of the pixel buffers shown with `D` and keeps their
+/// display size in sync.
+///
+/// Not needed for [Sprite], it's part of the
+/// [PixelBufferPlugin](crate::pixel_buffer::PixelBufferPlugin).
+pub struct PixelBufferDisplayPlugin<D: PixelBufferDisplay>(PhantomData<D>);
+
+impl<D: PixelBufferDisplay> Default for PixelBufferDisplayPlugin<D> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<D: PixelBufferDisplay> Plugin for PixelBufferDisplayPlugin<D> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (resize::<D>, display_size::<D>)
+                .after(fill)
+                .run_if(pixel_buffer_active),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_buffer::{create_image, Fill, PixelBuffer, PixelBufferSize};
+
+    #[derive(Component)]
+    struct TestDisplay {
+        image: Handle<Image>,
+        size: Option<Vec2>,
+        changes: u32,
+    }
+
+    impl PixelBufferDisplay for TestDisplay {
+        fn image(&self) -> &Handle<Image> {
+            &self.image
+        }
+
+        fn display_size(&self) -> Option<Vec2> {
+            self.size
+        }
+
+        fn set_display_size(&mut self, size: Vec2) {
+            self.size = Some(size);
+            self.changes += 1;
+        }
+    }
+
+    #[test]
+    fn custom_display() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_plugins(PixelBufferDisplayPlugin::<TestDisplay>::default())
+            .add_systems(PreUpdate, fill);
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(2, 2).into()));
+        let entity = app
+            .world_mut()
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::pixel_size((2, 2)),
+                    fill: Fill::custom((20.0, 10.0)),
+                    ..Default::default()
+                },
+                TestDisplay {
+                    image: image.clone(),
+                    size: None,
+                    changes: 0,
+                },
+            ))
+            .id();
+
+        app.update();
+        app.update();
+        let display = app.world().get::<TestDisplay>(entity).unwrap();
+        assert_eq!(display.size, Some(Vec2::new(20.0, 10.0)));
+        assert_eq!(display.changes, 1);
+        let images = app.world().resource::<Assets<Image>>();
+        assert_eq!(images.get(&image).unwrap().size(), UVec2::new(10, 5));
+    }
+}
@@ -22,6 +22,93 @@ pub struct EguiTexture {
     pub size: egui::Vec2,
 }
 
+/// Grid lines between the pixels of a pixel buffer shown in egui, like in pixel editors.
+///
+/// The grid is painted on top of the egui image, the pixels of the buffer are not
+/// modified. It can be stored anywhere, for example as a component of the pixel buffer
+/// or in a [Local].
+///
+/// # Example
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_egui::{egui, EguiContexts};
+/// # use bevy_pixel_buffer::prelude::*;
+/// fn ui(mut egui_context: EguiContexts, pb: QueryPixelBuffer, mut grid: Local<PixelGrid>) {
+///     egui::CentralPanel::default().show(egui_context.ctx_mut(), |ui| {
+///         ui.checkbox(&mut grid.enabled, "Grid");
+///         let texture = pb.egui_texture();
+///         let response = ui.image(egui::load::SizedTexture::new(texture.id, texture.size));
+///         grid.paint(ui.painter(), response.rect, pb.single().pixel_buffer.size.size);
+///     });
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PixelGrid {
+    /// If the grid is painted
+    pub enabled: bool,
+    /// Color of the lines
+    pub color: Color32,
+    /// The grid is not painted when the pixels are displayed smaller than this, in egui
+    /// points, so the lines don't hide the image when it is not zoomed in
+    pub min_cell_size: f32,
+}
+
+impl Default for PixelGrid {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: Color32::from_black_alpha(96),
+            min_cell_size: 4.0,
+        }
+    }
+}
+
+impl PixelGrid {
+    /// Enabled grid with the given line color
+    pub fn new(color: impl Into<Color32>) -> Self {
+        Self {
+            enabled: true,
+            color: color.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the color of the lines
+    pub fn with_color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Switches the grid on or off
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Paints the grid over the image of a buffer of `size` pixels shown in `rect`.
+    ///
+    /// Use the rect of the [egui::Response] of the image. The lines are one physical
+    /// pixel wide and only the inner lines are painted, not the border.
+    pub fn paint(&self, painter: &egui::Painter, rect: egui::Rect, size: UVec2) {
+        if !self.enabled || size.cmpeq(UVec2::ZERO).any() {
+            return;
+        }
+        let cell = rect.size() / egui::Vec2::new(size.x as f32, size.y as f32);
+        if cell.min_elem() < self.min_cell_size {
+            return;
+        }
+        let painter = painter.with_clip_rect(rect);
+        let stroke = egui::Stroke::new(1.0 / painter.ctx().pixels_per_point(), self.color);
+        for x in 1..size.x {
+            let x = rect.left() + x as f32 * cell.x;
+            painter.vline(x, rect.y_range(), stroke);
+        }
+        for y in 1..size.y {
+            let y = rect.top() + y as f32 * cell.y;
+            painter.hline(rect.x_range(), y, stroke);
+        }
+    }
+}
+
 /// Plugin that adds a [EguiTexture] component to all pixel buffers and keeps them up to date
 pub struct PixelBufferEguiPlugin;
 
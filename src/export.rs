@@ -0,0 +1,467 @@
+//! Saving pixel buffers as PNG images, and loading encoded images into them.
+//!
+//! Requires the `image` feature.
+//!
+//! - [Frame::save_png] saves a single frame.
+//! - [Frame::load_bytes] decodes an image from memory into a frame, without touching the
+//!   filesystem, for example with bytes downloaded in a wasm app.
+//! - [Frame::to_data_url] encodes a single frame as a `data:` URL, to embed it in HTML or
+//!   share it quickly.
+//! - [PngSequenceRecorder] saves the pixel buffer every few frames as numbered PNG files,
+//!   for offline rendering of animations. The files can be assembled into a video later,
+//!   for example with `ffmpeg -framerate 30 -i frame_%04d.png output.mp4`.
+//! - [AutoSaveOnExit] saves the pixel buffer when the app exits.
+//! - [FrameRecorder::save_apng] saves the frames recorded in memory as an animated PNG.
+//!
+//! # Example
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::prelude::*;
+//! fn start_recording(mut commands: Commands, pixel_buffer: Single<Entity, With<PixelBuffer>>) {
+//!     commands
+//!         .entity(*pixel_buffer)
+//!         .insert(PngSequenceRecorder::new("recording").with_stride(2));
+//! }
+//! # bevy::ecs::system::assert_is_system(start_recording);
+//! ```
+
+use std::{
+    fs::File,
+    io::{BufWriter, Cursor, Write},
+    path::{Path, PathBuf},
+};
+
+use base64::Engine;
+
+use bevy::prelude::*;
+
+use crate::{
+    blend::image_pixels, frame::Frame, pixel::Pixel, pixel_buffer::PixelBuffer,
+    recorder::FrameRecorder,
+};
+
+impl<'a> Frame<'a> {
+    /// Saves the frame as a PNG image.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> image::ImageResult<()> {
+        save_png(self.raw(), self.size(), path.as_ref())
+    }
+
+    /// Encodes the frame as a PNG image in a base64 data URL, `data:image/png;base64,...`.
+    pub fn to_data_url(&self) -> image::ImageResult<String> {
+        let png = encode_png(self.raw(), self.size())?;
+        Ok(format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(png)
+        ))
+    }
+
+    /// Decodes an encoded image, like a PNG, and copies it into the frame.
+    ///
+    /// The image is converted to RGBA with 8 bits per channel. It is copied to the top left
+    /// corner of the frame, if it is bigger the rest is clipped and if it is smaller the
+    /// rest of the frame is not modified.
+    ///
+    /// PNG is always supported, other formats need their feature in the `image` crate,
+    /// like `jpeg`.
+    pub fn load_bytes(&mut self, bytes: &[u8]) -> Result<(), LoadError> {
+        let image = image::load_from_memory(bytes)?.into_rgba8();
+        let width = image.width().min(self.size().x) as usize;
+        let rows = image.rows().take(self.size().y as usize);
+        for (y, src) in (0..).zip(rows) {
+            let row = self.row_mut(y).expect("row inside the frame");
+            for (dst, src) in row[..width].iter_mut().zip(src) {
+                *dst = Pixel::from(src.0);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error for [Frame::load_bytes]
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum LoadError {
+    /// The bytes are not an image in a supported format
+    #[error("could not decode the image: {0}")]
+    Decode(#[from] image::ImageError),
+}
+
+impl FrameRecorder {
+    /// Saves the recorded frames as an animated PNG that loops forever.
+    ///
+    /// Every frame is shown for `1 / fps` seconds, the minimum `fps` is 1.
+    pub fn save_apng(&self, path: impl AsRef<Path>, fps: u16) -> Result<(), ApngError> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_apng(&mut file, fps)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Encodes the recorded frames as an animated PNG, see [FrameRecorder::save_apng].
+    pub fn write_apng(&self, writer: impl Write, fps: u16) -> Result<(), ApngError> {
+        let size = self.size().ok_or(ApngError::Empty)?;
+        let mut encoder = png::Encoder::new(writer, size.x, size.y);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.len() as u32, 0)?;
+        encoder.set_frame_delay(1, fps.max(1))?;
+        let mut writer = encoder.write_header()?;
+        for frame in self.frames() {
+            writer.write_image_data(bytemuck::cast_slice(frame.raw()))?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Error for [FrameRecorder::save_apng]
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum ApngError {
+    /// There are no recorded frames
+    #[error("no frames recorded")]
+    Empty,
+    /// The file could not be written
+    #[error("could not write the animation: {0}")]
+    Io(#[from] std::io::Error),
+    /// The frames could not be encoded
+    #[error("could not encode the animation: {0}")]
+    Encode(#[from] png::EncodingError),
+}
+
+fn encode_png(pixels: &[Pixel], size: UVec2) -> image::ImageResult<Vec<u8>> {
+    let mut png = Cursor::new(Vec::new());
+    image::write_buffer_with_format(
+        &mut png,
+        bytemuck::cast_slice(pixels),
+        size.x,
+        size.y,
+        image::ExtendedColorType::Rgba8,
+        image::ImageFormat::Png,
+    )?;
+    Ok(png.into_inner())
+}
+
+pub(crate) fn save_png(pixels: &[Pixel], size: UVec2, path: &Path) -> image::ImageResult<()> {
+    image::save_buffer_with_format(
+        path,
+        bytemuck::cast_slice(pixels),
+        size.x,
+        size.y,
+        image::ExtendedColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+}
+
+/// Saves the pixel buffer of the entity as numbered PNG files in a directory.
+///
+/// The files are named `frame_0001.png`, `frame_0002.png`... One file is written every
+/// [stride](PngSequenceRecorder::with_stride) updates while recording. For a fixed
+/// framerate in the output, the app should advance time by a fixed amount every update,
+/// for example with [TimeUpdateStrategy::ManualDuration](bevy::time::TimeUpdateStrategy).
+///
+/// The directory is created if it does not exist. Files are written synchronously at the
+/// end of the update, in the [Last] schedule, with the CPU side contents of the image.
+/// Errors are logged and stop the recording.
+#[derive(Component, Debug, Clone)]
+pub struct PngSequenceRecorder {
+    directory: PathBuf,
+    stride: u32,
+    recording: bool,
+    updates: u32,
+    frames_written: u32,
+}
+
+impl PngSequenceRecorder {
+    /// Records into a directory, starting right away, every update.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            stride: 1,
+            recording: true,
+            updates: 0,
+            frames_written: 0,
+        }
+    }
+
+    /// Only records one every `stride` updates. The minimum is 1, every update.
+    pub fn with_stride(mut self, stride: u32) -> Self {
+        self.stride = stride.max(1);
+        self
+    }
+
+    /// Starts or resumes the recording. The numbering continues after the last written frame.
+    pub fn start(&mut self) {
+        self.recording = true;
+    }
+
+    /// Stops the recording
+    pub fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    /// If it is recording
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Number of files written
+    pub fn frames_written(&self) -> u32 {
+        self.frames_written
+    }
+
+    /// Path of the next file
+    fn next_path(&self) -> PathBuf {
+        self.directory
+            .join(format!("frame_{:04}.png", self.frames_written + 1))
+    }
+}
+
+/// Writes the frames of the [PngSequenceRecorder]s
+pub(crate) fn record_png_sequence(
+    mut recorders: Query<(&Sprite, &mut PngSequenceRecorder), With<PixelBuffer>>,
+    images: Res<Assets<Image>>,
+) {
+    for (sprite, mut recorder) in recorders.iter_mut() {
+        if !recorder.recording {
+            continue;
+        }
+        let update = recorder.updates;
+        recorder.updates = recorder.updates.wrapping_add(1);
+        if update % recorder.stride != 0 {
+            continue;
+        }
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+
+        let path = recorder.next_path();
+        let result = std::fs::create_dir_all(&recorder.directory)
+            .map_err(image::ImageError::from)
+            .and_then(|_| save_png(image_pixels(image), image.size(), &path));
+        match result {
+            Ok(()) => recorder.frames_written += 1,
+            Err(err) => {
+                error!("Could not save frame {}: {err}", path.display());
+                recorder.stop();
+            }
+        }
+    }
+}
+
+/// Saves the pixel buffer of the entity as a PNG image when the app exits.
+///
+/// The image is written when an [AppExit] event is sent, in the [Last] schedule of the
+/// same update, with the CPU side contents of the image. Errors are logged. Exiting by
+/// other means, like a crash or killing the process, does not save.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct AutoSaveOnExit {
+    /// Path of the PNG file, it is overwritten if it exists
+    pub path: PathBuf,
+}
+
+impl AutoSaveOnExit {
+    /// Saves to a path on exit
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Writes the [AutoSaveOnExit] buffers when the app exits
+pub(crate) fn auto_save_on_exit(
+    mut exit: EventReader<AppExit>,
+    pixel_buffers: Query<(&Sprite, &AutoSaveOnExit), With<PixelBuffer>>,
+    images: Res<Assets<Image>>,
+) {
+    if exit.read().last().is_none() {
+        return;
+    }
+    for (sprite, auto_save) in pixel_buffers.iter() {
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        if let Err(err) = save_png(image_pixels(image), image.size(), &auto_save.path) {
+            error!("Could not save {}: {err}", auto_save.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        frame::GetFrameFromImages,
+        pixel_buffer::{create_image, Fill, PixelBufferSize},
+    };
+
+    #[test]
+    fn data_url() {
+        let mut pixels = vec![Pixel::BLUE; 3 * 2];
+        let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 2));
+        let url = frame.to_data_url().unwrap();
+
+        let data = url.strip_prefix("data:image/png;base64,").unwrap();
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().into_rgba8();
+        assert_eq!(decoded.dimensions(), (3, 2));
+        assert!(decoded.pixels().all(|p| p.0 == [0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn load_bytes() {
+        let mut img = image::RgbImage::new(3, 2);
+        img.put_pixel(2, 0, image::Rgb([10, 20, 30]));
+        let mut png = Cursor::new(Vec::new());
+        img.write_to(&mut png, image::ImageFormat::Png).unwrap();
+
+        let mut pixels = vec![Pixel::BLUE; 2 * 3];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 3));
+        frame.load_bytes(png.get_ref()).unwrap();
+        // clipped horizontally, the last row is not modified
+        assert_eq!(
+            frame.raw(),
+            &[
+                Pixel::BLACK,
+                Pixel::BLACK,
+                Pixel::BLACK,
+                Pixel::BLACK,
+                Pixel::BLUE,
+                Pixel::BLUE
+            ]
+        );
+
+        let mut pixels = vec![Pixel::BLUE; 3 * 2];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 2));
+        frame.load_bytes(png.get_ref()).unwrap();
+        assert_eq!(frame.raw()[2], Pixel::from([10, 20, 30, 255]));
+
+        assert!(matches!(
+            frame.load_bytes(b"not an image"),
+            Err(LoadError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn apng_frames() {
+        use image::AnimationDecoder;
+
+        let mut recorder = FrameRecorder::new(10);
+        let mut apng = Vec::new();
+        assert!(matches!(
+            recorder.write_apng(&mut apng, 30),
+            Err(ApngError::Empty)
+        ));
+
+        for color in [Pixel::RED, Pixel::GREEN, Pixel::BLUE] {
+            let mut pixels = vec![color; 3 * 2];
+            recorder.record(&Frame::from_raw_parts(&mut pixels, UVec2::new(3, 2)));
+        }
+        recorder.write_apng(&mut apng, 30).unwrap();
+
+        let decoder = image::codecs::png::PngDecoder::new(Cursor::new(apng)).unwrap();
+        assert!(decoder.is_apng().unwrap());
+        let frames = decoder
+            .apng()
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(frames.len(), 3);
+        let (num, den) = frames[0].delay().numer_denom_ms();
+        assert_eq!(num as f32 / den as f32, 1000.0 / 30.0);
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+        for (frame, color) in frames.iter().zip(colors) {
+            assert_eq!(frame.buffer().dimensions(), (3, 2));
+            assert!(frame.buffer().pixels().all(|p| p.0 == color));
+        }
+    }
+
+    #[test]
+    fn save_on_exit() {
+        let path =
+            std::env::temp_dir().join(format!("bevy_pixel_buffer_exit_{}.png", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_systems(Last, auto_save_on_exit);
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let image = images.add(create_image(UVec2::new(2, 2).into()));
+        images.frame(&image).per_pixel(|_, _| Pixel::GREEN);
+        app.world_mut().spawn((
+            PixelBuffer {
+                size: PixelBufferSize::size((2, 2)),
+                ..Default::default()
+            },
+            Sprite::from_image(image),
+            AutoSaveOnExit::new(&path),
+        ));
+
+        app.update();
+        assert!(!path.exists());
+
+        app.world_mut().send_event(AppExit::Success);
+        app.update();
+        let saved = image::open(&path).unwrap().into_rgba8();
+        assert_eq!(saved.dimensions(), (2, 2));
+        assert!(saved.pixels().all(|p| p.0 == [0, 255, 0, 255]));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn record_sequence() {
+        let directory =
+            std::env::temp_dir().join(format!("bevy_pixel_buffer_sequence_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_systems(Last, record_png_sequence);
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let image = images.add(create_image(UVec2::new(4, 2).into()));
+        images.frame(&image).per_pixel(|_, _| Pixel::RED);
+        let entity = app
+            .world_mut()
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((4, 2)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image),
+                PngSequenceRecorder::new(&directory).with_stride(2),
+            ))
+            .id();
+
+        for _ in 0..5 {
+            app.update();
+        }
+        app.world_mut()
+            .get_mut::<PngSequenceRecorder>(entity)
+            .unwrap()
+            .stop();
+        app.update();
+
+        let recorder = app.world().get::<PngSequenceRecorder>(entity).unwrap();
+        assert_eq!(recorder.frames_written(), 3);
+        assert!(directory.join("frame_0003.png").exists());
+        assert!(!directory.join("frame_0004.png").exists());
+
+        let saved = image::open(directory.join("frame_0001.png"))
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(saved.dimensions(), (4, 2));
+        assert!(saved.pixels().all(|p| p.0 == [255, 0, 0, 255]));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,7 +1,14 @@
 //! Frame and frame utility functions that helps to draw things on raw image data.
 
-use crate::pixel::Pixel;
-use bevy::{prelude::*, render::render_resource::TextureUsages};
+use crate::{
+    blend::{blend, image_pixels, BlendMode},
+    pixel::Pixel,
+};
+use bevy::{
+    math::U64Vec2,
+    prelude::*,
+    render::render_resource::{TextureFormat, TextureUsages},
+};
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 
 /// Helper structure to edit a pixel buffer
@@ -10,6 +17,8 @@ pub struct Frame<'a> {
     pixels: &'a mut [Pixel],
     /// Size of the frame
     size: UVec2,
+    /// Default [AddressMode] to read outside of the frame
+    edge_mode: AddressMode,
 }
 
 impl<'a> Frame<'a> {
@@ -28,6 +37,68 @@ impl<'a> Frame<'a> {
         self.size
     }
 
+    /// If a location is inside the frame.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*5];
+    /// # let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 5));
+    /// assert!(frame.contains(UVec2::new(9, 4)));
+    /// assert!(!frame.contains(UVec2::new(10, 4)));
+    /// assert!(!frame.contains(UVec2::new(9, 5)));
+    /// ```
+    pub fn contains(&self, pos: UVec2) -> bool {
+        pos.cmplt(self.size).all()
+    }
+
+    /// Closest location inside the frame, clamping each coordinate to the edges.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*5];
+    /// # let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 5));
+    /// assert_eq!(frame.clamp_pos(IVec2::new(-3, 2)), UVec2::new(0, 2));
+    /// assert_eq!(frame.clamp_pos(IVec2::new(12, 4)), UVec2::new(9, 4));
+    /// assert_eq!(frame.clamp_pos(IVec2::new(9, -1)), UVec2::new(9, 0));
+    /// ```
+    pub fn clamp_pos(&self, pos: IVec2) -> UVec2 {
+        let max = self.size.saturating_sub(UVec2::ONE).as_ivec2();
+        pos.clamp(IVec2::ZERO, max).as_uvec2()
+    }
+
+    /// Overwrites the whole frame with pixels computed somewhere else.
+    ///
+    /// The data has to have exactly one pixel per pixel of the frame, in row-major order.
+    /// This is a single copy, the image is uploaded to the GPU once as with any other
+    /// change to the frame.
+    pub fn copy_from_pixels(&mut self, data: &[Pixel]) -> FrameResult {
+        if data.len() != self.pixels.len() {
+            return Err(FrameError::LengthMismatch {
+                expected: self.pixels.len(),
+                found: data.len(),
+            });
+        }
+        self.pixels.copy_from_slice(data);
+        Ok(())
+    }
+
+    /// Overwrites the whole frame with raw RGBA bytes, 4 per pixel, in row-major order.
+    pub fn copy_from_bytes(&mut self, data: &[u8]) -> FrameResult {
+        let bytes: &mut [u8] = bytemuck::cast_slice_mut(self.pixels);
+        if data.len() != bytes.len() {
+            return Err(FrameError::LengthMismatch {
+                expected: bytes.len(),
+                found: data.len(),
+            });
+        }
+        bytes.copy_from_slice(data);
+        Ok(())
+    }
+
     /// Runs a function once per pixel with 2 parameters:
     /// - The X and Y position, (0, 0) in the top left.
     /// - The current pixel value
@@ -66,6 +137,15 @@ impl<'a> Frame<'a> {
             });
     }
 
+    /// Sets every pixel from its linear index, `x + y * width`.
+    ///
+    /// The function is called once per pixel in order, so it can keep state.
+    pub fn fill_with_index(&mut self, mut f: impl FnMut(usize) -> Pixel) {
+        for (idx, pixel) in self.pixels.iter_mut().enumerate() {
+            *pixel = f(idx);
+        }
+    }
+
     /// Sets a pixel in the frame
     pub fn set(&mut self, location: impl Into<UVec2>, pixel: impl Into<Pixel>) -> FrameResult {
         let location: UVec2 = location.into();
@@ -77,136 +157,3039 @@ impl<'a> Frame<'a> {
         Ok(())
     }
 
-    fn check_bounds(&self, location: UVec2) -> FrameResult {
-        if location.x >= self.size.x || location.y >= self.size.y {
-            Err(FrameError::LocationOutOfBounds {
-                location,
-                size: self.size,
-            })
-        } else {
-            Ok(())
+    /// Pixels of a row, `None` if `y` is outside of the frame
+    pub fn row(&self, y: u32) -> Option<&[Pixel]> {
+        let range = self.row_range(y)?;
+        Some(&self.pixels[range])
+    }
+
+    /// Pixels of a row mutable, `None` if `y` is outside of the frame.
+    ///
+    /// There is no per row change tracking, getting the frame from the [Assets] already
+    /// marks the whole image as changed.
+    pub fn row_mut(&mut self, y: u32) -> Option<&mut [Pixel]> {
+        let range = self.row_range(y)?;
+        Some(&mut self.pixels[range])
+    }
+
+    /// Copy of the pixels of a column, `None` if `x` is outside of the frame.
+    ///
+    /// The pixels of a column are not contiguous in memory, so they can't be borrowed
+    /// as a slice like [Frame::row].
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// frame.row_mut(2).unwrap().fill(Pixel::RED);
+    /// let column = frame.column(4).unwrap();
+    /// assert_eq!(column[2], Pixel::RED);
+    /// assert_eq!(column.len(), 10);
+    /// assert!(frame.column(10).is_none());
+    /// ```
+    pub fn column(&self, x: u32) -> Option<Vec<Pixel>> {
+        if x >= self.size.x {
+            return None;
         }
+        Some(self.column_indices(x).map(|i| self.pixels[i]).collect())
     }
-}
 
-/// Result type for some methods of [Frame]
-pub type FrameResult = Result<(), FrameError>;
+    /// Overwrites the pixels of a column.
+    ///
+    /// The length of `pixels` has to be the height of the frame.
+    pub fn set_column(&mut self, x: u32, pixels: &[Pixel]) -> FrameResult {
+        self.check_bounds(UVec2::new(x, 0))?;
+        if pixels.len() != self.size.y as usize {
+            return Err(FrameError::SizeMismatch {
+                expected: UVec2::new(1, self.size.y),
+                found: UVec2::new(1, pixels.len() as u32),
+            });
+        }
+        for (i, pixel) in self.column_indices(x).zip(pixels) {
+            self.pixels[i] = *pixel;
+        }
+        Ok(())
+    }
 
-/// Error type for some methods of [Frame]
-#[derive(thiserror::Error, Debug)]
-#[non_exhaustive]
-pub enum FrameError {
-    /// The location is not inside the frame
-    #[error(
-        "location out of the bounds of the frame (location: {location:?}, frame size: {size:?}"
-    )]
-    LocationOutOfBounds {
-        /// wrong location
-        location: UVec2,
-        /// frame size
-        size: UVec2,
-    },
-}
+    /// If all the pixels have an alpha of 0.
+    ///
+    /// Stops at the first pixel that is not fully transparent.
+    pub fn is_fully_transparent(&self) -> bool {
+        self.pixels.iter().all(|p| p.a == 0)
+    }
 
-impl<'a> Frame<'a> {
-    /// Builds a frame from a bevy image
-    pub fn get(image: &'a mut Image) -> Self {
-        debug_assert_eq!(image.texture_descriptor.format, Pixel::FORMAT);
-        debug_assert!(image
-            .texture_descriptor
-            .usage
-            .contains(TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST));
-        let size = image.size();
-        let pixels = bytemuck::cast_slice_mut(&mut image.data);
-        Self { pixels, size }
+    /// If all the pixels have an alpha of 255.
+    ///
+    /// Stops at the first pixel that is not fully opaque.
+    pub fn is_fully_opaque(&self) -> bool {
+        self.pixels.iter().all(|p| p.a == 255)
     }
 
-    /// Builds a frame by extracting a bevy image from the assets.
-    pub fn extract(images: &'a mut Assets<Image>, image_handle: &Handle<Image>) -> Self {
-        Self::get(
-            images
-                .get_mut(image_handle)
-                .expect("image when building frame"),
-        )
+    /// Converts all the pixels from straight alpha to premultiplied alpha,
+    /// see [Pixel::premultiplied].
+    pub fn premultiply_all(&mut self) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel = pixel.premultiplied();
+        }
     }
 
-    /// Constructs a frame from raw parts
+    /// Converts all the pixels from premultiplied alpha to straight alpha,
+    /// see [Pixel::unpremultiplied].
+    ///
+    /// The conversion loses precision for pixels with a low alpha, and the color of
+    /// fully transparent pixels is lost.
+    pub fn unpremultiply_all(&mut self) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel = pixel.unpremultiplied();
+        }
+    }
+
+    /// Multiplies the color channels of all the pixels by a color, for global effects like
+    /// a damage flash or a night tint.
+    ///
+    /// Each channel is multiplied by the channel of the tint in the `0.0..=1.0` range and
+    /// rounded, so a white tint changes nothing. The alpha of the pixels is kept, the
+    /// alpha of the tint is ignored. To tint only when displaying, see
+    /// [PixelBufferBuilder::with_tint](crate::builder::PixelBufferBuilder::with_tint).
+    pub fn tint(&mut self, color: impl Into<Pixel>) {
+        let tint = color.into();
+        let mul = |c: u8, t: u8| ((c as u32 * t as u32 + 127) / 255) as u8;
+        for pixel in self.pixels.iter_mut() {
+            pixel.r = mul(pixel.r, tint.r);
+            pixel.g = mul(pixel.g, tint.g);
+            pixel.b = mul(pixel.b, tint.b);
+        }
+    }
+
+    /// Converts the color channels of all the pixels from sRGB to linear.
+    ///
+    /// Colors are usually picked in sRGB, where the values are not proportional to the
+    /// light. Averaging or blending them directly, like in a blur, gives darker and muddier
+    /// results than expected. Convert to linear, do the operation, and convert back with
+    /// [Frame::to_srgb].
+    ///
+    /// The alpha is not changed. With 8 bits per channel, linear values lose precision in
+    /// the dark tones, so a round trip is not lossless. For long chains of operations, an
+    /// [AccumulationBuffer](crate::accumulation::AccumulationBuffer) keeps full precision.
+    pub fn to_linear(&mut self) {
+        self.map_color_channels(bevy::color::Srgba::gamma_function);
+    }
+
+    /// Converts the color channels of all the pixels from linear to sRGB, the inverse of
+    /// [Frame::to_linear].
+    pub fn to_srgb(&mut self) {
+        self.map_color_channels(bevy::color::Srgba::gamma_function_inverse);
+    }
+
+    /// Applies a tone curve to the luminance of all the pixels, keeping their color.
+    ///
+    /// The curve gets and returns a luminance in the `0.0..=1.0` range, with the
+    /// Rec. 709 weights as [Pixel::luminance]. The color channels are multiplied by the
+    /// ratio between the new and the old luminance, so the proportions between them, the
+    /// hue and saturation, are kept instead of washing out to gray. Channels that go over
+    /// 255 are clamped, which desaturates very bright colors a bit. Black pixels have no
+    /// color to scale and become the gray of the new luminance.
+    ///
+    /// The alpha is not changed.
+    pub fn map_luminance(&mut self, curve: impl Fn(f32) -> f32) {
+        for pixel in self.pixels.iter_mut() {
+            let rgb = pixel.as_vec4().truncate();
+            let luminance = rgb.dot(Vec3::new(0.2126, 0.7152, 0.0722));
+            let target = curve(luminance).clamp(0.0, 1.0);
+            let rgb = if luminance > 0.0 {
+                rgb * (target / luminance)
+            } else {
+                Vec3::splat(target)
+            };
+            let rgb = (rgb * 255.0).round().clamp(Vec3::ZERO, Vec3::splat(255.0));
+            pixel.r = rgb.x as u8;
+            pixel.g = rgb.y as u8;
+            pixel.b = rgb.z as u8;
+        }
+    }
+
+    /// Stretches the levels of the frame to the full range, improving the contrast of dull
+    /// frames.
+    ///
+    /// The darkest and brightest values are found in the histogram of the visible pixels,
+    /// fully transparent ones are ignored. `percentile` is the percent of pixels, from
+    /// `0.0` to `50.0`, that are ignored at each end, so a few outliers don't prevent the
+    /// stretch; they are clipped instead. With `0.0` the minimum and maximum are used. The
+    /// values in between are remapped linearly:
+    /// - [LevelsMode::Channels] stretches each color channel on its own. This also removes
+    ///   color casts, but it can shift the hues, as the channels are scaled differently.
+    /// - [LevelsMode::Luminance] stretches the luminance with [Frame::map_luminance], so
+    ///   the colors are kept.
+    ///
+    /// Channels, or the luminance, with a single level are left as they are. The alpha is
+    /// not changed.
     ///
     /// # Example
     /// ```
     /// # use bevy::math::UVec2;
-    /// # use bevy_pixel_buffer::prelude::{Frame, Pixel};
-    /// let mut pixels = vec![Pixel::BLACK; 10*10];
-    /// let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// # use bevy_pixel_buffer::{frame::LevelsMode, prelude::*};
+    /// let mut pixels = vec![Pixel::from([64, 64, 64, 255]), Pixel::from([192, 192, 192, 255])];
+    /// let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 1));
+    /// frame.auto_levels(0.0, LevelsMode::Channels);
+    /// assert_eq!(frame.raw(), &[Pixel::BLACK, Pixel::WHITE]);
+    /// ```
+    pub fn auto_levels(&mut self, percentile: f32, mode: LevelsMode) {
+        let percentile = percentile.clamp(0.0, 50.0);
+        let visible = || self.pixels.iter().filter(|pixel| pixel.a > 0);
+        match mode {
+            LevelsMode::Channels => {
+                let mut histograms = [[0u32; 256]; 3];
+                for pixel in visible() {
+                    histograms[0][pixel.r as usize] += 1;
+                    histograms[1][pixel.g as usize] += 1;
+                    histograms[2][pixel.b as usize] += 1;
+                }
+                let tables = histograms.map(|histogram| {
+                    let (low, high) = histogram_levels(&histogram, percentile);
+                    std::array::from_fn::<u8, 256, _>(|i| stretch_level(i as u8, low, high))
+                });
+                for pixel in self.pixels.iter_mut() {
+                    pixel.r = tables[0][pixel.r as usize];
+                    pixel.g = tables[1][pixel.g as usize];
+                    pixel.b = tables[2][pixel.b as usize];
+                }
+            }
+            LevelsMode::Luminance => {
+                let mut histogram = [0u32; 256];
+                for pixel in visible() {
+                    histogram[pixel.luminance() as usize] += 1;
+                }
+                let (low, high) = histogram_levels(&histogram, percentile);
+                if low < high {
+                    let (low, range) = (low as f32 / 255.0, (high - low) as f32 / 255.0);
+                    self.map_luminance(|l| (l - low) / range);
+                }
+            }
+        }
+    }
+
+    /// Maps the color channels with a function of normalized values, through a lookup table
+    fn map_color_channels(&mut self, f: impl Fn(f32) -> f32) {
+        let table: [u8; 256] =
+            std::array::from_fn(|i| (f(i as f32 / 255.0) * 255.0).round().clamp(0.0, 255.0) as u8);
+        for pixel in self.pixels.iter_mut() {
+            pixel.r = table[pixel.r as usize];
+            pixel.g = table[pixel.g as usize];
+            pixel.b = table[pixel.b as usize];
+        }
+    }
+
+    /// Uses the luminance of a mask as the alpha channel of the frame.
+    ///
+    /// The luminance uses the Rec. 709 weights, for grayscale masks it is the same as
+    /// their red channel. The alpha of the mask is ignored. The mask has to be the same
+    /// size as the frame.
+    pub fn apply_alpha_mask(&mut self, mask: &Frame) -> FrameResult {
+        self.check_same_size(mask.size())?;
+        for (pixel, mask) in self.pixels.iter_mut().zip(mask.raw()) {
+            pixel.a = mask.luminance();
+        }
+        Ok(())
+    }
+
+    /// Makes transparent the pixels with a color close to `key`, like a green screen.
+    ///
+    /// Colors are compared by their chroma, the CbCr plane of the Rec. 709 YCbCr color
+    /// space, ignoring the luma. This is closer to how the eye perceives color differences
+    /// than comparing the RGB channels. The distance between two chromas is at most
+    /// around `0.7`:
+    /// - Pixels closer than `tolerance` become fully transparent.
+    /// - Pixels between `tolerance` and `tolerance + softness` fade linearly, for a soft edge.
+    /// - Pixels further away keep their alpha.
+    ///
+    /// Only the alpha is modified, it is multiplied by the key factor.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::GREEN, Pixel::from([20, 230, 30, 255]), Pixel::RED];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 1));
+    /// frame.chroma_key(Pixel::GREEN, 0.2, 0.1);
+    /// assert_eq!(frame.raw()[0].a, 0);
+    /// assert_eq!(frame.raw()[1].a, 0);
+    /// assert_eq!(frame.raw()[2].a, 255);
     /// ```
+    pub fn chroma_key(&mut self, key: impl Into<Pixel>, tolerance: f32, softness: f32) {
+        let key = chroma(key.into());
+        for pixel in self.pixels.iter_mut() {
+            let distance = chroma(*pixel).distance(key);
+            let factor = if distance <= tolerance {
+                0.0
+            } else if distance >= tolerance + softness {
+                1.0
+            } else {
+                (distance - tolerance) / softness
+            };
+            pixel.a = (pixel.a as f32 * factor).round() as u8;
+        }
+    }
+
+    /// Draws a line between 2 points, both included.
     ///
-    /// # Panics
-    /// If the length of the slice does not correspond with the given size
-    pub fn from_raw_parts(pixels: &'a mut [Pixel], size: UVec2) -> Self {
-        assert_eq!(pixels.len(), (size.x * size.y) as usize);
-        Self { pixels, size }
+    /// The points can be outside of the frame, only the visible part of the
+    /// line is drawn.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// frame.draw_line(IVec2::new(-5, 0), IVec2::new(9, 0), Pixel::RED);
+    /// assert!(frame.raw()[..10].iter().all(|p| *p == Pixel::RED));
+    /// ```
+    pub fn draw_line(
+        &mut self,
+        from: impl Into<IVec2>,
+        to: impl Into<IVec2>,
+        pixel: impl Into<Pixel>,
+    ) {
+        let pixel = pixel.into();
+        for location in line_points(from.into(), to.into()) {
+            self.set_clipped(location, pixel);
+        }
     }
-}
 
-/// Convenience trait to get a [Frame]
-pub trait GetFrame {
-    /// Get a frame to mutate a pixel buffer
-    fn frame(&mut self) -> Frame<'_>;
-}
+    /// Draws a dashed line, `dash` pixels on and `gap` pixels off, starting with a dash at
+    /// `from`.
+    ///
+    /// The pattern is counted along the pixels of the line, like in [Frame::draw_line], so
+    /// it continues in the parts that are clipped out of the frame.
+    pub fn draw_dashed_line(
+        &mut self,
+        from: impl Into<IVec2>,
+        to: impl Into<IVec2>,
+        pixel: impl Into<Pixel>,
+        dash: u32,
+        gap: u32,
+    ) {
+        if dash == 0 {
+            return;
+        }
+        let pixel = pixel.into();
+        let period = dash as usize + gap as usize;
+        for (i, location) in line_points(from.into(), to.into()).enumerate() {
+            if i % period < dash as usize {
+                self.set_clipped(location, pixel);
+            }
+        }
+    }
 
-impl GetFrame for Image {
-    #[inline(always)]
-    fn frame(&mut self) -> Frame<'_> {
-        Frame::get(self)
+    /// Draws connected line segments between the points, closing the loop back to the
+    /// first point if `closed`.
+    ///
+    /// A single point is drawn as a pixel, and no points draw nothing. The lines are
+    /// clipped to the frame.
+    pub fn draw_polyline(&mut self, points: &[IVec2], pixel: impl Into<Pixel>, closed: bool) {
+        let close = points.first().filter(|_| closed && points.len() > 2);
+        self.draw_path(points.iter().chain(close).copied(), pixel);
     }
-}
 
-/// Convenience trait to get a [Frame] from a [Handle] needs the [image](Image) [assets](Assets).
-pub trait GetFrameFromHandle: AsImageHandle {
-    /// Get a frame to mutate a pixel buffer
-    fn frame<'a>(&self, images: &'a mut Assets<Image>) -> Frame<'a> {
-        Frame::extract(images, self.as_image_handle())
+    /// Draws connected line segments between the points of an iterator, like
+    /// [Frame::draw_polyline] but without collecting them first.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// // plot of a function
+    /// let f = |x: i32| (x * x) / 10;
+    /// frame.draw_path((0..10).map(|x| IVec2::new(x, 9 - f(x))), Pixel::GREEN);
+    /// assert_eq!(frame.raw()[9 * 10], Pixel::GREEN);
+    /// ```
+    pub fn draw_path(&mut self, points: impl IntoIterator<Item = IVec2>, pixel: impl Into<Pixel>) {
+        let pixel = pixel.into();
+        let mut points = points.into_iter();
+        let Some(mut previous) = points.next() else {
+            return;
+        };
+        self.set_clipped(previous, pixel);
+        for point in points {
+            self.draw_line(previous, point, pixel);
+            previous = point;
+        }
     }
-}
 
-impl<T: AsImageHandle> GetFrameFromHandle for T {}
+    /// Draws the outline of a circle.
+    ///
+    /// The circle can be partially outside of the frame, only the visible part is drawn.
+    pub fn draw_circle(&mut self, center: IVec2, radius: u32, pixel: impl Into<Pixel>) {
+        let pixel = pixel.into();
+        for location in circle_points(center, radius) {
+            self.set_clipped(location, pixel);
+        }
+    }
 
-/// Convenience trait to get a frame from the [image](Image) [assets](Assets) with a [Handle]
-pub trait GetFrameFromImages: AsMut<Assets<Image>> {
-    /// Get a frame to mutate a pixel buffer
-    fn frame(&mut self, image_handle: impl AsImageHandle) -> Frame<'_> {
-        Frame::extract(self.as_mut(), image_handle.as_image_handle())
+    /// Draws the part of a circle outline between two angles, in degrees.
+    ///
+    /// Angles start at the positive X axis and, as the Y axis points down, increase
+    /// clockwise. The arc goes from `start_deg` to `end_deg`, wrapping around 360
+    /// degrees if `end_deg` is smaller. If they are 360 degrees or more apart the
+    /// arc is the full circle, like [Frame::draw_circle].
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// // bottom half
+    /// frame.draw_arc(IVec2::new(5, 5), 4, 0.0, 180.0, Pixel::RED);
+    /// assert_eq!(frame.raw()[9 * 10 + 5], Pixel::RED);
+    /// assert_eq!(frame.raw()[1 * 10 + 5], Pixel::BLACK);
+    /// ```
+    pub fn draw_arc(
+        &mut self,
+        center: IVec2,
+        radius: u32,
+        start_deg: f32,
+        end_deg: f32,
+        pixel: impl Into<Pixel>,
+    ) {
+        let pixel = pixel.into();
+        let in_arc = arc_test(start_deg, end_deg);
+        for location in circle_points(center, radius) {
+            if in_arc(location - center) {
+                self.set_clipped(location, pixel);
+            }
+        }
     }
-}
 
-impl<T: AsMut<Assets<Image>>> GetFrameFromImages for T {}
+    /// Fills a pie slice, the part of a circle between two angles, in degrees.
+    ///
+    /// The angles are the same as in [Frame::draw_arc], so the outline of the slice is
+    /// the arc. Useful for radial progress indicators. The slice can be partially outside
+    /// of the frame, only the visible part is drawn.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let progress = 0.25;
+    /// frame.fill_pie(IVec2::new(5, 5), 4, -90.0, -90.0 + 360.0 * progress, Pixel::GREEN);
+    /// // top right quarter
+    /// assert_eq!(frame.raw()[3 * 10 + 7], Pixel::GREEN);
+    /// assert_eq!(frame.raw()[7 * 10 + 3], Pixel::BLACK);
+    /// ```
+    pub fn fill_pie(
+        &mut self,
+        center: IVec2,
+        radius: u32,
+        start_deg: f32,
+        end_deg: f32,
+        pixel: impl Into<Pixel>,
+    ) {
+        let pixel = pixel.into();
+        let in_arc = arc_test(start_deg, end_deg);
+        let radius = radius as i32;
+        // same rounding as the outline of the circle
+        let max_distance = radius * radius + radius;
+        let min = (center - radius).max(IVec2::ZERO);
+        let max = (center + radius).min(self.size.as_ivec2() - 1);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let offset = IVec2::new(x, y) - center;
+                if offset.length_squared() <= max_distance
+                    && (offset == IVec2::ZERO || in_arc(offset))
+                {
+                    self.pixels[(x + y * self.size.x as i32) as usize] = pixel;
+                }
+            }
+        }
+    }
 
-/// Used to get a reference to a [image](Image) [handle](Handle).
-///
-/// This is a workaround until `impl<T> AsRef<T> for &T` is stabilized.
-pub trait AsImageHandle {
-    /// Get a image handle from the type
-    fn as_image_handle(&self) -> &Handle<Image>;
-}
+    /// Fills a polygon with antialiased edges, blending the color over the frame.
+    ///
+    /// The coverage of every pixel is computed with 4x4 samples and multiplies the alpha
+    /// of the color. The pixel `(x, y)` is the square from `(x, y)` to `(x + 1, y + 1)`, so
+    /// its center is at `(x + 0.5, y + 0.5)`. The polygon is closed automatically and
+    /// self-intersections use the even-odd rule. It can be partially outside of the frame.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{UVec2, Vec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::TRANSPARENT; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let triangle = [Vec2::new(1.0, 1.0), Vec2::new(9.0, 1.0), Vec2::new(1.0, 9.0)];
+    /// frame.fill_polygon_aa(&triangle, Pixel::RED);
+    /// assert_eq!(frame.raw()[2 * 10 + 2], Pixel::RED);
+    /// // the diagonal edge is partially covered
+    /// let edge = frame.raw()[5 * 10 + 4];
+    /// assert!(edge.a > 0 && edge.a < 255);
+    /// ```
+    pub fn fill_polygon_aa(&mut self, points: &[Vec2], pixel: impl Into<Pixel>) {
+        const SAMPLES: u32 = 4;
+        let color = pixel.into().as_vec4();
+        if points.len() < 3 {
+            return;
+        }
 
-impl AsImageHandle for Handle<Image> {
-    fn as_image_handle(&self) -> &Handle<Image> {
-        self
+        let (min_y, max_y) = points
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                (min.min(p.y), max.max(p.y))
+            });
+        let first_row = min_y.floor().max(0.0) as u32;
+        let end_row = (max_y.ceil().max(0.0) as u32).min(self.size.y);
+        let width_samples = (self.size.x * SAMPLES) as i64;
+
+        let mut coverage = vec![0u32; self.size.x as usize];
+        let mut crossings = Vec::new();
+        for y in first_row..end_row {
+            coverage.fill(0);
+            for sub_row in 0..SAMPLES {
+                let sample_y = y as f32 + (sub_row as f32 + 0.5) / SAMPLES as f32;
+
+                crossings.clear();
+                for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                    if (a.y <= sample_y) != (b.y <= sample_y) {
+                        let t = (sample_y - a.y) / (b.y - a.y);
+                        crossings.push(a.x + t * (b.x - a.x));
+                    }
+                }
+                crossings.sort_unstable_by(f32::total_cmp);
+
+                for span in crossings.chunks_exact(2) {
+                    // samples at x = (k + 0.5) / SAMPLES inside the span
+                    let to_sample = |x: f32| (x * SAMPLES as f32 - 0.5).ceil() as i64;
+                    let start = to_sample(span[0]).clamp(0, width_samples);
+                    let end = to_sample(span[1]).clamp(0, width_samples);
+                    for k in start..end {
+                        coverage[(k / SAMPLES as i64) as usize] += 1;
+                    }
+                }
+            }
+
+            let row = y as usize * self.size.x as usize;
+            for (x, covered) in coverage.iter().enumerate() {
+                if *covered > 0 {
+                    let mut source = color;
+                    source.w *= *covered as f32 / (SAMPLES * SAMPLES) as f32;
+                    let dst = &mut self.pixels[row + x];
+                    *dst =
+                        Pixel::from_vec4_rounded(blend(dst.as_vec4(), source, BlendMode::Normal));
+                }
+            }
+        }
     }
-}
 
-impl AsImageHandle for &Handle<Image> {
-    fn as_image_handle(&self) -> &Handle<Image> {
-        self
+    /// Adds a color at a fractional location, split between the four closest pixels
+    /// with bilinear weights.
+    ///
+    /// Pixel centers are at integer coordinates, like in [Frame::sample_bilinear], so a
+    /// splat at a pixel center only changes that pixel. All the channels, alpha included,
+    /// are added and clamped to 255. Splatting many points gives a smooth density map,
+    /// for example for scatter plots. Each splat is rounded to 8 bits, for many faint
+    /// points an [AccumulationBuffer](crate::accumulation::AccumulationBuffer) keeps
+    /// more precision.
+    pub fn splat(&mut self, pos: Vec2, color: impl Into<Pixel>) {
+        let color = color.into().as_vec4();
+        let base = pos.floor();
+        let t = pos - base;
+        let base = base.as_ivec2();
+        for (offset, weight) in [
+            (IVec2::new(0, 0), (1.0 - t.x) * (1.0 - t.y)),
+            (IVec2::new(1, 0), t.x * (1.0 - t.y)),
+            (IVec2::new(0, 1), (1.0 - t.x) * t.y),
+            (IVec2::new(1, 1), t.x * t.y),
+        ] {
+            if weight <= 0.0 {
+                continue;
+            }
+            if let Some(index) = self.index_clipped(base + offset) {
+                let dst = &mut self.pixels[index];
+                *dst = Pixel::from_vec4_rounded(dst.as_vec4() + color * weight);
+            }
+        }
     }
-}
 
-/// Convenience trait to modify elements with a [Frame]
-pub trait FrameEditExtension: GetFrame {
-    /// Runs a given closure to modify the buffer.
-    fn edit_frame(&mut self, f: impl Fn(&mut Frame)) {
-        f(&mut self.frame())
+    /// Returns a view of the frame with its coordinates rotated `angle` radians
+    /// around `center`.
+    ///
+    /// Anything drawn through the [RotatedFrame] is transformed and then rasterized
+    /// in the frame, rounding to the nearest pixel. As the Y axis points down,
+    /// positive angles rotate clockwise.
+    pub fn rotated(&mut self, angle: f32, center: Vec2) -> RotatedFrame<'_> {
+        RotatedFrame {
+            frame: Frame {
+                pixels: self.pixels,
+                size: self.size,
+                edge_mode: self.edge_mode,
+            },
+            rotation: Vec2::from_angle(angle),
+            center,
+        }
     }
-}
 
-impl<T: GetFrame> FrameEditExtension for T {}
+    /// Gets a pixel, resolving locations outside of the frame with an [AddressMode].
+    ///
+    /// Returns [None] only for [AddressMode::Transparent] outside of the frame.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// frame.set((9, 0), Pixel::RED).unwrap();
+    /// assert_eq!(frame.get_pixel(IVec2::new(-1, 0), AddressMode::Wrap), Some(Pixel::RED));
+    /// assert_eq!(frame.get_pixel(IVec2::new(-1, 0), AddressMode::Transparent), None);
+    /// ```
+    pub fn get_pixel(&self, location: IVec2, address: AddressMode) -> Option<Pixel> {
+        get_pixel(self.pixels, self.size, location, address)
+    }
+
+    /// Uses an [AddressMode] by default to read outside of the frame, with
+    /// [Frame::sample] and [Frame::for_each_neighbor].
+    ///
+    /// It's [AddressMode::Transparent] unless the frame comes from a pixel buffer with
+    /// another [edge mode](crate::pixel_buffer::PixelBuffer::edge_mode). Snapshots keep it.
+    pub fn with_edge_mode(mut self, edge_mode: AddressMode) -> Self {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    /// Default [AddressMode] to read outside of the frame, see [Frame::with_edge_mode]
+    pub fn edge_mode(&self) -> AddressMode {
+        self.edge_mode
+    }
+
+    /// Gets a pixel, resolving locations outside of the frame with the
+    /// [edge mode](Frame::with_edge_mode) of the frame.
+    ///
+    /// Locations that can't be resolved, with [AddressMode::Transparent], are transparent.
+    pub fn sample(&self, location: IVec2) -> Pixel {
+        sample(self.pixels, self.size, location, self.edge_mode)
+    }
+
+    /// Calls `f` with the offset and the pixel of the 8 neighbors of a location, the
+    /// Moore neighborhood, resolved like [Frame::sample].
+    ///
+    /// With [AddressMode::Wrap] the frame is a torus, like in many cellular automata.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let mut frame = frame.with_edge_mode(AddressMode::Wrap);
+    /// frame.set((9, 9), Pixel::WHITE).unwrap();
+    /// let mut alive = 0;
+    /// frame.for_each_neighbor(IVec2::ZERO, |_, pixel| alive += (pixel == Pixel::WHITE) as u32);
+    /// assert_eq!(alive, 1);
+    /// ```
+    pub fn for_each_neighbor(&self, location: IVec2, f: impl FnMut(IVec2, Pixel)) {
+        for_each_neighbor(self.pixels, self.size, location, self.edge_mode, f);
+    }
+
+    /// Samples the frame at a location using bilinear interpolation.
+    ///
+    /// Pixel centers are at integer coordinates, so sampling `(1.0, 2.0)` returns
+    /// exactly the pixel at `(1, 2)`. Samples outside of the frame are resolved with
+    /// the [AddressMode], with [AddressMode::Transparent] they are transparent.
+    ///
+    /// The interpolation is done with premultiplied alpha, so transparent pixels
+    /// do not darken the result.
+    pub fn sample_bilinear(&self, location: Vec2, address: AddressMode) -> Pixel {
+        sample_bilinear(self.pixels, self.size, location, address)
+    }
+
+    /// Writes this frame rotated `angle` radians around its center into `dst`.
+    ///
+    /// The source is only read and the destination only written, so they have to
+    /// be different buffers. The centers of both frames are aligned and, as the Y
+    /// axis points down, positive angles rotate clockwise.
+    ///
+    /// Every pixel of the destination is overwritten by inverse mapping it into the
+    /// source and sampling it with [Frame::sample_bilinear]. Locations outside of
+    /// the source become transparent, see [AddressMode::Transparent].
+    pub fn rotate_into(&self, angle: f32, dst: &mut Frame) {
+        let src_center = (self.size.as_vec2() - Vec2::ONE) / 2.0;
+        let dst_center = (dst.size.as_vec2() - Vec2::ONE) / 2.0;
+        let inverse = Vec2::from_angle(-angle);
+
+        let width = dst.size.x;
+        for (idx, pixel) in dst.pixels.iter_mut().enumerate() {
+            let idx = idx as u32;
+            let pos = UVec2::new(idx % width, idx / width).as_vec2();
+            let src_pos = src_center + inverse.rotate(pos - dst_center);
+            *pixel = self.sample_bilinear(src_pos, AddressMode::Transparent);
+        }
+    }
+
+    /// Overwrites the frame sampling another one through a coordinate mapping.
+    ///
+    /// For every pixel of this frame, `map` returns the location to sample in `src` with
+    /// [Frame::sample_bilinear] and `address`. Pixel centers are at integer coordinates,
+    /// so `|pos| pos.as_vec2()` is the identity. Both frames can have different sizes.
+    ///
+    /// # Example
+    /// A swirl around the center.
+    /// ```
+    /// # use bevy::math::{UVec2, Vec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut src_pixels = vec![Pixel::BLACK; 10*10];
+    /// # let src = Frame::from_raw_parts(&mut src_pixels, UVec2::new(10, 10));
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let center = Vec2::splat(4.5);
+    /// frame.warp_from(&src, |pos| {
+    ///     let offset = pos.as_vec2() - center;
+    ///     let angle = (5.0 - offset.length()).max(0.0) * 0.3;
+    ///     center + Vec2::from_angle(angle).rotate(offset)
+    /// }, AddressMode::Clamp);
+    /// ```
+    pub fn warp_from(&mut self, src: &Frame, map: impl Fn(UVec2) -> Vec2, address: AddressMode) {
+        let width = self.size.x;
+        for (idx, pixel) in self.pixels.iter_mut().enumerate() {
+            let idx = idx as u32;
+            let pos = UVec2::new(idx % width, idx / width);
+            *pixel = src.sample_bilinear(map(pos), address);
+        }
+    }
+
+    /// Fills the frame with a wedge of it mirrored around `center`, like a kaleidoscope.
+    ///
+    /// The frame is split in `segments` wedges of the same angle around `center`. The wedge
+    /// that starts at the positive X axis and goes clockwise, as the Y axis points down, is
+    /// kept and the others are copies of it, every other one mirrored. With an even number of
+    /// segments the edges between wedges are seamless, with an odd number there is a seam on
+    /// the positive X axis. 0 and 1 segments leave the frame as is.
+    ///
+    /// As in [Frame::sample_bilinear], pixel centers are at integer coordinates, so the
+    /// center of the frame is `(size - 1) / 2`. The center can be anywhere, the pixels are
+    /// sampled from a copy of the frame with [AddressMode::Clamp], so the parts of the wedge
+    /// outside of the frame repeat its edges.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{UVec2, Vec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// frame.kaleidoscope(6, (frame.size().as_vec2() - 1.0) / 2.0);
+    /// ```
+    pub fn kaleidoscope(&mut self, segments: u32, center: Vec2) {
+        if segments < 2 {
+            return;
+        }
+        let snapshot = self.pixels.to_vec();
+        let wedge = std::f32::consts::TAU / segments as f32;
+        let width = self.size.x;
+        for (idx, pixel) in self.pixels.iter_mut().enumerate() {
+            let idx = idx as u32;
+            let offset = UVec2::new(idx % width, idx / width).as_vec2() - center;
+            let angle = offset.to_angle().rem_euclid(std::f32::consts::TAU);
+            let segment = (angle / wedge) as u32;
+            let mut source_angle = angle - segment as f32 * wedge;
+            if segment % 2 == 1 {
+                source_angle = wedge - source_angle;
+            }
+            let location = center + Vec2::from_angle(source_angle) * offset.length();
+            *pixel = sample_bilinear(&snapshot, self.size, location, AddressMode::Clamp);
+        }
+    }
+
+    /// Draws an image rotated `angle` radians around its center, placed at `center`.
+    ///
+    /// As in [Frame::sample_bilinear], pixel centers are at integer coordinates, so the
+    /// center of the image is `(size - 1) / 2`. As the Y axis points down, positive angles
+    /// rotate clockwise.
+    ///
+    /// Only the pixels of the frame covered by the rotated image are modified. Each one is
+    /// inverse mapped into the image and sampled with bilinear interpolation, transparent
+    /// outside of the image, and then blended over the frame with [BlendMode::Normal].
+    /// The image is in straight alpha, like the frame.
+    ///
+    /// # Panics
+    /// If the image is not in the [Pixel::FORMAT] format.
+    pub fn stamp_rotated(&mut self, src: &Image, center: Vec2, angle: f32) {
+        let src_pixels = image_pixels(src);
+        let src_size = src.size();
+        let src_center = (src_size.as_vec2() - Vec2::ONE) / 2.0;
+        let inverse = Vec2::from_angle(-angle);
+
+        // the rotated image is inside the circle that contains the whole image
+        let radius = (src_size.as_vec2() / 2.0).length() + 1.0;
+        let min = (center - radius).floor().max(Vec2::ZERO).as_uvec2();
+        let max = (center + radius)
+            .ceil()
+            .max(Vec2::ZERO)
+            .as_uvec2()
+            .min(self.size);
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let pos = UVec2::new(x, y).as_vec2();
+                let src_pos = src_center + inverse.rotate(pos - center);
+                let sample =
+                    sample_bilinear(src_pixels, src_size, src_pos, AddressMode::Transparent);
+                if sample.a > 0 {
+                    let dst = &mut self.pixels[(x + y * self.size.x) as usize];
+                    *dst = dst.blend(sample, BlendMode::Normal);
+                }
+            }
+        }
+    }
+
+    /// Selects the region of similar pixels connected to `start`, like a magic wand.
+    ///
+    /// A pixel is similar if no channel, alpha included, differs more than `tolerance`
+    /// from the pixel at `start`. Pixels are connected horizontally and vertically. The
+    /// frame is not modified.
+    ///
+    /// Returns a mask with one value per pixel, row by row, `true` for the selected ones.
+    /// If `start` is outside of the frame nothing is selected.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 5*5];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(5, 5));
+    /// // split the frame in 2 with a line
+    /// frame.draw_line(IVec2::new(2, 0), IVec2::new(2, 4), Pixel::WHITE);
+    /// let mask = frame.select_contiguous(UVec2::new(0, 0), 0);
+    /// assert_eq!(mask.iter().filter(|selected| **selected).count(), 10);
+    /// ```
+    pub fn select_contiguous(&self, start: UVec2, tolerance: u8) -> Vec<bool> {
+        let mut mask = vec![false; self.pixels.len()];
+        let Some(start_index) = self.index_clipped(start.as_ivec2()) else {
+            return mask;
+        };
+
+        let reference = self.pixels[start_index];
+        let similar = |pixel: Pixel| {
+            let a = [pixel.r, pixel.g, pixel.b, pixel.a];
+            let b = [reference.r, reference.g, reference.b, reference.a];
+            a.iter().zip(b).all(|(a, b)| a.abs_diff(b) <= tolerance)
+        };
+
+        mask[start_index] = true;
+        let mut stack = vec![start.as_ivec2()];
+        while let Some(location) = stack.pop() {
+            for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let neighbor = location + offset;
+                if let Some(index) = self.index_clipped(neighbor) {
+                    if !mask[index] && similar(self.pixels[index]) {
+                        mask[index] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        mask
+    }
+
+    /// Replaces every pixel with the median of its neighborhood, per channel.
+    ///
+    /// The neighborhood is the square of `2 * radius + 1` pixels of side centered on the
+    /// pixel, the edges of the frame are extended, like [AddressMode::Clamp]. Removes
+    /// isolated noisy pixels without blurring the edges like an average does.
+    ///
+    /// The cost grows with the square of the radius, a radius of 1 is usually enough.
+    pub fn median_filter(&mut self, radius: u32) {
+        if radius == 0 {
+            return;
+        }
+        let source = self.pixels.to_vec();
+        let size = self.size.as_ivec2();
+        let radius = radius as i32;
+        let median_index = ((2 * radius + 1).pow(2) / 2) as usize;
+        let mut channels: [Vec<u8>; 4] = Default::default();
+
+        for (idx, pixel) in self.pixels.iter_mut().enumerate() {
+            let pos = IVec2::new(idx as i32 % size.x, idx as i32 / size.x);
+            channels.iter_mut().for_each(Vec::clear);
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let n = (pos + IVec2::new(dx, dy)).clamp(IVec2::ZERO, size - 1);
+                    let p = source[(n.x + n.y * size.x) as usize];
+                    channels[0].push(p.r);
+                    channels[1].push(p.g);
+                    channels[2].push(p.b);
+                    channels[3].push(p.a);
+                }
+            }
+            *pixel = channels
+                .each_mut()
+                .map(|c| *c.select_nth_unstable(median_index).1)
+                .into();
+        }
+    }
+
+    /// Applies a 3x3 convolution kernel to the color channels, like a blur, sharpen or
+    /// emboss.
+    ///
+    /// Each channel is the sum of the neighbours multiplied by the kernel, divided by
+    /// `divisor` and plus `bias`, clamped to `0..=255`. `kernel[1][1]` is the weight of
+    /// the pixel itself, rows are from top to bottom. Pixels outside of the frame are
+    /// clamped to the edge. The alpha is not modified. A `divisor` of 0 is treated as 1.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::WHITE; 10*10];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let box_blur = [[1.0; 3]; 3];
+    /// frame.apply_kernel_3x3(box_blur, 9.0, 0.0);
+    ///
+    /// let sharpen = [[0.0, -1.0, 0.0], [-1.0, 5.0, -1.0], [0.0, -1.0, 0.0]];
+    /// frame.apply_kernel_3x3(sharpen, 1.0, 0.0);
+    /// # assert!(frame.raw().iter().all(|p| *p == Pixel::WHITE));
+    /// ```
+    pub fn apply_kernel_3x3(&mut self, kernel: [[f32; 3]; 3], divisor: f32, bias: f32) {
+        let divisor = if divisor == 0.0 { 1.0 } else { divisor };
+        let source = self.pixels.to_vec();
+        let size = self.size.as_ivec2();
+
+        for (idx, pixel) in self.pixels.iter_mut().enumerate() {
+            let pos = IVec2::new(idx as i32 % size.x, idx as i32 / size.x);
+            let mut sum = Vec3::ZERO;
+            for (dy, row) in (-1..=1).zip(kernel) {
+                for (dx, weight) in (-1..=1).zip(row) {
+                    let n = (pos + IVec2::new(dx, dy)).clamp(IVec2::ZERO, size - 1);
+                    let p = source[(n.x + n.y * size.x) as usize];
+                    sum += weight * Vec3::new(p.r as f32, p.g as f32, p.b as f32);
+                }
+            }
+            let color = (sum / divisor + bias)
+                .round()
+                .clamp(Vec3::ZERO, Vec3::splat(255.0));
+            pixel.r = color.x as u8;
+            pixel.g = color.y as u8;
+            pixel.b = color.z as u8;
+        }
+    }
+
+    /// Blurs the pixels inside a region with a box blur, for example under a brush.
+    ///
+    /// Each pixel in `rect` becomes the average of the square of `2 * radius + 1` pixels of
+    /// side centered on it. The neighbours just outside of `rect` are read but only the
+    /// pixels inside are written, so the cost only depends on the size of the region. The
+    /// edges of the frame are extended, like [AddressMode::Clamp]. The colors are averaged
+    /// weighted by their alpha, so transparent pixels don't darken the result.
+    ///
+    /// The region is clipped to the frame, `rect.max` is exclusive.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{URect, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::WHITE; 64*64];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(64, 64));
+    /// // hide a license plate
+    /// frame.blur_rect(URect::new(20, 40, 44, 48), 4);
+    /// ```
+    pub fn blur_rect(&mut self, rect: URect, radius: u32) {
+        let rect = self.clip_rect(rect);
+        if radius == 0 || rect.is_empty() {
+            return;
+        }
+        let size = self.size.as_ivec2();
+        let radius = radius as i32;
+        let premultiplied = |p: Pixel| {
+            let p = p.as_vec4();
+            (p.truncate() * p.w).extend(p.w)
+        };
+
+        // horizontal pass, on the rows of the region and the ones around it
+        let rows = (rect.min.y as i32 - radius).max(0)..(rect.max.y as i32 + radius).min(size.y);
+        let width = rect.width() as usize;
+        let mut horizontal = Vec::with_capacity(width * rows.len());
+        for y in rows.clone() {
+            for x in rect.min.x as i32..rect.max.x as i32 {
+                let sum: Vec4 = (x - radius..=x + radius)
+                    .map(|nx| {
+                        premultiplied(self.pixels[(nx.clamp(0, size.x - 1) + y * size.x) as usize])
+                    })
+                    .sum();
+                horizontal.push(sum);
+            }
+        }
+
+        // vertical pass, writing only inside the region
+        let count = (2 * radius + 1).pow(2) as f32;
+        for y in rect.min.y as i32..rect.max.y as i32 {
+            for (column, x) in (rect.min.x..rect.max.x).enumerate() {
+                let sum: Vec4 = (y - radius..=y + radius)
+                    .map(|ny| {
+                        let row = (ny.clamp(rows.start, rows.end - 1) - rows.start) as usize;
+                        horizontal[row * width + column]
+                    })
+                    .sum();
+                let pixel = &mut self.pixels[(x as i32 + y * size.x) as usize];
+                *pixel = if sum.w <= 0.0 {
+                    Pixel::TRANSPARENT
+                } else {
+                    Pixel::from_vec4_rounded((sum.truncate() / sum.w).extend(sum.w / count))
+                };
+            }
+        }
+    }
+
+    /// Average value of the pixels inside a region.
+    ///
+    /// The region is clipped to the frame, `rect.max` is exclusive. If the clipped
+    /// region is empty, the result is [Pixel::TRANSPARENT].
+    pub fn region_average(&self, rect: URect) -> Pixel {
+        let mut sum = [0u64; 4];
+        let mut count = 0u64;
+        for row in self.region_rows(rect) {
+            for pixel in row {
+                sum[0] += pixel.r as u64;
+                sum[1] += pixel.g as u64;
+                sum[2] += pixel.b as u64;
+                sum[3] += pixel.a as u64;
+            }
+            count += row.len() as u64;
+        }
+
+        if count == 0 {
+            return Pixel::TRANSPARENT;
+        }
+        // rounded integer division
+        sum.map(|c| ((c + count / 2) / count) as u8).into()
+    }
+
+    /// Iterates over the pixels inside a region, with their location in the frame.
+    ///
+    /// The region is clipped to the frame, `rect.max` is exclusive. The pixels are visited
+    /// row by row.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{URect, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::WHITE; 10*10];
+    /// # let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let brush = URect::new(8, 8, 12, 12);
+    /// let lightness: u32 = frame
+    ///     .iter_region(brush)
+    ///     .map(|(_, pixel)| pixel.luminance() as u32)
+    ///     .sum();
+    /// assert_eq!(lightness, 4 * 255);
+    /// ```
+    pub fn iter_region(&self, rect: URect) -> impl Iterator<Item = (UVec2, Pixel)> + '_ {
+        let rect = self.clip_rect(rect);
+        self.region_rows(rect)
+            .zip(rect.min.y..)
+            .flat_map(move |(row, y)| {
+                row.iter()
+                    .zip(rect.min.x..)
+                    .map(move |(pixel, x)| (UVec2::new(x, y), *pixel))
+            })
+    }
+
+    /// Writes this frame scaled down into `dst`, each pixel of `dst` being the
+    /// [average](Frame::region_average) of a `block` x `block` region of this frame.
+    ///
+    /// Useful for antialiased minimaps. The blocks on the right and bottom edges can be
+    /// smaller when the size doesn't divide evenly, they are the average of the pixels they
+    /// cover. To cover the whole frame `dst` has to be the size divided by `block` rounding
+    /// up, pixels of `dst` outside of that are left as is.
+    ///
+    /// # Panics
+    /// If `block` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::WHITE; 10*10];
+    /// # let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// let mut minimap_pixels = vec![Pixel::BLACK; 3 * 3];
+    /// let mut minimap = Frame::from_raw_parts(&mut minimap_pixels, UVec2::new(3, 3));
+    /// frame.downsample_into(4, &mut minimap);
+    /// assert!(minimap.raw().iter().all(|p| *p == Pixel::WHITE));
+    /// ```
+    pub fn downsample_into(&self, block: u32, dst: &mut Frame) {
+        assert_ne!(block, 0, "the block size can't be 0");
+        let size =
+            UVec2::new(self.size.x.div_ceil(block), self.size.y.div_ceil(block)).min(dst.size);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let min = UVec2::new(x, y) * block;
+                let rect = URect::from_corners(min, min + block);
+                dst.pixels[(x + y * dst.size.x) as usize] = self.region_average(rect);
+            }
+        }
+    }
+
+    /// Counts the pixels inside a region that match a predicate.
+    ///
+    /// The region is clipped to the frame, `rect.max` is exclusive.
+    pub fn region_count_matching(&self, rect: URect, pred: impl Fn(Pixel) -> bool) -> u32 {
+        self.region_rows(rect)
+            .map(|row| row.iter().filter(|p| pred(**p)).count() as u32)
+            .sum()
+    }
+
+    /// Location of the first pixel that matches a predicate, scanning row by row from
+    /// the top left.
+    ///
+    /// Useful to find markers in pixel maps, like a spawn point of a specific color.
+    pub fn find_pixel(&self, pred: impl Fn(Pixel) -> bool) -> Option<UVec2> {
+        self.pixels
+            .iter()
+            .position(|p| pred(*p))
+            .map(|i| UVec2::new(i as u32 % self.size.x, i as u32 / self.size.x))
+    }
+
+    /// Locations of all the pixels that match a predicate, in row-major order.
+    pub fn find_all(&self, pred: impl Fn(Pixel) -> bool) -> Vec<UVec2> {
+        self.pixels
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| pred(**p))
+            .map(|(i, _)| UVec2::new(i as u32 % self.size.x, i as u32 / self.size.x))
+            .collect()
+    }
+
+    /// Pixels that changed since a previous copy of the frame, with their location and
+    /// new value, in row-major order.
+    ///
+    /// `previous` are the raw RGBA bytes of the frame, for example from
+    /// `bytemuck::cast_slice(frame.raw()).to_vec()`. It has to have the length of the
+    /// frame, otherwise [FrameError::LengthMismatch] is returned. Useful to send only the
+    /// changes over the network.
+    pub fn diff_pixels<'b>(
+        &'b self,
+        previous: &'b [u8],
+    ) -> Result<impl Iterator<Item = (UVec2, Pixel)> + 'b, FrameError> {
+        let expected = std::mem::size_of_val(self.pixels);
+        if previous.len() != expected {
+            return Err(FrameError::LengthMismatch {
+                expected,
+                found: previous.len(),
+            });
+        }
+        let previous: &[Pixel] = bytemuck::cast_slice(previous);
+        let width = self.size.x;
+        Ok(self
+            .pixels
+            .iter()
+            .zip(previous)
+            .enumerate()
+            .filter(|(_, (pixel, previous))| pixel != previous)
+            .map(move |(i, (pixel, _))| (UVec2::new(i as u32 % width, i as u32 / width), *pixel)))
+    }
+
+    /// Text preview of the frame, for logs and terminals without a window.
+    ///
+    /// The frame is downsampled to `width` characters per line, averaging the pixels under
+    /// each character with [Frame::region_average]. Characters are about twice as tall as
+    /// wide, so there are half as many lines as needed to keep the aspect ratio with
+    /// square cells. The luminance of each cell, darkened by its alpha, picks a character
+    /// from ` .:-=+*#%@`, from dark to light. Every line ends with a newline.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 8 * 4];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(8, 4));
+    /// frame.draw_line((0, 0), (7, 0), Pixel::WHITE);
+    /// assert_eq!(frame.to_ascii(8), "++++++++\n        \n");
+    /// ```
+    pub fn to_ascii(&self, width: u32) -> String {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+        if width == 0 || self.size.cmpeq(UVec2::ZERO).any() {
+            return String::new();
+        }
+        let size = self.size.as_u64vec2();
+        // character cells are about twice as tall as wide
+        let height = ((size.y * width as u64 + size.x) / (2 * size.x)).max(1);
+        let cells = U64Vec2::new(width as u64, height);
+        let edge = |cell: U64Vec2| (cell * size / cells).as_uvec2();
+
+        let mut ascii = String::with_capacity(((width as u64 + 1) * height) as usize);
+        for y in 0..height {
+            for x in 0..width as u64 {
+                let min = edge(U64Vec2::new(x, y));
+                let max = edge(U64Vec2::new(x + 1, y + 1)).max(min + 1);
+                let value = self
+                    .region_average(URect::from_corners(min, max))
+                    .premultiplied()
+                    .luminance();
+                ascii.push(RAMP[value as usize * RAMP.len() / 256] as char);
+            }
+            ascii.push('\n');
+        }
+        ascii
+    }
+
+    /// Minimum, maximum and mean of each channel, and the number of fully transparent and
+    /// fully opaque pixels, in a single pass.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{UVec2, Vec4};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::RED, Pixel::TRANSPARENT];
+    /// # let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 1));
+    /// let stats = frame.stats();
+    /// assert_eq!(stats.max, Pixel::RED);
+    /// assert_eq!(stats.mean, Vec4::new(127.5, 0.0, 0.0, 127.5));
+    /// assert_eq!((stats.transparent, stats.opaque), (1, 1));
+    /// ```
+    pub fn stats(&self) -> FrameStats {
+        if self.pixels.is_empty() {
+            return FrameStats::default();
+        }
+
+        let mut min = [u8::MAX; 4];
+        let mut max = [0u8; 4];
+        let mut sum = [0u64; 4];
+        let mut transparent = 0;
+        let mut opaque = 0;
+        for pixel in self.pixels.iter() {
+            let channels = [pixel.r, pixel.g, pixel.b, pixel.a];
+            for i in 0..4 {
+                min[i] = min[i].min(channels[i]);
+                max[i] = max[i].max(channels[i]);
+                sum[i] += channels[i] as u64;
+            }
+            match pixel.a {
+                0 => transparent += 1,
+                255 => opaque += 1,
+                _ => {}
+            }
+        }
+
+        let count = self.pixels.len() as f64;
+        FrameStats {
+            min: min.into(),
+            max: max.into(),
+            mean: Vec4::from_array(sum.map(|c| (c as f64 / count) as f32)),
+            transparent,
+            opaque,
+        }
+    }
+
+    /// Swaps two pixels. Does nothing if either location is outside the frame.
+    pub fn swap_pixels(&mut self, a: UVec2, b: UVec2) {
+        if let (Some(a), Some(b)) = (
+            self.index_clipped(a.as_ivec2()),
+            self.index_clipped(b.as_ivec2()),
+        ) {
+            self.pixels.swap(a, b);
+        }
+    }
+
+    /// Copies a region of the frame to another location of the same frame.
+    ///
+    /// `dst` is the top left corner of the destination. The source and destination can
+    /// overlap, for example to scroll the frame by a few pixels without a temporary buffer.
+    /// Both are clipped to the frame, `src_rect.max` is exclusive.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{URect, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::RED, Pixel::BLUE, Pixel::BLACK];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 1));
+    /// // scroll one pixel to the right
+    /// frame.copy_within(URect::new(0, 0, 2, 1), UVec2::new(1, 0));
+    /// assert_eq!(frame.raw(), &[Pixel::RED, Pixel::RED, Pixel::BLUE]);
+    /// ```
+    pub fn copy_within(&mut self, src_rect: URect, dst: UVec2) {
+        let src = self.clip_rect(src_rect);
+        let size = src
+            .max
+            .saturating_sub(src.min)
+            .min(self.size.saturating_sub(dst));
+        if size.cmpeq(UVec2::ZERO).any() {
+            return;
+        }
+        let width = self.size.x as usize;
+        // copying downwards starts from the last row to not overwrite rows not copied yet,
+        // the overlap inside a row is handled by `copy_within`
+        let downwards = dst.y > src.min.y;
+        for i in 0..size.y {
+            let y = if downwards { size.y - 1 - i } else { i };
+            let from = (src.min.y + y) as usize * width + src.min.x as usize;
+            let to = (dst.y + y) as usize * width + dst.x as usize;
+            self.pixels.copy_within(from..from + size.x as usize, to);
+        }
+    }
+
+    /// Clips a region to the bounds of the frame
+    fn clip_rect(&self, rect: URect) -> URect {
+        rect.intersect(URect::from_corners(UVec2::ZERO, self.size))
+    }
+
+    /// Rows of pixels inside a region, clipped to the frame
+    fn region_rows(&self, rect: URect) -> impl Iterator<Item = &[Pixel]> {
+        let rect = self.clip_rect(rect);
+        let width = self.size.x as usize;
+        (rect.min.y..rect.max.y).map(move |y| {
+            let start = y as usize * width;
+            &self.pixels[start + rect.min.x as usize..start + rect.max.x as usize]
+        })
+    }
+
+    /// Range of a row in the pixels, if it is inside the frame
+    fn row_range(&self, y: u32) -> Option<std::ops::Range<usize>> {
+        if y >= self.size.y {
+            return None;
+        }
+        let start = y as usize * self.size.x as usize;
+        Some(start..start + self.size.x as usize)
+    }
+
+    /// Indices of a column in the pixels, `x` has to be inside the frame
+    fn column_indices(&self, x: u32) -> impl Iterator<Item = usize> {
+        (x as usize..self.pixels.len()).step_by(self.size.x as usize)
+    }
+
+    /// Index of a location in the pixels, if it is inside the frame
+    fn index_clipped(&self, location: IVec2) -> Option<usize> {
+        if location.cmpge(IVec2::ZERO).all() && location.as_uvec2().cmplt(self.size).all() {
+            Some((location.x as u32 + location.y as u32 * self.size.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Sets a pixel if the location is inside the frame
+    pub(crate) fn set_clipped(&mut self, location: IVec2, pixel: Pixel) {
+        if let Some(index) = self.index_clipped(location) {
+            self.pixels[index] = pixel;
+        }
+    }
+
+    pub(crate) fn check_same_size(&self, size: UVec2) -> FrameResult {
+        if size != self.size {
+            Err(FrameError::SizeMismatch {
+                expected: self.size,
+                found: size,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_bounds(&self, location: UVec2) -> FrameResult {
+        if location.x >= self.size.x || location.y >= self.size.y {
+            Err(FrameError::LocationOutOfBounds {
+                location,
+                size: self.size,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Lowest and highest levels of a histogram, ignoring `percentile` percent of the count
+/// at each end
+fn histogram_levels(histogram: &[u32; 256], percentile: f32) -> (u8, u8) {
+    let count: u32 = histogram.iter().sum();
+    let cut = (count as f32 * percentile / 100.0) as u32;
+    let low = first_level_over(histogram, cut, 0..256);
+    let high = first_level_over(histogram, cut, (0..256).rev());
+    (low, high.max(low))
+}
+
+/// First level in `levels` where the accumulated count goes over `cut`
+fn first_level_over(
+    histogram: &[u32; 256],
+    cut: u32,
+    mut levels: impl Iterator<Item = usize>,
+) -> u8 {
+    let mut seen = 0;
+    levels
+        .find(|&level| {
+            seen += histogram[level];
+            seen > cut
+        })
+        .unwrap_or(0) as u8
+}
+
+/// Remaps `low..=high` to the full range, the identity if there's a single level
+fn stretch_level(value: u8, low: u8, high: u8) -> u8 {
+    if low >= high {
+        return value;
+    }
+    let stretched = (value.saturating_sub(low) as f32 * 255.0 / (high - low) as f32).round();
+    stretched.min(255.0) as u8
+}
+
+/// What [Frame::auto_levels] stretches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelsMode {
+    /// Each color channel independently
+    #[default]
+    Channels,
+    /// The luminance, keeping the colors
+    Luminance,
+}
+
+/// How locations outside of a frame are resolved when reading it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddressMode {
+    /// Locations outside of the frame have no pixel, they are transparent when sampling
+    #[default]
+    Transparent,
+    /// Locations are clamped to the edges of the frame
+    Clamp,
+    /// The frame repeats, like a torus
+    Wrap,
+    /// The frame repeats, mirrored every time
+    Mirror,
+}
+
+impl AddressMode {
+    /// Resolves a location to one inside of a frame of the given size.
+    ///
+    /// Returns [None] if the location cannot be resolved, which only happens for
+    /// [AddressMode::Transparent] or an empty size.
+    pub fn resolve(self, location: IVec2, size: UVec2) -> Option<UVec2> {
+        if size.cmpeq(UVec2::ZERO).any() {
+            return None;
+        }
+        let size = size.as_ivec2();
+        let location = match self {
+            AddressMode::Transparent => {
+                if location.cmplt(IVec2::ZERO).any() || location.cmpge(size).any() {
+                    return None;
+                }
+                location
+            }
+            AddressMode::Clamp => location.clamp(IVec2::ZERO, size - 1),
+            AddressMode::Wrap => location.rem_euclid(size),
+            AddressMode::Mirror => {
+                let period = location.rem_euclid(size * 2);
+                IVec2::select(period.cmplt(size), period, size * 2 - 1 - period)
+            }
+        };
+        Some(location.as_uvec2())
+    }
+}
+
+/// See [Frame::get_pixel]
+fn get_pixel(
+    pixels: &[Pixel],
+    size: UVec2,
+    location: IVec2,
+    address: AddressMode,
+) -> Option<Pixel> {
+    address
+        .resolve(location, size)
+        .map(|location| pixels[(location.x + location.y * size.x) as usize])
+}
+
+/// See [Frame::sample]
+fn sample(pixels: &[Pixel], size: UVec2, location: IVec2, address: AddressMode) -> Pixel {
+    get_pixel(pixels, size, location, address).unwrap_or(Pixel::TRANSPARENT)
+}
+
+/// See [Frame::for_each_neighbor]
+fn for_each_neighbor(
+    pixels: &[Pixel],
+    size: UVec2,
+    location: IVec2,
+    address: AddressMode,
+    mut f: impl FnMut(IVec2, Pixel),
+) {
+    for y in -1..=1 {
+        for x in -1..=1 {
+            let offset = IVec2::new(x, y);
+            if offset != IVec2::ZERO {
+                f(offset, sample(pixels, size, location + offset, address));
+            }
+        }
+    }
+}
+
+/// See [Frame::sample_bilinear]
+fn sample_bilinear(pixels: &[Pixel], size: UVec2, location: Vec2, address: AddressMode) -> Pixel {
+    let base = location.floor();
+    let t = location - base;
+    let base = base.as_ivec2();
+
+    let mut color = Vec3::ZERO;
+    let mut alpha = 0.0;
+    for (offset, weight) in [
+        (IVec2::new(0, 0), (1.0 - t.x) * (1.0 - t.y)),
+        (IVec2::new(1, 0), t.x * (1.0 - t.y)),
+        (IVec2::new(0, 1), (1.0 - t.x) * t.y),
+        (IVec2::new(1, 1), t.x * t.y),
+    ] {
+        if let Some(pixel) = get_pixel(pixels, size, base + offset, address) {
+            let pixel = pixel.as_vec4();
+            color += pixel.truncate() * pixel.w * weight;
+            alpha += pixel.w * weight;
+        }
+    }
+
+    if alpha <= 0.0 {
+        return Pixel::TRANSPARENT;
+    }
+    Pixel::from_vec4_rounded((color / alpha).extend(alpha))
+}
+
+/// Bresenham's line algorithm, both ends included.
+fn line_points(from: IVec2, to: IVec2) -> impl Iterator<Item = IVec2> {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut error = delta.x - delta.y;
+    let mut current = from;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let point = current;
+        if current == to {
+            done = true;
+        } else {
+            let e2 = 2 * error;
+            if e2 > -delta.y {
+                error -= delta.y;
+                current.x += step.x;
+            }
+            if e2 < delta.x {
+                error += delta.x;
+                current.y += step.y;
+            }
+        }
+        Some(point)
+    })
+}
+
+/// Midpoint circle algorithm, the points of the 8 octants.
+///
+/// Points on the boundaries between octants are repeated.
+fn circle_points(center: IVec2, radius: u32) -> impl Iterator<Item = IVec2> {
+    let mut offset = IVec2::new(radius as i32, 0);
+    let mut error = 1 - radius as i32;
+
+    std::iter::from_fn(move || {
+        if offset.y > offset.x {
+            return None;
+        }
+        let IVec2 { x, y } = offset;
+        offset.y += 1;
+        if error < 0 {
+            error += 2 * offset.y + 1;
+        } else {
+            offset.x -= 1;
+            error += 2 * (offset.y - offset.x) + 1;
+        }
+        Some([
+            (x, y),
+            (y, x),
+            (-y, x),
+            (-x, y),
+            (-x, -y),
+            (-y, -x),
+            (y, -x),
+            (x, -y),
+        ])
+    })
+    .flatten()
+    .map(move |offset| center + IVec2::from(offset))
+}
+
+/// Tests if an offset from the center is inside the angles of an arc, see [Frame::draw_arc]
+fn arc_test(start_deg: f32, end_deg: f32) -> impl Fn(IVec2) -> bool {
+    let full = end_deg - start_deg >= 360.0;
+    let sweep = (end_deg - start_deg).rem_euclid(360.0);
+    move |offset| {
+        let offset = offset.as_vec2();
+        let angle = offset.y.atan2(offset.x).to_degrees();
+        full || (angle - start_deg).rem_euclid(360.0) <= sweep
+    }
+}
+
+/// CbCr components of the Rec. 709 YCbCr color space
+fn chroma(pixel: Pixel) -> Vec2 {
+    let c = pixel.as_vec4();
+    let y = 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z;
+    Vec2::new((c.z - y) / 1.8556, (c.x - y) / 1.5748)
+}
+
+/// A [Frame] with a rotated coordinate space.
+///
+/// Obtained with [Frame::rotated].
+pub struct RotatedFrame<'a> {
+    frame: Frame<'a>,
+    rotation: Vec2,
+    center: Vec2,
+}
+
+impl<'a> RotatedFrame<'a> {
+    /// Transforms a location in the rotated space to the nearest pixel in the frame.
+    pub fn transform(&self, location: Vec2) -> IVec2 {
+        (self.center + self.rotation.rotate(location - self.center))
+            .round()
+            .as_ivec2()
+    }
+
+    /// Sets a pixel in the frame
+    pub fn set(&mut self, location: impl Into<Vec2>, pixel: impl Into<Pixel>) -> FrameResult {
+        let location = self.transform(location.into());
+        // negative values wrap around and are reported as out of bounds
+        self.frame.set(location.as_uvec2(), pixel)
+    }
+
+    /// Draws a line between 2 points, both included. See [Frame::draw_line].
+    pub fn draw_line(
+        &mut self,
+        from: impl Into<Vec2>,
+        to: impl Into<Vec2>,
+        pixel: impl Into<Pixel>,
+    ) {
+        let from = self.transform(from.into());
+        let to = self.transform(to.into());
+        self.frame.draw_line(from, to, pixel);
+    }
+
+    /// Access the underlying, not rotated, frame
+    pub fn frame(&mut self) -> &mut Frame<'a> {
+        &mut self.frame
+    }
+}
+
+/// Summary of the pixels of a frame, see [Frame::stats].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameStats {
+    /// Minimum value of each channel
+    pub min: Pixel,
+    /// Maximum value of each channel
+    pub max: Pixel,
+    /// Mean value of each channel, in the `0.0..=255.0` range
+    pub mean: Vec4,
+    /// Number of pixels with an alpha of 0
+    pub transparent: u32,
+    /// Number of pixels with an alpha of 255
+    pub opaque: u32,
+}
+
+/// All zeros, the stats of an empty frame
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self {
+            min: Pixel::TRANSPARENT,
+            max: Pixel::TRANSPARENT,
+            mean: Vec4::ZERO,
+            transparent: 0,
+            opaque: 0,
+        }
+    }
+}
+
+/// Result type for some methods of [Frame]
+pub type FrameResult = Result<(), FrameError>;
+
+/// Error type for some methods of [Frame]
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum FrameError {
+    /// The location is not inside the frame
+    #[error(
+        "location out of the bounds of the frame (location: {location:?}, frame size: {size:?}"
+    )]
+    LocationOutOfBounds {
+        /// wrong location
+        location: UVec2,
+        /// frame size
+        size: UVec2,
+    },
+    /// Another buffer does not have the same size as the frame
+    #[error("size mismatch (frame size: {expected:?}, other size: {found:?})")]
+    SizeMismatch {
+        /// frame size
+        expected: UVec2,
+        /// size of the other buffer
+        found: UVec2,
+    },
+    /// The data to copy into the frame does not have the same length as the frame
+    #[error("length mismatch (frame length: {expected}, data length: {found})")]
+    LengthMismatch {
+        /// frame length
+        expected: usize,
+        /// data length
+        found: usize,
+    },
+    /// An image is not in the [Pixel::FORMAT] format
+    #[error("format mismatch (expected: {expected:?}, found: {found:?})")]
+    FormatMismatch {
+        /// [Pixel::FORMAT]
+        expected: TextureFormat,
+        /// format of the image
+        found: TextureFormat,
+    },
+}
+
+impl<'a> Frame<'a> {
+    /// Builds a frame from a bevy image
+    pub fn get(image: &'a mut Image) -> Self {
+        debug_assert_eq!(image.texture_descriptor.format, Pixel::FORMAT);
+        debug_assert!(image
+            .texture_descriptor
+            .usage
+            .contains(TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST));
+        let size = image.size();
+        let pixels = bytemuck::cast_slice_mut(&mut image.data);
+        Self {
+            pixels,
+            size,
+            edge_mode: AddressMode::default(),
+        }
+    }
+
+    /// Builds a frame by extracting a bevy image from the assets.
+    pub fn extract(images: &'a mut Assets<Image>, image_handle: &Handle<Image>) -> Self {
+        Self::get(
+            images
+                .get_mut(image_handle)
+                .expect("image when building frame"),
+        )
+    }
+
+    /// Constructs a frame from raw parts
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::UVec2;
+    /// # use bevy_pixel_buffer::prelude::{Frame, Pixel};
+    /// let mut pixels = vec![Pixel::BLACK; 10*10];
+    /// let frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+    /// ```
+    ///
+    /// # Panics
+    /// If the length of the slice does not correspond with the given size
+    pub fn from_raw_parts(pixels: &'a mut [Pixel], size: UVec2) -> Self {
+        assert_eq!(pixels.len(), (size.x * size.y) as usize);
+        Self {
+            pixels,
+            size,
+            edge_mode: AddressMode::default(),
+        }
+    }
+
+    /// Copies the pixels into an [OwnedFrame], that can be sent to another thread.
+    pub fn snapshot(&self) -> OwnedFrame {
+        OwnedFrame {
+            pixels: self.pixels.to_vec(),
+            size: self.size,
+            edge_mode: self.edge_mode,
+        }
+    }
+
+    /// Overwrites the pixels with a snapshot. The snapshot has to be the same size.
+    pub fn apply_snapshot(&mut self, snapshot: &OwnedFrame) -> FrameResult {
+        self.check_same_size(snapshot.size)?;
+        self.pixels.copy_from_slice(&snapshot.pixels);
+        Ok(())
+    }
+}
+
+/// Copy of the pixels of a [Frame] that owns them.
+///
+/// Frames borrow the image, so they cannot be sent to an async task, like one of the
+/// [AsyncComputeTaskPool](bevy::tasks::AsyncComputeTaskPool). Take a
+/// [snapshot](Frame::snapshot), process it in the task with [OwnedFrame::frame], and
+/// when it is done apply it back with [Frame::apply_snapshot].
+///
+/// # Example
+/// ```
+/// # use bevy::{prelude::*, tasks::{block_on, AsyncComputeTaskPool}};
+/// # use bevy_pixel_buffer::prelude::*;
+/// # AsyncComputeTaskPool::get_or_init(Default::default);
+/// # let mut pixels = vec![Pixel::BLACK; 10*10];
+/// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+/// let mut snapshot = frame.snapshot();
+/// let task = AsyncComputeTaskPool::get().spawn(async move {
+///     snapshot.frame().per_pixel(|_, _| Pixel::WHITE);
+///     snapshot
+/// });
+/// // later, for example polling the task in a system
+/// let snapshot = block_on(task);
+/// frame.apply_snapshot(&snapshot).unwrap();
+/// assert_eq!(frame.raw()[0], Pixel::WHITE);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedFrame {
+    pixels: Vec<Pixel>,
+    size: UVec2,
+    edge_mode: AddressMode,
+}
+
+impl OwnedFrame {
+    pub(crate) fn from_pixels(pixels: Vec<Pixel>, size: UVec2) -> Self {
+        debug_assert_eq!(pixels.len(), size.x as usize * size.y as usize);
+        Self {
+            pixels,
+            size,
+            edge_mode: AddressMode::default(),
+        }
+    }
+
+    /// Gets the size
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Access the pixels directly
+    pub fn raw(&self) -> &[Pixel] {
+        &self.pixels
+    }
+
+    /// Edits the pixels with a [Frame], with the same [edge mode](Frame::with_edge_mode)
+    pub fn frame(&mut self) -> Frame<'_> {
+        Frame::from_raw_parts(&mut self.pixels, self.size).with_edge_mode(self.edge_mode)
+    }
+
+    /// Gets a pixel like [Frame::sample], with the edge mode of the frame it was taken from
+    pub fn sample(&self, location: IVec2) -> Pixel {
+        sample(&self.pixels, self.size, location, self.edge_mode)
+    }
+
+    /// Visits the neighbors of a location like [Frame::for_each_neighbor], with the edge
+    /// mode of the frame it was taken from.
+    ///
+    /// A snapshot taken before updating a frame is the previous state of a simulation, so
+    /// every cell is updated from the same state.
+    pub fn for_each_neighbor(&self, location: IVec2, f: impl FnMut(IVec2, Pixel)) {
+        for_each_neighbor(&self.pixels, self.size, location, self.edge_mode, f);
+    }
+}
+
+/// Bytes of an image in the [Pixel::FORMAT] format with the pixels given by a function.
+///
+/// `f` is called with the location of every pixel, row by row from the top left. The bytes
+/// can be used as the data of an image from [create_image](crate::pixel_buffer::create_image)
+/// of the same size.
+///
+/// # Example
+/// ```
+/// # use bevy::math::UVec2;
+/// # use bevy_pixel_buffer::{frame::frame_bytes_from_fn, pixel_buffer::create_image, prelude::*};
+/// let size = UVec2::new(64, 64);
+/// let mut image = create_image(size.into());
+/// image.data = frame_bytes_from_fn(size, |pos| Pixel::from([pos.x as u8 * 4, 0, 0, 255]));
+/// ```
+pub fn frame_bytes_from_fn(size: UVec2, mut f: impl FnMut(UVec2) -> Pixel) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity((size.x * size.y) as usize * size_of::<Pixel>());
+    for y in 0..size.y {
+        for x in 0..size.x {
+            bytes.extend_from_slice(bytemuck::bytes_of(&f(UVec2::new(x, y))));
+        }
+    }
+    bytes
+}
+
+/// Frames of several images edited at the same time.
+///
+/// A [Frame] borrows the image [assets](Assets) mutably, so only one can exist at a time.
+/// The batch takes the pixels of the images out of the assets when they are
+/// [added](FrameBatch::add), so all its [frames](FrameBatch::frames) can be used
+/// together, for example to copy between buffers. The pixels are put back when the batch
+/// is dropped. Each image is marked as modified and uploaded to the GPU once at the end
+/// of the frame, like any other change.
+///
+/// While the batch exists, the images in it have no pixels in the assets.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_buffer::prelude::*;
+/// fn mirror(
+///     mut images: ResMut<Assets<Image>>,
+///     pixel_buffers: Query<&Sprite, With<PixelBuffer>>,
+/// ) {
+///     let mut batch = FrameBatch::new(&mut images);
+///     for sprite in pixel_buffers.iter() {
+///         batch.add(&sprite.image);
+///     }
+///     if let [source, rest @ ..] = batch.frames().as_mut_slice() {
+///         for frame in rest {
+///             let _ = frame.copy_from_pixels(source.raw());
+///         }
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(mirror);
+/// ```
+pub struct FrameBatch<'a> {
+    images: &'a mut Assets<Image>,
+    taken: Vec<TakenImage>,
+}
+
+/// Pixels taken out of an image of the assets by a [FrameBatch]
+struct TakenImage {
+    id: AssetId<Image>,
+    size: UVec2,
+    data: Vec<u8>,
+}
+
+impl<'a> FrameBatch<'a> {
+    /// Creates an empty batch
+    pub fn new(images: &'a mut Assets<Image>) -> Self {
+        Self {
+            images,
+            taken: Vec::new(),
+        }
+    }
+
+    /// Adds an image to the batch and returns the index of its frame.
+    ///
+    /// Adding an image again returns the same index. Returns [None] if the image is not
+    /// in the assets.
+    pub fn add(&mut self, image_handle: impl AsImageHandle) -> Option<usize> {
+        let id = image_handle.as_image_handle().id();
+        if let Some(index) = self.taken.iter().position(|taken| taken.id == id) {
+            return Some(index);
+        }
+        let image = self.images.get_mut(id)?;
+        debug_assert_eq!(image.texture_descriptor.format, Pixel::FORMAT);
+        self.taken.push(TakenImage {
+            id,
+            size: image.size(),
+            data: std::mem::take(&mut image.data),
+        });
+        Some(self.taken.len() - 1)
+    }
+
+    /// Number of images in the batch
+    pub fn len(&self) -> usize {
+        self.taken.len()
+    }
+
+    /// If the batch has no images
+    pub fn is_empty(&self) -> bool {
+        self.taken.is_empty()
+    }
+
+    /// Frame of the image at `index`, see [FrameBatch::add]
+    pub fn frame(&mut self, index: usize) -> Option<Frame<'_>> {
+        self.taken.get_mut(index).map(TakenImage::frame)
+    }
+
+    /// Frames of all the images, in the order they were added
+    pub fn frames(&mut self) -> Vec<Frame<'_>> {
+        self.taken.iter_mut().map(TakenImage::frame).collect()
+    }
+}
+
+impl TakenImage {
+    fn frame(&mut self) -> Frame<'_> {
+        Frame::from_raw_parts(bytemuck::cast_slice_mut(&mut self.data), self.size)
+    }
+}
+
+impl Drop for FrameBatch<'_> {
+    fn drop(&mut self) {
+        for taken in self.taken.drain(..) {
+            if let Some(image) = self.images.get_mut(taken.id) {
+                image.data = taken.data;
+            }
+        }
+    }
+}
+
+/// Convenience trait to get a [Frame]
+pub trait GetFrame {
+    /// Get a frame to mutate a pixel buffer
+    fn frame(&mut self) -> Frame<'_>;
+}
+
+impl GetFrame for Image {
+    #[inline(always)]
+    fn frame(&mut self) -> Frame<'_> {
+        Frame::get(self)
+    }
+}
+
+/// Convenience trait to get a [Frame] from a [Handle] needs the [image](Image) [assets](Assets).
+pub trait GetFrameFromHandle: AsImageHandle {
+    /// Get a frame to mutate a pixel buffer
+    fn frame<'a>(&self, images: &'a mut Assets<Image>) -> Frame<'a> {
+        Frame::extract(images, self.as_image_handle())
+    }
+}
+
+impl<T: AsImageHandle> GetFrameFromHandle for T {}
+
+/// Convenience trait to get a frame from the [image](Image) [assets](Assets) with a [Handle]
+pub trait GetFrameFromImages: AsMut<Assets<Image>> {
+    /// Get a frame to mutate a pixel buffer
+    fn frame(&mut self, image_handle: impl AsImageHandle) -> Frame<'_> {
+        Frame::extract(self.as_mut(), image_handle.as_image_handle())
+    }
+}
+
+impl<T: AsMut<Assets<Image>>> GetFrameFromImages for T {}
+
+/// Used to get a reference to a [image](Image) [handle](Handle).
+///
+/// This is a workaround until `impl<T> AsRef<T> for &T` is stabilized.
+pub trait AsImageHandle {
+    /// Get a image handle from the type
+    fn as_image_handle(&self) -> &Handle<Image>;
+}
+
+impl AsImageHandle for Handle<Image> {
+    fn as_image_handle(&self) -> &Handle<Image> {
+        self
+    }
+}
+
+impl AsImageHandle for &Handle<Image> {
+    fn as_image_handle(&self) -> &Handle<Image> {
+        self
+    }
+}
+
+/// Convenience trait to modify elements with a [Frame]
+pub trait FrameEditExtension: GetFrame {
+    /// Runs a given closure to modify the buffer.
+    fn edit_frame(&mut self, f: impl Fn(&mut Frame)) {
+        f(&mut self.frame())
+    }
+}
+
+impl<T: GetFrame> FrameEditExtension for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_buffer::create_image;
+
+    fn frame_pixels(size: UVec2) -> Vec<Pixel> {
+        vec![Pixel::TRANSPARENT; (size.x * size.y) as usize]
+    }
+
+    #[test]
+    fn rotated_line() {
+        let size = UVec2::new(9, 9);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        frame
+            .rotated(std::f32::consts::FRAC_PI_2, Vec2::new(4.0, 4.0))
+            .draw_line(Vec2::new(2.0, 4.0), Vec2::new(6.0, 4.0), Pixel::RED);
+
+        for (idx, pixel) in frame.raw().iter().enumerate() {
+            let (x, y) = (idx as u32 % size.x, idx as u32 / size.x);
+            let expected = if x == 4 && (2..=6).contains(&y) {
+                Pixel::RED
+            } else {
+                Pixel::TRANSPARENT
+            };
+            assert_eq!(*pixel, expected, "pixel at ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn region_stats() {
+        let size = UVec2::new(4, 4);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        // left half red, right half blue
+        frame.per_pixel(|pos, _| if pos.x < 2 { Pixel::RED } else { Pixel::BLUE });
+
+        let top_left = URect::new(0, 0, 2, 2);
+        assert_eq!(frame.region_average(top_left), Pixel::RED);
+
+        let top = URect::new(0, 0, 4, 2);
+        assert_eq!(frame.region_average(top), Pixel::from([128, 0, 128, 255]));
+        assert_eq!(frame.region_count_matching(top, |p| p == Pixel::BLUE), 4);
+
+        // clipped to the frame
+        let overflowing = URect::new(3, 3, 10, 10);
+        assert_eq!(frame.region_average(overflowing), Pixel::BLUE);
+        assert_eq!(frame.region_count_matching(overflowing, |_| true), 1);
+        assert_eq!(
+            frame.region_average(URect::new(8, 8, 10, 10)),
+            Pixel::TRANSPARENT
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let size = UVec2::new(3, 2);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        frame.row_mut(1).unwrap().fill(Pixel::RED);
+        frame.set_column(2, &[Pixel::BLUE, Pixel::GREEN]).unwrap();
+
+        assert_eq!(
+            frame.row(1).unwrap(),
+            &[Pixel::RED, Pixel::RED, Pixel::GREEN]
+        );
+        assert_eq!(
+            frame.column(0).unwrap(),
+            vec![Pixel::TRANSPARENT, Pixel::RED]
+        );
+        assert!(frame.row(2).is_none());
+        assert!(frame.row_mut(2).is_none());
+        assert!(frame.column(3).is_none());
+        assert!(matches!(
+            frame.set_column(3, &[Pixel::RED; 2]),
+            Err(FrameError::LocationOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            frame.set_column(0, &[Pixel::RED; 3]),
+            Err(FrameError::SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn premultiply_round_trip() {
+        let size = UVec2::new(16, 16);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.per_pixel(|pos, _| [(pos.x * 17) as u8, (pos.y * 17) as u8, 200, 255]);
+        let original = frame.raw().to_vec();
+
+        frame.premultiply_all();
+        frame.unpremultiply_all();
+        assert_eq!(frame.raw(), original);
+
+        frame.per_pixel(|_, _| [200, 100, 50, 128]);
+        frame.premultiply_all();
+        assert!(frame
+            .raw()
+            .iter()
+            .all(|p| *p == Pixel::from([100, 50, 25, 128])));
+        frame.unpremultiply_all();
+        for pixel in frame.raw() {
+            assert!(pixel.r.abs_diff(200) <= 1 && pixel.g.abs_diff(100) <= 1);
+            assert!(pixel.b.abs_diff(50) <= 1 && pixel.a == 128);
+        }
+    }
+
+    #[test]
+    fn arcs() {
+        let size = UVec2::new(17, 17);
+        let center = IVec2::new(8, 8);
+        let mut circle = frame_pixels(size);
+        Frame::from_raw_parts(&mut circle, size).draw_circle(center, 6, Pixel::RED);
+
+        let mut arc = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut arc, size);
+        frame.draw_arc(center, 6, 0.0, 360.0, Pixel::RED);
+        assert_eq!(frame.raw(), circle);
+
+        frame.per_pixel(|_, _| Pixel::TRANSPARENT);
+        frame.draw_arc(center, 6, 0.0, 90.0, Pixel::RED);
+        for (idx, (pixel, circle)) in frame.raw().iter().zip(&circle).enumerate() {
+            let (x, y) = (idx as i32 % 17, idx as i32 / 17);
+            // bottom right quadrant, as angles are clockwise
+            let expected = if x >= 8 && y >= 8 {
+                *circle
+            } else {
+                Pixel::TRANSPARENT
+            };
+            assert_eq!(*pixel, expected, "pixel at ({x}, {y})");
+        }
+        assert_eq!(frame.raw()[8 * 17 + 14], Pixel::RED);
+        assert_eq!(frame.raw()[14 * 17 + 8], Pixel::RED);
+    }
+
+    #[test]
+    fn alpha_mask() {
+        let size = UVec2::new(4, 2);
+        let mut mask_pixels = frame_pixels(size);
+        let mut mask = Frame::from_raw_parts(&mut mask_pixels, size);
+        mask.per_pixel(|pos, _| {
+            let v = (pos.x * 85) as u8;
+            [v, v, v, 255]
+        });
+
+        let mut pixels = vec![Pixel::RED; 8];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.apply_alpha_mask(&mask).unwrap();
+
+        let alphas: Vec<u8> = frame.row(1).unwrap().iter().map(|p| p.a).collect();
+        assert_eq!(alphas, vec![0, 85, 170, 255]);
+        assert!(frame.raw().iter().all(|p| p.r == 255 && p.g == 0));
+
+        let mut small = vec![Pixel::WHITE; 2];
+        assert!(matches!(
+            frame.apply_alpha_mask(&Frame::from_raw_parts(&mut small, UVec2::new(2, 1))),
+            Err(FrameError::SizeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn alpha_predicates() {
+        let size = UVec2::new(3, 3);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        assert!(frame.is_fully_transparent());
+        assert!(!frame.is_fully_opaque());
+
+        frame.per_pixel(|_, _| Pixel::BLUE);
+        assert!(!frame.is_fully_transparent());
+        assert!(frame.is_fully_opaque());
+
+        frame.set((2, 2), [0, 0, 255, 128]).unwrap();
+        assert!(!frame.is_fully_transparent());
+        assert!(!frame.is_fully_opaque());
+    }
+
+    #[test]
+    fn chroma_key() {
+        let size = UVec2::new(4, 1);
+        let mut pixels = vec![
+            Pixel::GREEN,
+            Pixel::from([20, 230, 30, 255]),
+            Pixel::from([20, 230, 30, 128]),
+            Pixel::WHITE,
+        ];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.chroma_key(Pixel::GREEN, 0.1, 0.05);
+
+        let alphas: Vec<u8> = frame.raw().iter().map(|p| p.a).collect();
+        assert_eq!(alphas[0], 0);
+        // on the soft edge, scaled from the original alpha
+        assert!(alphas[1] > 0 && alphas[1] < 255);
+        let expected = (alphas[1] as f32 * 128.0 / 255.0).round() as u8;
+        assert!(alphas[2].abs_diff(expected) <= 1);
+        assert_eq!(alphas[3], 255);
+        // the color is not modified
+        assert_eq!(frame.raw()[1], Pixel::from([20, 230, 30, alphas[1]]));
+    }
+
+    #[test]
+    fn select_contiguous() {
+        let size = UVec2::new(4, 3);
+        let (a, b, c) = (
+            Pixel::from([100, 0, 0, 255]),
+            Pixel::from([104, 0, 0, 255]),
+            Pixel::BLUE,
+        );
+        #[rustfmt::skip]
+        let mut pixels = vec![
+            a, b, c, a,
+            c, a, c, a,
+            a, b, c, b,
+        ];
+        let frame = Frame::from_raw_parts(&mut pixels, size);
+
+        let selected = |mask: Vec<bool>| -> Vec<usize> {
+            mask.iter()
+                .enumerate()
+                .filter_map(|(i, s)| s.then_some(i))
+                .collect()
+        };
+        // exact color, the diagonal is not connected
+        assert_eq!(selected(frame.select_contiguous(UVec2::new(0, 0), 0)), [0]);
+        // with tolerance
+        assert_eq!(
+            selected(frame.select_contiguous(UVec2::new(0, 0), 4)),
+            [0, 1, 5, 8, 9]
+        );
+        assert_eq!(
+            selected(frame.select_contiguous(UVec2::new(3, 2), 10)),
+            [3, 7, 11]
+        );
+        assert!(!frame
+            .select_contiguous(UVec2::new(4, 0), 255)
+            .contains(&true));
+    }
+
+    #[test]
+    fn fill_with_index() {
+        let size = UVec2::new(4, 2);
+        let mut pixels = vec![Pixel::TRANSPARENT; 8];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.fill_with_index(|i| {
+            if i % 3 == 0 {
+                Pixel::WHITE
+            } else {
+                Pixel::BLACK
+            }
+        });
+
+        let (w, b) = (Pixel::WHITE, Pixel::BLACK);
+        assert_eq!(frame.raw(), &[w, b, b, w, b, b, w, b]);
+    }
+
+    #[test]
+    fn iter_region() {
+        let size = UVec2::new(4, 3);
+        let mut pixels: Vec<Pixel> = (0..12).map(|i| Pixel::from([i, 0, 0, 255])).collect();
+        let frame = Frame::from_raw_parts(&mut pixels, size);
+
+        let region: Vec<_> = frame.iter_region(URect::new(2, 1, 10, 10)).collect();
+        assert_eq!(
+            region,
+            [(2, 1), (3, 1), (2, 2), (3, 2)].map(|(x, y)| {
+                let location = UVec2::new(x, y);
+                (location, Pixel::from([(x + y * 4) as u8, 0, 0, 255]))
+            })
+        );
+        assert_eq!(frame.iter_region(URect::new(5, 0, 8, 3)).count(), 0);
+    }
+
+    #[test]
+    fn copy_from() {
+        let size = UVec2::new(2, 1);
+        let mut pixels = vec![Pixel::TRANSPARENT; 2];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        frame.copy_from_pixels(&[Pixel::RED, Pixel::BLUE]).unwrap();
+        assert_eq!(frame.raw(), &[Pixel::RED, Pixel::BLUE]);
+        frame
+            .copy_from_bytes(&[0, 255, 0, 255, 1, 2, 3, 4])
+            .unwrap();
+        assert_eq!(frame.raw(), &[Pixel::GREEN, Pixel::from([1, 2, 3, 4])]);
+
+        assert!(matches!(
+            frame.copy_from_pixels(&[Pixel::RED]),
+            Err(FrameError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            frame.copy_from_bytes(&[0; 7]),
+            Err(FrameError::LengthMismatch {
+                expected: 8,
+                found: 7
+            })
+        ));
+        assert_eq!(frame.raw()[0], Pixel::GREEN);
+    }
+
+    #[test]
+    fn draw_dashed_line() {
+        let mut pixels = vec![Pixel::BLACK; 10];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 1));
+        frame.draw_dashed_line((-2, 0), (20, 0), Pixel::RED, 3, 2);
+        let pattern: String = frame
+            .raw()
+            .iter()
+            .map(|p| if *p == Pixel::RED { '#' } else { '.' })
+            .collect();
+        assert_eq!(pattern, "#..###..##");
+
+        frame.draw_dashed_line((0, 0), (9, 0), Pixel::BLUE, 0, 2);
+        frame.draw_dashed_line((0, 0), (9, 0), Pixel::BLUE, 0, 0);
+        assert!(frame.find_pixel(|p| p == Pixel::BLUE).is_none());
+    }
+
+    #[test]
+    fn draw_polyline() {
+        let size = UVec2::new(4, 4);
+        let mut pixels = vec![Pixel::BLACK; 4 * 4];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        let drawn = |frame: &Frame| {
+            frame
+                .find_all(|p| p == Pixel::RED)
+                .into_iter()
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>()
+        };
+
+        frame.draw_polyline(&[], Pixel::RED, true);
+        assert!(drawn(&frame).is_empty());
+        frame.draw_polyline(&[IVec2::new(1, 1)], Pixel::RED, true);
+        assert_eq!(drawn(&frame), [(1, 1)]);
+
+        let corners = [IVec2::new(0, 0), IVec2::new(3, 0), IVec2::new(3, 3)];
+        frame.fill_with_index(|_| Pixel::BLACK);
+        frame.draw_polyline(&corners, Pixel::RED, false);
+        assert_eq!(
+            drawn(&frame),
+            [(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2), (3, 3)]
+        );
+
+        frame.draw_polyline(&corners, Pixel::RED, true);
+        assert_eq!(drawn(&frame).len(), 9);
+        assert_eq!(frame.raw()[1 + 4], Pixel::RED);
+        assert_eq!(frame.raw()[2 + 2 * 4], Pixel::RED);
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let size = UVec2::new(2, 2);
+        let mut pixels = vec![Pixel::RED; 4];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        let mut snapshot = frame.snapshot();
+        assert_eq!(snapshot.raw(), frame.raw());
+        let handle = std::thread::spawn(move || {
+            snapshot.frame().set((1, 1), Pixel::BLUE).unwrap();
+            snapshot
+        });
+        let snapshot = handle.join().unwrap();
+        frame.apply_snapshot(&snapshot).unwrap();
+        assert_eq!(
+            frame.raw(),
+            &[Pixel::RED, Pixel::RED, Pixel::RED, Pixel::BLUE]
+        );
+
+        let mut small = vec![Pixel::RED; 1];
+        let small = Frame::from_raw_parts(&mut small, UVec2::ONE).snapshot();
+        assert!(frame.apply_snapshot(&small).is_err());
+    }
+
+    #[test]
+    fn frame_batch() {
+        let mut images = Assets::<Image>::default();
+        let a = images.add(create_image(UVec2::new(2, 1).into()));
+        let b = images.add(create_image(UVec2::new(2, 1).into()));
+        let missing = Handle::<Image>::default();
+
+        {
+            let mut batch = FrameBatch::new(&mut images);
+            assert_eq!(batch.add(&a), Some(0));
+            assert_eq!(batch.add(&b), Some(1));
+            assert_eq!(batch.add(&a), Some(0));
+            assert_eq!(batch.add(&missing), None);
+            assert_eq!(batch.len(), 2);
+
+            let mut frames = batch.frames();
+            frames[0].set((0, 0), Pixel::RED).unwrap();
+            frames[1].set((1, 0), Pixel::BLUE).unwrap();
+            let (first, second) = frames.split_at_mut(1);
+            second[0].raw_mut()[0] = first[0].raw()[0];
+        }
+
+        assert_eq!(
+            Frame::extract(&mut images, &a).raw(),
+            &[Pixel::RED, Pixel::TRANSPARENT]
+        );
+        assert_eq!(
+            Frame::extract(&mut images, &b).raw(),
+            &[Pixel::RED, Pixel::BLUE]
+        );
+    }
+
+    #[test]
+    fn tint() {
+        let original = vec![
+            Pixel::from([200, 100, 0, 255]),
+            Pixel::from([255, 51, 7, 128]),
+        ];
+        let mut pixels = original.clone();
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 1));
+
+        frame.tint(Pixel::WHITE);
+        assert_eq!(frame.raw(), original.as_slice());
+
+        frame.tint([128, 128, 128, 255]);
+        assert_eq!(
+            frame.raw(),
+            &[
+                Pixel::from([100, 50, 0, 255]),
+                Pixel::from([128, 26, 4, 128])
+            ]
+        );
+    }
+
+    #[test]
+    fn linear_srgb() {
+        let mut pixels = vec![
+            Pixel::from([0, 128, 255, 128]),
+            Pixel::from([188, 188, 188, 255]),
+        ];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 1));
+
+        frame.to_linear();
+        assert_eq!(frame.raw()[0], Pixel::from([0, 55, 255, 128]));
+        assert_eq!(frame.raw()[1], Pixel::from([128, 128, 128, 255]));
+
+        frame.to_srgb();
+        assert_eq!(frame.raw()[0], Pixel::from([0, 128, 255, 128]));
+        assert_eq!(frame.raw()[1], Pixel::from([188, 188, 188, 255]));
+    }
+
+    #[test]
+    fn splat() {
+        let mut pixels = vec![Pixel::TRANSPARENT; 3 * 3];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 3));
+
+        frame.splat(Vec2::new(1.0, 1.0), [200, 100, 40, 200]);
+        assert_eq!(frame.raw()[4], Pixel::from([200, 100, 40, 200]));
+        assert_eq!(frame.find_all(|p| p != Pixel::TRANSPARENT).len(), 1);
+
+        // additive and clamped
+        frame.splat(Vec2::new(1.0, 1.0), [200, 100, 40, 200]);
+        assert_eq!(frame.raw()[4], Pixel::from([255, 200, 80, 255]));
+
+        frame.raw_mut().fill(Pixel::TRANSPARENT);
+        frame.splat(Vec2::new(0.5, 0.5), [200, 100, 40, 200]);
+        for i in [0, 1, 3, 4] {
+            assert_eq!(frame.raw()[i], Pixel::from([50, 25, 10, 50]));
+        }
+        assert_eq!(frame.find_all(|p| p != Pixel::TRANSPARENT).len(), 4);
+
+        // partially outside
+        frame.splat(Vec2::new(2.5, -0.5), Pixel::WHITE);
+        assert_eq!(frame.raw()[2], Pixel::from([64, 64, 64, 64]));
+    }
+
+    #[test]
+    fn diff() {
+        let mut pixels = vec![Pixel::BLACK; 3 * 2];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 2));
+        let previous: Vec<u8> = bytemuck::cast_slice(frame.raw()).to_vec();
+        assert_eq!(frame.diff_pixels(&previous).unwrap().count(), 0);
+
+        frame.set((2, 1), Pixel::RED).unwrap();
+        let changed: Vec<_> = frame.diff_pixels(&previous).unwrap().collect();
+        assert_eq!(changed, vec![(UVec2::new(2, 1), Pixel::RED)]);
+
+        assert!(matches!(
+            frame.diff_pixels(&previous[1..]),
+            Err(FrameError::LengthMismatch {
+                expected: 24,
+                found: 23
+            })
+        ));
+    }
+
+    #[test]
+    fn ascii_preview() {
+        let mut pixels = vec![Pixel::BLACK; 4 * 4];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(4, 4));
+        frame.per_pixel(|pos, _| {
+            if pos.x < 2 {
+                Pixel::WHITE
+            } else {
+                Pixel::BLACK
+            }
+        });
+
+        assert_eq!(frame.to_ascii(4), "@@  \n@@  \n");
+        assert_eq!(frame.to_ascii(2), "@ \n");
+        assert_eq!(frame.to_ascii(0), "");
+
+        // transparent is dark, half gray is in the middle of the ramp
+        frame.set_column(0, &[Pixel::TRANSPARENT; 4]).unwrap();
+        frame
+            .set_column(1, &[Pixel::from([128, 128, 128, 255]); 4])
+            .unwrap();
+        assert_eq!(frame.to_ascii(4), " +  \n +  \n");
+    }
+
+    #[test]
+    fn luminance_curve() {
+        let mut pixels = vec![
+            Pixel::from([100, 50, 20, 200]),
+            Pixel::from([0, 0, 0, 255]),
+            Pixel::from([200, 100, 0, 255]),
+        ];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 1));
+
+        frame.map_luminance(|l| l);
+        assert_eq!(frame.raw()[0], Pixel::from([100, 50, 20, 200]));
+
+        frame.map_luminance(|l| if l > 0.0 { l * 2.0 } else { 0.5 });
+        assert_eq!(frame.raw()[0], Pixel::from([200, 100, 40, 200]));
+        assert_eq!(frame.raw()[1], Pixel::from([128, 128, 128, 255]));
+        assert_eq!(frame.raw()[2], Pixel::from([255, 200, 0, 255]));
+    }
+
+    #[test]
+    fn auto_levels() {
+        let size = UVec2::new(12, 1);
+        let mut pixels: Vec<_> = (0..10)
+            .map(|i| Pixel::from([50 + i * 10, 100, 40 + i * 5, 255]))
+            .collect();
+        // an outlier, and a transparent pixel that is ignored
+        pixels.push(Pixel::from([255, 100, 40, 255]));
+        pixels.push(Pixel::from([0, 0, 0, 0]));
+
+        let mut stretched = pixels.clone();
+        let mut frame = Frame::from_raw_parts(&mut stretched, size);
+        frame.auto_levels(0.0, LevelsMode::Channels);
+        assert_eq!(frame.raw()[0], Pixel::from([0, 100, 0, 255]));
+        // the outlier is the maximum
+        assert_eq!(frame.raw()[9].r, 112);
+        assert_eq!(frame.raw()[9].b, 255);
+        assert_eq!(frame.raw()[11], Pixel::TRANSPARENT);
+
+        let mut stretched = pixels.clone();
+        let mut frame = Frame::from_raw_parts(&mut stretched, size);
+        frame.auto_levels(10.0, LevelsMode::Channels);
+        assert_eq!(frame.raw()[0].r, 0);
+        assert_eq!(frame.raw()[9].r, 255);
+        assert_eq!(frame.raw()[10].r, 255);
+
+        // gray pixels keep being gray
+        let mut pixels: Vec<_> = [60, 120, 180].map(|v| Pixel::from([v, v, v, 255])).into();
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(3, 1));
+        frame.auto_levels(0.0, LevelsMode::Luminance);
+        assert_eq!(frame.raw()[0], Pixel::from([0, 0, 0, 255]));
+        assert_eq!(frame.raw()[2], Pixel::from([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn kernel_3x3() {
+        let size = UVec2::new(3, 3);
+        let mut pixels: Vec<_> = (0..9)
+            .map(|i| Pixel::from([i * 20, 100, 255 - i * 10, 200]))
+            .collect();
+        let original = pixels.clone();
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        let identity = [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]];
+        frame.apply_kernel_3x3(identity, 1.0, 0.0);
+        assert_eq!(frame.raw(), original);
+
+        // flat areas are mid gray with an emboss
+        frame.per_pixel(|_, _| Pixel::from([30, 60, 90, 200]));
+        let emboss = [[-1.0, -1.0, 0.0], [-1.0, 0.0, 1.0], [0.0, 1.0, 1.0]];
+        frame.apply_kernel_3x3(emboss, 1.0, 128.0);
+        assert!(frame
+            .raw()
+            .iter()
+            .all(|p| *p == Pixel::from([128, 128, 128, 200])));
+    }
+
+    #[test]
+    fn fill_pie() {
+        let size = UVec2::new(9, 9);
+        let mut pixels = vec![Pixel::BLACK; 9 * 9];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        let center = IVec2::new(4, 4);
+
+        // wraps around 0 degrees, the right half
+        frame.fill_pie(center, 3, 270.0, 90.0, Pixel::RED);
+        let red = frame.find_all(|p| p == Pixel::RED);
+        assert!(red.iter().all(|p| p.x >= 4));
+        assert!(red.contains(&UVec2::new(7, 4)));
+        assert!(red.contains(&UVec2::new(4, 1)) && red.contains(&UVec2::new(4, 7)));
+        assert!(!red.contains(&UVec2::new(7, 7)));
+
+        // clipped, and the full circle
+        frame.fill_pie(IVec2::new(0, 0), 3, 0.0, 360.0, Pixel::BLUE);
+        assert_eq!(frame.raw()[0], Pixel::BLUE);
+        assert_eq!(frame.raw()[3], Pixel::BLUE);
+        assert_eq!(frame.raw()[3 * 9 + 3], Pixel::BLACK);
+    }
+
+    #[test]
+    fn find_pixel() {
+        let size = UVec2::new(4, 3);
+        let mut pixels = vec![Pixel::BLACK; 4 * 3];
+        pixels[6] = Pixel::RED;
+        pixels[9] = Pixel::RED;
+        pixels[7] = Pixel::GREEN;
+        let frame = Frame::from_raw_parts(&mut pixels, size);
+
+        assert_eq!(
+            frame.find_pixel(|p| p == Pixel::GREEN),
+            Some(UVec2::new(3, 1))
+        );
+        assert_eq!(
+            frame.find_pixel(|p| p == Pixel::RED),
+            Some(UVec2::new(2, 1))
+        );
+        assert_eq!(frame.find_pixel(|p| p == Pixel::BLUE), None);
+        assert_eq!(
+            frame.find_all(|p| p == Pixel::RED),
+            [UVec2::new(2, 1), UVec2::new(1, 2)]
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let size = UVec2::new(2, 2);
+        let mut pixels = vec![
+            Pixel::from([10, 20, 30, 0]),
+            Pixel::from([50, 20, 0, 255]),
+            Pixel::from([30, 200, 60, 100]),
+            Pixel::from([30, 0, 10, 255]),
+        ];
+        let frame = Frame::from_raw_parts(&mut pixels, size);
+        let stats = frame.stats();
+        assert_eq!(stats.min, Pixel::from([10, 0, 0, 0]));
+        assert_eq!(stats.max, Pixel::from([50, 200, 60, 255]));
+        assert_eq!(stats.mean, Vec4::new(30.0, 60.0, 25.0, 152.5));
+        assert_eq!(stats.transparent, 1);
+        assert_eq!(stats.opaque, 2);
+
+        let frame = Frame::from_raw_parts(&mut [], UVec2::ZERO);
+        assert_eq!(frame.stats(), FrameStats::default());
+    }
+
+    #[test]
+    fn median_filter() {
+        let size = UVec2::new(5, 5);
+        let mut pixels = vec![Pixel::BLACK; 25];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        // salt and pepper, a corner included
+        frame.set((2, 2), Pixel::WHITE).unwrap();
+        frame.set((0, 0), Pixel::RED).unwrap();
+        // the right column is a solid edge
+        for y in 0..5 {
+            frame.set((4, y), Pixel::WHITE).unwrap();
+        }
+
+        frame.median_filter(1);
+
+        for (idx, pixel) in frame.raw().iter().enumerate() {
+            let expected = if idx % 5 == 4 {
+                Pixel::WHITE
+            } else {
+                Pixel::BLACK
+            };
+            assert_eq!(*pixel, expected, "pixel {idx}");
+        }
+    }
+
+    #[test]
+    fn polygon_coverage() {
+        let size = UVec2::new(8, 8);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        let square = [
+            Vec2::new(1.5, 1.5),
+            Vec2::new(6.5, 1.5),
+            Vec2::new(6.5, 6.5),
+            Vec2::new(1.5, 6.5),
+        ];
+        frame.fill_polygon_aa(&square, Pixel::RED);
+
+        let alpha = |x: usize, y: usize| frame.raw()[y * 8 + x].a;
+        // interior
+        for y in 2..=5 {
+            for x in 2..=5 {
+                assert_eq!(frame.raw()[y * 8 + x], Pixel::RED);
+            }
+        }
+        // edges are half covered and corners a quarter
+        assert_eq!(alpha(1, 3), 128);
+        assert_eq!(alpha(6, 3), 128);
+        assert_eq!(alpha(3, 6), 128);
+        assert_eq!(alpha(1, 1), 64);
+        assert_eq!(alpha(6, 6), 64);
+        // outside
+        assert_eq!(alpha(0, 0), 0);
+        assert_eq!(alpha(7, 3), 0);
+    }
+
+    #[test]
+    fn stamp_rotated() {
+        // 3x1 horizontal bar, rotated 90 degrees it's vertical
+        let mut bar = crate::pixel_buffer::create_image(UVec2::new(3, 1).into());
+        bar.frame().per_pixel(|_, _| Pixel::RED);
+
+        let size = UVec2::new(5, 5);
+        let mut pixels = vec![Pixel::BLUE; 25];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.stamp_rotated(&bar, Vec2::new(2.0, 2.0), std::f32::consts::FRAC_PI_2);
+
+        for (idx, pixel) in frame.raw().iter().enumerate() {
+            let (x, y) = (idx % 5, idx / 5);
+            let expected = if x == 2 && (1..=3).contains(&y) {
+                Pixel::RED
+            } else {
+                Pixel::BLUE
+            };
+            assert_eq!(*pixel, expected, "pixel at ({x}, {y})");
+        }
+
+        // clipped to the frame
+        frame.stamp_rotated(&bar, Vec2::new(0.0, 4.0), 0.0);
+        assert_eq!(frame.raw()[20], Pixel::RED);
+        assert_eq!(frame.raw()[21], Pixel::RED);
+    }
+
+    #[test]
+    fn warp_identity_and_flip() {
+        let size = UVec2::new(4, 3);
+        let mut src_pixels = frame_pixels(size);
+        let mut src = Frame::from_raw_parts(&mut src_pixels, size);
+        src.per_pixel(|pos, _| [(pos.x * 60) as u8, (pos.y * 100) as u8, 7, 255]);
+
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.warp_from(&src, |pos| pos.as_vec2(), AddressMode::Transparent);
+        assert_eq!(frame.raw(), src.raw());
+
+        frame.warp_from(
+            &src,
+            |pos| Vec2::new(3.0 - pos.x as f32, pos.y as f32),
+            AddressMode::Transparent,
+        );
+        assert_eq!(frame.row(1).unwrap()[0], src.row(1).unwrap()[3]);
+    }
+
+    /// One generation of the game of life, white cells are alive
+    fn life_step(frame: &mut Frame) {
+        let previous = frame.snapshot();
+        frame.per_pixel(|pos, pixel| {
+            let mut alive = 0;
+            previous.for_each_neighbor(pos.as_ivec2(), |_, neighbor| {
+                alive += (neighbor == Pixel::WHITE) as u32;
+            });
+            match (pixel == Pixel::WHITE, alive) {
+                (true, 2 | 3) | (false, 3) => Pixel::WHITE,
+                _ => Pixel::BLACK,
+            }
+        });
+    }
+
+    #[test]
+    fn wrapping_life() {
+        let size = UVec2::new(6, 6);
+        let mut pixels = vec![Pixel::BLACK; 36];
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            pixels[x + y * 6] = Pixel::WHITE;
+        }
+        let glider = pixels.clone();
+
+        // the glider moves one cell diagonally every 4 generations, around the torus
+        let mut frame = Frame::from_raw_parts(&mut pixels, size).with_edge_mode(AddressMode::Wrap);
+        for _ in 0..4 * 6 {
+            life_step(&mut frame);
+        }
+        assert_eq!(frame.raw(), &glider);
+
+        // without wrapping it becomes a block in the corner
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        assert_eq!(frame.edge_mode(), AddressMode::Transparent);
+        for _ in 0..4 * 6 {
+            life_step(&mut frame);
+        }
+        assert_ne!(frame.raw(), &glider);
+        assert_eq!(
+            frame.snapshot().frame().edge_mode(),
+            AddressMode::Transparent
+        );
+        assert_eq!(frame.sample(IVec2::new(-1, 0)), Pixel::TRANSPARENT);
+    }
+
+    #[test]
+    fn address_modes() {
+        let size = UVec2::new(4, 1);
+        let mut pixels = vec![
+            Pixel::RED,
+            Pixel::GREEN,
+            Pixel::BLUE,
+            Pixel::from([255, 255, 0, 255]),
+        ];
+        let frame = Frame::from_raw_parts(&mut pixels, size);
+        let (first, last) = (frame.raw()[0], frame.raw()[3]);
+
+        let before = IVec2::new(-1, 0);
+        let after = IVec2::new(4, 0);
+        let cases = [
+            (AddressMode::Transparent, None, None),
+            (AddressMode::Clamp, Some(first), Some(last)),
+            (AddressMode::Wrap, Some(last), Some(first)),
+            (AddressMode::Mirror, Some(first), Some(last)),
+        ];
+        for (mode, expected_before, expected_after) in cases {
+            assert_eq!(frame.get_pixel(before, mode), expected_before, "{mode:?}");
+            assert_eq!(frame.get_pixel(after, mode), expected_after, "{mode:?}");
+        }
+
+        assert_eq!(
+            frame.get_pixel(IVec2::new(-6, 0), AddressMode::Mirror),
+            Some(frame.raw()[2])
+        );
+        assert_eq!(
+            frame.sample_bilinear(Vec2::new(-1.0, 0.0), AddressMode::Transparent),
+            Pixel::TRANSPARENT
+        );
+        assert_eq!(
+            frame.sample_bilinear(Vec2::new(-0.5, 0.0), AddressMode::Wrap),
+            Pixel::from([255, 128, 0, 255])
+        );
+        assert_eq!(
+            frame.sample_bilinear(Vec2::new(3.5, 0.0), AddressMode::Clamp),
+            last
+        );
+    }
+
+    #[test]
+    fn rotate_into() {
+        let size = UVec2::new(3, 3);
+        let mut src_pixels = frame_pixels(size);
+        let mut src = Frame::from_raw_parts(&mut src_pixels, size);
+        src.set((2, 1), Pixel::RED).unwrap();
+
+        let mut dst_pixels = frame_pixels(size);
+        let mut dst = Frame::from_raw_parts(&mut dst_pixels, size);
+        src.rotate_into(std::f32::consts::FRAC_PI_2, &mut dst);
+
+        // right of the center goes below the center
+        assert_eq!(dst.raw()[7], Pixel::RED);
+        assert_eq!(dst.raw().iter().filter(|p| **p == Pixel::RED).count(), 1);
+        assert!(dst
+            .raw()
+            .iter()
+            .filter(|p| **p != Pixel::RED)
+            .all(|p| p.a == 0));
+    }
+
+    #[test]
+    fn swap_pixels() {
+        let size = UVec2::new(2, 2);
+        let mut pixels = frame_pixels(size);
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.set((0, 0), Pixel::RED).unwrap();
+
+        frame.swap_pixels(UVec2::new(0, 0), UVec2::new(1, 1));
+        assert_eq!(frame.raw()[3], Pixel::RED);
+        assert_eq!(frame.raw()[0], Pixel::TRANSPARENT);
+
+        frame.swap_pixels(UVec2::new(1, 1), UVec2::new(2, 1));
+        assert_eq!(frame.raw()[3], Pixel::RED);
+    }
+
+    #[test]
+    fn copy_within_overlapping() {
+        let size = UVec2::new(5, 3);
+        let mut pixels: Vec<Pixel> = (0..15).map(|i| Pixel::from([i, 0, 0, 255])).collect();
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        // rightward, overlapping and clipped by the right edge
+        frame.copy_within(URect::new(0, 0, 4, 1), UVec2::new(2, 0));
+        let row: Vec<u8> = frame.row(0).unwrap().iter().map(|p| p.r).collect();
+        assert_eq!(row, [0, 1, 0, 1, 2]);
+
+        // downwards, overlapping
+        frame.copy_within(URect::new(0, 0, 5, 2), UVec2::new(0, 1));
+        let column: Vec<u8> = frame.column(4).unwrap().iter().map(|p| p.r).collect();
+        assert_eq!(column, [2, 2, 9]);
+
+        // upwards and leftwards
+        frame.copy_within(URect::new(1, 1, 5, 3), UVec2::new(0, 0));
+        let row: Vec<u8> = frame.row(0).unwrap().iter().map(|p| p.r).collect();
+        assert_eq!(row, [1, 0, 1, 2, 2]);
+
+        // out of bounds does nothing
+        let before = frame.raw().to_vec();
+        frame.copy_within(URect::new(0, 0, 2, 2), UVec2::new(5, 0));
+        frame.copy_within(URect::new(0, 0, 2, 2), UVec2::new(100, 100));
+        frame.copy_within(URect::new(6, 6, 8, 8), UVec2::ZERO);
+        assert_eq!(frame.raw(), &before[..]);
+    }
+
+    #[test]
+    fn downsample_partial_blocks() {
+        let size = UVec2::new(5, 5);
+        let mut pixels: Vec<Pixel> = (0..25u8)
+            .map(|i| Pixel::from([10 * (i % 5) + 50 * (i / 5), 0, 0, 255]))
+            .collect();
+        let frame = Frame::from_raw_parts(&mut pixels, size);
+
+        // 3x3 blocks, the last ones only have 2 columns or rows
+        let mut dst_pixels = vec![Pixel::TRANSPARENT; 3 * 3];
+        let mut dst = Frame::from_raw_parts(&mut dst_pixels, UVec2::new(3, 3));
+        frame.downsample_into(3, &mut dst);
+
+        let red = |x: u32, y: u32| dst.raw()[(x + y * 3) as usize].r;
+        assert_eq!(red(0, 0), 60);
+        assert_eq!(red(1, 0), 85);
+        assert_eq!(red(0, 1), 185);
+        assert_eq!(red(1, 1), 210);
+        assert!([0, 1, 3, 4].iter().all(|i| dst.raw()[*i].a == 255));
+        // outside of the downsampled size
+        assert_eq!(dst.raw()[2], Pixel::TRANSPARENT);
+        assert_eq!(dst.raw()[8], Pixel::TRANSPARENT);
+    }
+
+    #[test]
+    fn kaleidoscope() {
+        let size = UVec2::new(5, 5);
+        let mut pixels = vec![Pixel::BLACK; 25];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.set((4, 3), Pixel::RED).unwrap();
+        frame.kaleidoscope(4, Vec2::splat(2.0));
+
+        // the source wedge is kept, copied to the opposite quadrant and mirrored to the others
+        let red: Vec<_> = frame
+            .raw()
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.r > 250 && p.g < 5)
+            .map(|(idx, _)| UVec2::new(idx as u32 % 5, idx as u32 / 5))
+            .collect();
+        assert_eq!(
+            red,
+            [
+                UVec2::new(0, 1),
+                UVec2::new(4, 1),
+                UVec2::new(0, 3),
+                UVec2::new(4, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn bytes_from_fn() {
+        let size = UVec2::new(3, 2);
+        let bytes = frame_bytes_from_fn(size, |pos| {
+            Pixel::from([pos.x as u8 * 100, pos.y as u8 * 100, 0, 255])
+        });
+        assert_eq!(
+            bytes,
+            [
+                0, 0, 0, 255, 100, 0, 0, 255, 200, 0, 0, 255, //
+                0, 100, 0, 255, 100, 100, 0, 255, 200, 100, 0, 255,
+            ]
+        );
+
+        // seeds an image
+        let mut image = create_image(size.into());
+        image.data = bytes;
+        assert_eq!(
+            Frame::get(&mut image).raw()[4],
+            Pixel::from([100, 100, 0, 255])
+        );
+    }
+
+    #[test]
+    fn blur_rect() {
+        // left half white, right half black
+        let size = UVec2::new(6, 3);
+        let mut pixels = vec![Pixel::BLACK; 18];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.per_pixel(|pos, p| if pos.x < 3 { Pixel::WHITE } else { p });
+
+        // reads the white column just outside of the region, clamps at the right edge
+        frame.blur_rect(URect::new(3, 0, 6, 3), 1);
+        for y in 0..3 {
+            let row: Vec<u8> = frame.row(y).unwrap().iter().map(|p| p.r).collect();
+            assert_eq!(row, [255, 255, 255, 85, 0, 0]);
+        }
+
+        // a single pixel spreads to its neighbours, nothing changes outside of the region
+        let size = UVec2::new(5, 5);
+        let mut pixels = vec![Pixel::BLACK; 25];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.set((2, 2), Pixel::WHITE).unwrap();
+        frame.set((0, 0), Pixel::WHITE).unwrap();
+        frame.blur_rect(URect::new(1, 1, 4, 4), 1);
+        assert_eq!(frame.raw()[0], Pixel::WHITE);
+        for y in 1..4 {
+            for x in 1..4 {
+                let expected = if (x, y) == (1, 1) { 57 } else { 28 };
+                let pixel = frame.raw()[(x + y * 5) as usize];
+                assert_eq!((pixel.r, pixel.a), (expected, 255), "{x}, {y}");
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Animating every pixel of the buffers with a single function.
+//!
+//! The [PixelGeneratorPlugin] registers a system in [Update] that calls a function for
+//! every pixel of every pixel buffer, every frame. It is the same as writing that system
+//! with [QueryPixelBuffer](crate::query::QueryPixelBuffer) and
+//! [Frame::per_pixel](crate::frame::Frame::per_pixel), for the common case of a buffer
+//! that only depends on the location, the previous value and the time.
+//!
+//! The generator can be limited to the buffers with a marker component with
+//! [PixelGeneratorPlugin::with_marker], so each buffer can have its own generator.
+//! Buffers that are [Culled] are skipped, and nothing runs while the
+//! [PixelBufferActive](crate::pixel_buffer::PixelBufferActive) resource is `false`.
+//!
+//! With the `rayon` feature the pixels are computed in parallel.
+//!
+//! # Example
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::prelude::*;
+//! #[derive(Component)]
+//! struct Plasma;
+//!
+//! App::new()
+//!     .add_plugins((DefaultPlugins, PixelBufferPlugin))
+//!     .add_plugins(
+//!         PixelGeneratorPlugin::new(|pos, _, time: &Time| {
+//!             let t = time.elapsed_secs();
+//!             let v = ((pos.x as f32 * 0.1 + t).sin() + (pos.y as f32 * 0.1 - t).cos()) * 0.25;
+//!             Pixel::from([v + 0.5, 0.2, 0.5 - v, 1.0])
+//!         })
+//!         .with_marker::<Plasma>(),
+//!     );
+//! ```
+
+use std::{marker::PhantomData, sync::Arc};
+
+use bevy::{ecs::query::QueryFilter, prelude::*};
+
+use crate::{
+    frame::GetFrameFromImages,
+    pixel::Pixel,
+    pixel_buffer::{pixel_buffer_active, PixelBuffer},
+    visibility::Culled,
+};
+
+/// Plugin that sets every pixel of the pixel buffers with a function, every frame.
+///
+/// The function gets the location of the pixel, its previous value and the [Time].
+/// `Filter` is an extra query filter for the pixel buffers, see
+/// [with_marker](PixelGeneratorPlugin::with_marker).
+///
+/// See the [module documentation](crate::generator).
+pub struct PixelGeneratorPlugin<F, Filter = ()> {
+    generator: Arc<F>,
+    filter: PhantomData<fn() -> Filter>,
+}
+
+impl<F> PixelGeneratorPlugin<F>
+where
+    F: Fn(UVec2, Pixel, &Time) -> Pixel + Send + Sync + 'static,
+{
+    /// Creates the plugin for all the pixel buffers
+    pub fn new(generator: F) -> Self {
+        Self {
+            generator: Arc::new(generator),
+            filter: PhantomData,
+        }
+    }
+}
+
+impl<F, Filter> PixelGeneratorPlugin<F, Filter> {
+    /// Only runs for the pixel buffers with the `M` component
+    pub fn with_marker<M: Component>(self) -> PixelGeneratorPlugin<F, With<M>> {
+        PixelGeneratorPlugin {
+            generator: self.generator,
+            filter: PhantomData,
+        }
+    }
+}
+
+impl<F, Filter> Plugin for PixelGeneratorPlugin<F, Filter>
+where
+    F: Fn(UVec2, Pixel, &Time) -> Pixel + Send + Sync + 'static,
+    Filter: QueryFilter + 'static,
+{
+    fn build(&self, app: &mut App) {
+        let generator = self.generator.clone();
+        app.add_systems(
+            Update,
+            (move |mut images: ResMut<Assets<Image>>,
+                   pixel_buffers: Query<&Sprite, (With<PixelBuffer>, Without<Culled>, Filter)>,
+                   time: Res<Time>| {
+                for sprite in pixel_buffers.iter() {
+                    let mut frame = images.frame(&sprite.image);
+                    let f = |pos, pixel| generator(pos, pixel, &time);
+                    #[cfg(feature = "rayon")]
+                    frame.per_pixel_par(f);
+                    #[cfg(not(feature = "rayon"))]
+                    frame.per_pixel(f);
+                }
+            })
+            .run_if(pixel_buffer_active),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_buffer::{create_image, Fill, PixelBufferSize};
+
+    #[derive(Component)]
+    struct Generated;
+
+    #[test]
+    fn generator_with_marker() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_plugins(
+                PixelGeneratorPlugin::new(
+                    |pos, pixel, _: &Time| {
+                        if pos.x == 0 {
+                            Pixel::RED
+                        } else {
+                            pixel
+                        }
+                    },
+                )
+                .with_marker::<Generated>(),
+            );
+
+        let mut spawn = |marked: bool| {
+            let image = app
+                .world_mut()
+                .resource_mut::<Assets<Image>>()
+                .add(create_image(UVec2::new(2, 2).into()));
+            let mut entity = app.world_mut().spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((2, 2)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image.clone()),
+            ));
+            if marked {
+                entity.insert(Generated);
+            }
+            image
+        };
+        let marked = spawn(true);
+        let unmarked = spawn(false);
+        app.update();
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let frame = images.frame(&marked);
+        assert_eq!(
+            frame.raw(),
+            &[
+                Pixel::RED,
+                Pixel::TRANSPARENT,
+                Pixel::RED,
+                Pixel::TRANSPARENT
+            ]
+        );
+        assert!(images.frame(&unmarked).is_fully_transparent());
+    }
+}
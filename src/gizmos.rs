@@ -0,0 +1,187 @@
+//! Immediate mode debug drawing, like bevy gizmos but into a pixel buffer.
+//!
+//! Mark one pixel buffer as the target with [PixelGizmosTarget], then draw into it from
+//! any system with the [PixelGizmos] system parameter. The shapes are queued and drawn in
+//! [PostUpdate], after clearing the buffer to [Pixel::TRANSPARENT]. Shapes only last one
+//! frame, so they have to be drawn every frame. Coordinates are in pixels of the target
+//! buffer, the shapes are clipped to it.
+//!
+//! The target is usually a transparent buffer on top of the main one, a debug layer.
+//!
+//! # Example
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::prelude::*;
+//! fn debug_player(mut gizmos: PixelGizmos) {
+//!     gizmos.circle(IVec2::new(16, 16), 4, Pixel::RED);
+//!     gizmos.line(IVec2::new(16, 16), IVec2::new(24, 16), Pixel::GREEN);
+//! }
+//! # bevy::ecs::system::assert_is_system(debug_player);
+//! ```
+//! See the `debug_gizmos` example.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{frame::GetFrameFromImages, pixel::Pixel, pixel_buffer::PixelBuffer};
+
+/// Marker component for the pixel buffer that [PixelGizmos] draw into.
+///
+/// If there is more than one, the shapes are drawn into all of them.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PixelGizmosTarget;
+
+/// Queued shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GizmoShape {
+    Point(IVec2),
+    Line(IVec2, IVec2),
+    Circle(IVec2, u32),
+    Rect(IRect),
+}
+
+/// Shapes queued this frame
+#[derive(Resource, Default, Debug)]
+pub(crate) struct PixelGizmosStorage {
+    shapes: Vec<(GizmoShape, Pixel)>,
+    /// If something was drawn in the last flush, to know if the targets need to be cleared
+    drawn: bool,
+}
+
+/// [SystemParam] to queue debug shapes for the [PixelGizmosTarget] buffer.
+///
+/// See the [module documentation](crate::gizmos).
+#[derive(SystemParam)]
+pub struct PixelGizmos<'w> {
+    storage: ResMut<'w, PixelGizmosStorage>,
+}
+
+impl PixelGizmos<'_> {
+    /// Draws a single pixel
+    pub fn point(&mut self, location: IVec2, pixel: impl Into<Pixel>) {
+        self.push(GizmoShape::Point(location), pixel);
+    }
+
+    /// Draws a line between 2 points, both included
+    pub fn line(&mut self, from: IVec2, to: IVec2, pixel: impl Into<Pixel>) {
+        self.push(GizmoShape::Line(from, to), pixel);
+    }
+
+    /// Draws the outline of a circle
+    pub fn circle(&mut self, center: IVec2, radius: u32, pixel: impl Into<Pixel>) {
+        self.push(GizmoShape::Circle(center, radius), pixel);
+    }
+
+    /// Draws the outline of a rectangle, the max corner included
+    pub fn rect(&mut self, rect: IRect, pixel: impl Into<Pixel>) {
+        self.push(GizmoShape::Rect(rect), pixel);
+    }
+
+    fn push(&mut self, shape: GizmoShape, pixel: impl Into<Pixel>) {
+        self.storage.shapes.push((shape, pixel.into()));
+    }
+}
+
+/// Clears the targets and draws the queued shapes
+pub(crate) fn flush_pixel_gizmos(
+    mut storage: ResMut<PixelGizmosStorage>,
+    targets: Query<&Sprite, (With<PixelBuffer>, With<PixelGizmosTarget>)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    // avoid modifying, and uploading, the targets when nothing is drawn
+    if storage.shapes.is_empty() && !storage.drawn {
+        return;
+    }
+
+    for sprite in targets.iter() {
+        let mut frame = images.frame(&sprite.image);
+        frame.raw_mut().fill(Pixel::TRANSPARENT);
+        for (shape, pixel) in storage.shapes.iter().copied() {
+            match shape {
+                GizmoShape::Point(location) => frame.set_clipped(location, pixel),
+                GizmoShape::Line(from, to) => frame.draw_line(from, to, pixel),
+                GizmoShape::Circle(center, radius) => frame.draw_circle(center, radius, pixel),
+                GizmoShape::Rect(rect) => {
+                    let corners = [
+                        rect.min,
+                        IVec2::new(rect.max.x, rect.min.y),
+                        rect.max,
+                        IVec2::new(rect.min.x, rect.max.y),
+                    ];
+                    for i in 0..4 {
+                        frame.draw_line(corners[i], corners[(i + 1) % 4], pixel);
+                    }
+                }
+            }
+        }
+    }
+
+    storage.drawn = !storage.shapes.is_empty();
+    storage.shapes.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_buffer::{create_image, Fill, PixelBufferSize};
+
+    /// Different shapes in the first 2 frames, then nothing
+    fn draw(mut gizmos: PixelGizmos, mut frame: Local<u32>) {
+        match *frame {
+            0 => {
+                gizmos.point(IVec2::new(0, 0), Pixel::RED);
+                gizmos.line(IVec2::new(0, 2), IVec2::new(3, 2), Pixel::GREEN);
+            }
+            1 => gizmos.rect(IRect::new(1, 1, 3, 3), Pixel::BLUE),
+            _ => {}
+        }
+        *frame += 1;
+    }
+
+    #[test]
+    fn queued_shapes_render() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .init_resource::<PixelGizmosStorage>()
+            .add_systems(Update, draw)
+            .add_systems(PostUpdate, flush_pixel_gizmos);
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(4, 4).into()));
+        app.world_mut().spawn((
+            PixelBuffer {
+                size: PixelBufferSize::size((4, 4)),
+                fill: Fill::none(),
+                ..Default::default()
+            },
+            Sprite::from_image(image.clone()),
+            PixelGizmosTarget,
+        ));
+
+        let pixels = |app: &mut App| {
+            let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+            images.frame(&image).raw().to_vec()
+        };
+
+        app.update();
+        let frame = pixels(&mut app);
+        assert_eq!(frame[0], Pixel::RED);
+        assert!(frame[8..12].iter().all(|p| *p == Pixel::GREEN));
+        assert_eq!(frame.iter().filter(|p| p.a != 0).count(), 5);
+
+        // the previous shapes are cleared
+        app.update();
+        let frame = pixels(&mut app);
+        assert_eq!(frame[0], Pixel::TRANSPARENT);
+        assert_eq!(frame[5], Pixel::BLUE);
+        assert_eq!(frame[15], Pixel::BLUE);
+        assert_eq!(frame[10], Pixel::TRANSPARENT);
+        assert_eq!(frame.iter().filter(|p| p.a != 0).count(), 8);
+
+        app.update();
+        assert!(pixels(&mut app).iter().all(|p| *p == Pixel::TRANSPARENT));
+    }
+}
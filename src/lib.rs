@@ -48,34 +48,71 @@
 //! The crate does not offer drawing behaviour (yet) for shapes like triangles, quads or anything like that,
 //! but with [Frame::raw_mut](crate::frame::Frame::raw_mut) you can implement any behaviour you want.
 //!
+//! To animate every pixel with a single function, without writing the system, see the
+//! [generator] module.
+//!
+//! Very large buffers can avoid uploading the whole texture after every change with the
+//! [streaming] module.
+//!
 
 #![deny(missing_docs)]
 #![warn(rustdoc::broken_intra_doc_links)]
 
+pub mod accumulation;
+pub mod blend;
 pub mod builder;
 pub mod bundle;
 pub mod compute_shader;
+pub mod display;
 #[cfg(feature = "egui")]
 pub mod egui;
+#[cfg(feature = "image")]
+pub mod export;
 pub mod frame;
+pub mod generator;
+pub mod gizmos;
+pub mod palette;
 pub mod pixel;
 pub mod pixel_buffer;
 pub mod query;
+pub mod readback;
+pub mod recorder;
+pub mod rle;
+pub mod streaming;
+pub mod text;
+pub mod visibility;
 
 pub mod prelude {
     //! Common imports
+    pub use crate::accumulation::AccumulationBuffer;
+    pub use crate::blend::BlendMode;
     pub use crate::builder::{pixel_buffer_setup, PixelBufferBuilder, RenderConfig};
-    pub use crate::compute_shader::{ComputeShader, ComputeShaderPlugin};
+    pub use crate::compute_shader::{
+        ComputeBuffers, ComputePoints, ComputeRegion, ComputeShader, ComputeShaderCommands,
+        ComputeShaderDispatched, ComputeShaderPlugin, ComputeShaderStatus, PresentAfterCompute,
+    };
+    pub use crate::display::{PixelBufferDisplay, PixelBufferDisplayPlugin};
     #[cfg(feature = "egui")]
-    pub use crate::egui::{EguiTexture, PixelBufferEguiPlugin};
+    pub use crate::egui::{EguiTexture, PixelBufferEguiPlugin, PixelGrid};
+    #[cfg(feature = "image")]
+    pub use crate::export::{AutoSaveOnExit, PngSequenceRecorder};
     pub use crate::frame::{
-        Frame, FrameEditExtension, GetFrame, GetFrameFromHandle, GetFrameFromImages,
+        AddressMode, Frame, FrameBatch, FrameEditExtension, GetFrame, GetFrameFromHandle,
+        GetFrameFromImages, LevelsMode, OwnedFrame,
     };
+    pub use crate::generator::PixelGeneratorPlugin;
+    pub use crate::gizmos::{PixelGizmos, PixelGizmosTarget};
+    pub use crate::palette::Palette;
     pub use crate::pixel::Pixel;
     pub use crate::pixel_buffer::{
-        Fill, FillKind, PixelBuffer, PixelBufferPlugin, PixelBufferPlugins, PixelBufferSize,
+        ClearEachFrame, Fill, FillKind, FillMargin, PixelBuffer, PixelBufferActive,
+        PixelBufferPlugin, PixelBufferPlugins, PixelBufferSize,
     };
     pub use crate::query::*;
+    pub use crate::readback::{CaptureComplete, CaptureRequest, OnReadback};
+    pub use crate::recorder::FrameRecorder;
+    pub use crate::streaming::{StreamedUpload, StreamedUploadPlugin};
+    pub use crate::visibility::{Culled, UpdateWhenVisible};
 }
 
 #[cfg(feature = "egui")]
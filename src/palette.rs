@@ -0,0 +1,74 @@
+//! Fixed sets of colors for quantization and dithering.
+
+use crate::pixel::Pixel;
+
+/// A fixed, ordered set of colors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<Pixel>,
+}
+
+impl Palette {
+    /// Creates a palette from its colors
+    pub fn new(colors: impl IntoIterator<Item = impl Into<Pixel>>) -> Self {
+        Self {
+            colors: colors.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Colors of the palette
+    pub fn colors(&self) -> &[Pixel] {
+        &self.colors
+    }
+
+    /// Number of colors in the palette
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// If the palette has no colors
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Closest color of the palette to `pixel` and its index, using [Pixel::distance_sq].
+    ///
+    /// On ties the first color wins. [None] if the palette is empty.
+    pub fn nearest(&self, pixel: Pixel) -> Option<(usize, Pixel)> {
+        self.colors
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|(_, c)| c.distance_sq(&pixel))
+    }
+}
+
+impl From<Vec<Pixel>> for Palette {
+    fn from(colors: Vec<Pixel>) -> Self {
+        Self { colors }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest() {
+        let palette = Palette::new([Pixel::BLACK, Pixel::RED, Pixel::GREEN, Pixel::WHITE]);
+        assert_eq!(
+            palette.nearest(Pixel::from([200u8, 30, 20, 255])),
+            Some((1, Pixel::RED))
+        );
+        assert_eq!(
+            palette.nearest(Pixel::from([40u8, 40, 40, 255])),
+            Some((0, Pixel::BLACK))
+        );
+        assert_eq!(
+            palette.nearest(Pixel::from([220u8, 230, 240, 255])),
+            Some((3, Pixel::WHITE))
+        );
+        assert_eq!(palette.nearest(Pixel::GREEN), Some((2, Pixel::GREEN)));
+        assert_eq!(Palette::default().nearest(Pixel::RED), None);
+    }
+}
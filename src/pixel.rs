@@ -87,6 +87,66 @@ impl Pixel {
             self.a as f32 / 255.0,
         )
     }
+
+    /// As a [Vec4] with every channel in the `0.0..=1.0` range
+    pub fn as_vec4(self) -> Vec4 {
+        Vec4::new(self.r as f32, self.g as f32, self.b as f32, self.a as f32) / 255.0
+    }
+
+    /// Relative luminance of the color with the Rec. 709 weights, ignoring the alpha.
+    pub fn luminance(self) -> u8 {
+        // weights scaled to add up to 256
+        ((54 * self.r as u32 + 183 * self.g as u32 + 19 * self.b as u32 + 128) >> 8) as u8
+    }
+
+    /// Squared euclidean distance to another pixel, summing the four channels.
+    pub fn distance_sq(&self, other: &Pixel) -> u32 {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+        d(self.r, other.r) + d(self.g, other.g) + d(self.b, other.b) + d(self.a, other.a)
+    }
+
+    /// Multiplies the color channels by the alpha.
+    ///
+    /// Converts from straight alpha to premultiplied alpha, rounding to the nearest value.
+    pub fn premultiplied(self) -> Self {
+        let mul = |c: u8| ((c as u32 * self.a as u32 + 127) / 255) as u8;
+        Self {
+            r: mul(self.r),
+            g: mul(self.g),
+            b: mul(self.b),
+            a: self.a,
+        }
+    }
+
+    /// Divides the color channels by the alpha.
+    ///
+    /// Converts from premultiplied alpha to straight alpha, rounding to the nearest value.
+    /// Fully transparent pixels have no color and become [Pixel::TRANSPARENT].
+    pub fn unpremultiplied(self) -> Self {
+        if self.a == 0 {
+            return Self::TRANSPARENT;
+        }
+        let a = self.a as u32;
+        let div = |c: u8| ((c as u32 * 255 + a / 2) / a).min(255) as u8;
+        Self {
+            r: div(self.r),
+            g: div(self.g),
+            b: div(self.b),
+            a: self.a,
+        }
+    }
+
+    /// From a [Vec4] with every channel in the `0.0..=1.0` range, rounding to
+    /// the nearest value instead of truncating like the [From] implementation.
+    pub(crate) fn from_vec4_rounded(v: Vec4) -> Self {
+        let v = (v * 255.0).round().clamp(Vec4::ZERO, Vec4::splat(255.0));
+        Self {
+            r: v.x as u8,
+            g: v.y as u8,
+            b: v.z as u8,
+            a: v.w as u8,
+        }
+    }
 }
 
 impl From<[u8; 3]> for Pixel {
@@ -217,3 +277,18 @@ impl From<LinearRgba> for Pixel {
         c.to_u8_array().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_sq() {
+        let a = Pixel::from([10u8, 20, 30, 255]);
+        let b = Pixel::from([13u8, 16, 30, 255]);
+        assert_eq!(a.distance_sq(&b), 25);
+        assert_eq!(a.distance_sq(&b), b.distance_sq(&a));
+        assert_eq!(a.distance_sq(&a), 0);
+        assert_eq!(Pixel::TRANSPARENT.distance_sq(&Pixel::WHITE), 4 * 255 * 255);
+    }
+}
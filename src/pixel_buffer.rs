@@ -4,22 +4,161 @@ use bevy::{
     app::PluginGroupBuilder,
     image::ImageSampler,
     prelude::*,
-    render::render_resource::{Extent3d, TextureDescriptor, TextureDimension, TextureUsages},
+    render::render_resource::{
+        Extent3d, TextureDescriptor, TextureDimension, TextureUsages, TextureViewDescriptor,
+        TextureViewDimension,
+    },
     window::PrimaryWindow,
 };
 
-use crate::prelude::Pixel;
+use crate::{
+    display::PixelBufferDisplay,
+    prelude::{AddressMode, Frame, Pixel},
+    visibility::Culled,
+};
 
 /// Component defining a pixel buffer.
 ///
 /// An [image handle](Handle<Image>) component is also
 /// needed for most operations, but can be added later.
-#[derive(Component, Default, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
 pub struct PixelBuffer {
     /// Size of the pixel buffer
     pub size: PixelBufferSize,
     /// Fill mode
     pub fill: Fill,
+    /// If the plugin keeps the [Sprite::custom_size] in sync with the buffer, `true` by
+    /// default.
+    ///
+    /// The custom size is the [screen size](PixelBufferSize::screen_size), or the fill
+    /// area with [Fill::with_stretch]. Set it to `false` to manage the sprite size or
+    /// scale yourself. The [Fill] keeps resizing the buffer, only the sprite is left as is.
+    /// It applies to custom [displays](crate::display) too.
+    pub auto_display_size: bool,
+    /// Default [AddressMode] of the frames of the buffer, to read outside of it with
+    /// [Frame::sample](crate::frame::Frame::sample) and
+    /// [Frame::for_each_neighbor](crate::frame::Frame::for_each_neighbor).
+    ///
+    /// It's applied to the frames from [QueryPixelBuffer](crate::query::QueryPixelBuffer)
+    /// and the [builder](crate::builder::PixelBufferCommands). For other frames use
+    /// [Frame::with_edge_mode](crate::frame::Frame::with_edge_mode). [AddressMode::Wrap]
+    /// makes the buffer a torus, for simulations like cellular automata.
+    pub edge_mode: AddressMode,
+}
+
+impl Default for PixelBuffer {
+    fn default() -> Self {
+        Self {
+            size: Default::default(),
+            fill: Default::default(),
+            auto_display_size: true,
+            edge_mode: AddressMode::default(),
+        }
+    }
+}
+
+impl PixelBuffer {
+    /// Changes the image displayed by the pixel buffer sprite, returning the previous one.
+    ///
+    /// This allows double buffering: draw into one image while the other one is displayed,
+    /// and swap them every frame.
+    ///
+    /// The new image must have the same size as the pixel buffer, so the [PixelBuffer]
+    /// stays consistent with it.
+    ///
+    /// With the `egui` feature, the [EguiTexture](crate::egui::EguiTexture) keeps
+    /// showing the original image.
+    pub fn swap_image(
+        &self,
+        sprite: &mut Sprite,
+        images: &Assets<Image>,
+        new: Handle<Image>,
+    ) -> Result<Handle<Image>, SwapImageError> {
+        let image = images.get(&new).ok_or(SwapImageError::MissingImage)?;
+        if image.size() != self.size.size {
+            return Err(SwapImageError::SizeMismatch {
+                expected: self.size.size,
+                found: image.size(),
+            });
+        }
+        Ok(std::mem::replace(&mut sprite.image, new))
+    }
+
+    /// Changes the [Fill], to switch between a fixed size and filling an area at runtime.
+    ///
+    /// With [Fill::custom] the new size is applied right away, with [Fill::window] it is
+    /// applied by the plugin the next time it runs. With [Fill::none] the buffer keeps its
+    /// current size.
+    pub fn set_fill(&mut self, fill: impl Into<Fill>) {
+        self.fill = fill.into();
+        self.size = self.fill_target_size(None, 1.0);
+    }
+
+    /// Size that the [Fill] will give to the buffer, without applying it.
+    ///
+    /// `window_size` is the logical size of the window that is filled, see [Window::width]
+    /// and [Window::height], only used by [Fill::window]. `scale_factor` is the one of the
+    /// window, see [Window::scale_factor], only used with [Fill::with_physical_pixels]. If
+    /// the fill is disabled, or there is no window, it's the current size. This is exactly
+    /// what the plugin will set, so it can be used to preallocate data before the buffer is
+    /// resized.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{UVec2, Vec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// let pb = PixelBuffer {
+    ///     size: PixelBufferSize::pixel_size((4, 4)),
+    ///     fill: Fill::window().with_scaling_multiple(8),
+    ///     ..Default::default()
+    /// };
+    /// let target = pb.fill_target_size(Some(Vec2::new(1280.0, 700.0)), 1.0);
+    /// assert_eq!(target.size, UVec2::new(320, 168));
+    /// assert_eq!(pb.fill_target_size(None, 1.0), pb.size);
+    ///
+    /// // one pixel per physical pixel of a HiDPI screen
+    /// let pb = PixelBuffer {
+    ///     size: PixelBufferSize::pixel_size((1, 1)),
+    ///     fill: Fill::window().with_physical_pixels(true),
+    ///     ..Default::default()
+    /// };
+    /// let target = pb.fill_target_size(Some(Vec2::new(640.0, 360.0)), 2.0);
+    /// assert_eq!(target.size, UVec2::new(1280, 720));
+    /// ```
+    pub fn fill_target_size(
+        &self,
+        window_size: Option<Vec2>,
+        scale_factor: f32,
+    ) -> PixelBufferSize {
+        match fill_area(&self.fill, window_size) {
+            Some(area) => PixelBufferSize {
+                size: fill_buffer_size(
+                    area * self.fill.scale(scale_factor),
+                    &self.size,
+                    &self.fill,
+                ),
+                ..self.size
+            },
+            None => self.size,
+        }
+    }
+}
+
+/// Error for [PixelBuffer::swap_image]
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SwapImageError {
+    /// The image is not in the assets
+    #[error("image not found in the assets")]
+    MissingImage,
+    /// The size of the image is not the size of the pixel buffer
+    #[error("image size mismatch (expected: {expected:?}, found: {found:?})")]
+    SizeMismatch {
+        /// pixel buffer size
+        expected: UVec2,
+        /// image size
+        found: UVec2,
+    },
 }
 
 /// Size of a pixel buffer.
@@ -28,6 +167,10 @@ pub struct PixelBufferSize {
     /// Number of (editable) pixels in each dimension.
     pub size: UVec2,
     /// Number of physical pixels each editable pixel takes up in the screen.
+    ///
+    /// The pixels don't have to be square, `(2, 3)` displays each pixel 2 wide and 3 tall
+    /// like some retro displays. The image keeps one texel per pixel, only the sprite is
+    /// stretched.
     pub pixel_size: UVec2,
 }
 
@@ -37,6 +180,10 @@ pub struct Fill {
     pub(crate) kind: FillKind,
     pub(crate) stretch: bool,
     pub(crate) multiple: u32,
+    pub(crate) preserve_content: bool,
+    pub(crate) margin: FillMargin,
+    pub(crate) physical_pixels: bool,
+    pub(crate) window: Option<Entity>,
 }
 
 /// What to fill
@@ -50,12 +197,43 @@ pub enum FillKind {
     Custom(Vec2),
 }
 
+/// Space left empty on each side of the area that is filled, see [Fill::with_margin]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillMargin {
+    /// Logical pixels, horizontally and vertically
+    Logical(Vec2),
+    /// Fraction of the area size, horizontally and vertically. `0.1` leaves 10% of the
+    /// width or height on each side.
+    Fraction(Vec2),
+}
+
+impl Default for FillMargin {
+    fn default() -> Self {
+        Self::Logical(Vec2::ZERO)
+    }
+}
+
+impl FillMargin {
+    /// Area left inside the margin, never negative
+    pub fn inner_area(self, area: Vec2) -> Vec2 {
+        let margin = match self {
+            FillMargin::Logical(margin) => margin,
+            FillMargin::Fraction(fraction) => area * fraction,
+        };
+        (area - 2.0 * margin).max(Vec2::ZERO)
+    }
+}
+
 impl Default for Fill {
     fn default() -> Self {
         Self {
             kind: FillKind::None,
             stretch: false,
             multiple: 1,
+            preserve_content: false,
+            margin: FillMargin::default(),
+            physical_pixels: false,
+            window: None,
         }
     }
 }
@@ -86,7 +264,7 @@ impl Fill {
         }
     }
 
-    /// Fill the primary window
+    /// Fill the primary window. To fill another window, see [Fill::with_window].
     pub fn window() -> Self {
         Self {
             kind: FillKind::Window,
@@ -102,6 +280,41 @@ impl Fill {
         }
     }
 
+    /// What is filled
+    pub fn kind(&self) -> FillKind {
+        self.kind
+    }
+
+    /// If the rendering sprite stretches to fill the area, see [Fill::with_stretch]
+    pub fn is_stretched(&self) -> bool {
+        self.stretch
+    }
+
+    /// Margin around the filled area, see [Fill::with_margin]
+    pub fn margin(&self) -> FillMargin {
+        self.margin
+    }
+
+    /// Multiple of the size of the buffer, see [Fill::with_scaling_multiple]
+    pub fn scaling_multiple(&self) -> u32 {
+        self.multiple
+    }
+
+    /// If the content is kept when the buffer is resized, see [Fill::with_preserve_content]
+    pub fn preserves_content(&self) -> bool {
+        self.preserve_content
+    }
+
+    /// Window that is filled, [None] for the primary window, see [Fill::with_window]
+    pub fn target_window(&self) -> Option<Entity> {
+        self.window
+    }
+
+    /// If the area is measured in physical pixels, see [Fill::with_physical_pixels]
+    pub fn uses_physical_pixels(&self) -> bool {
+        self.physical_pixels
+    }
+
     /// Wether to stretch the rendering sprite to fill the area
     pub fn with_stretch(mut self, stretch: bool) -> Self {
         self.stretch = stretch;
@@ -115,6 +328,78 @@ impl Fill {
         self.multiple = multiple;
         self
     }
+
+    /// Leave a margin around the buffer, for example for UI around it.
+    ///
+    /// The margin is taken out of the fill area first, on every side, so a sprite at the
+    /// center of the area stays centered. Then the buffer size is computed from what is
+    /// left and the [pixel size](PixelBufferSize::pixel_size), and truncated to the
+    /// [scaling multiple](Fill::with_scaling_multiple). With
+    /// [stretch](Fill::with_stretch), the sprite fills the area inside the margin.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{UVec2, Vec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// let pb = PixelBuffer {
+    ///     size: PixelBufferSize::pixel_size((2, 2)),
+    ///     fill: Fill::window().with_margin(FillMargin::Logical(Vec2::new(20.0, 0.0))),
+    ///     ..Default::default()
+    /// };
+    /// let target = pb.fill_target_size(Some(Vec2::new(200.0, 100.0)), 1.0);
+    /// assert_eq!(target.size, UVec2::new(80, 50));
+    /// ```
+    pub fn with_margin(mut self, margin: FillMargin) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Keep the content of the buffer when it is resized.
+    ///
+    /// The old content is copied to the top left corner of the new image, without any
+    /// scaling so the pixels stay sharp. When the buffer grows, the new area is
+    /// [Pixel::TRANSPARENT]. When it shrinks, the content outside of the new size is lost,
+    /// even if it grows back later.
+    ///
+    /// Without this the content after a resize is undefined, and should be redrawn.
+    pub fn with_preserve_content(mut self, preserve: bool) -> Self {
+        self.preserve_content = preserve;
+        self
+    }
+
+    /// Measure the fill area in physical pixels of the primary window instead of logical
+    /// pixels.
+    ///
+    /// On HiDPI screens a logical pixel is several physical pixels, see
+    /// [Window::scale_factor]. With this, a [pixel size](PixelBufferSize::pixel_size) of
+    /// `1x1` is one pixel of the screen. The margin is still in logical pixels, and the
+    /// sprite is sized in logical pixels so it keeps covering the same area.
+    pub fn with_physical_pixels(mut self, physical_pixels: bool) -> Self {
+        self.physical_pixels = physical_pixels;
+        self
+    }
+
+    /// Fill a specific window instead of the primary window, for apps with multiple
+    /// windows.
+    ///
+    /// This also sets the kind to [FillKind::Window]. If the window entity doesn't exist,
+    /// for example after the window is closed, the size is not changed. Displaying the
+    /// buffer in that window needs a camera that renders to it, see
+    /// [RenderTarget](bevy::render::camera::RenderTarget).
+    pub fn with_window(mut self, window: Entity) -> Self {
+        self.kind = FillKind::Window;
+        self.window = Some(window);
+        self
+    }
+
+    /// Physical pixels per logical pixel of the fill area
+    pub(crate) fn scale(&self, scale_factor: f32) -> f32 {
+        if self.physical_pixels {
+            scale_factor
+        } else {
+            1.0
+        }
+    }
 }
 
 impl From<FillKind> for Fill {
@@ -218,6 +503,23 @@ impl Default for CreateImageParams {
     }
 }
 
+impl CreateImageParams {
+    /// Adds the usages needed to capture the image with Bevy's screenshot API.
+    ///
+    /// [Screenshot::image](bevy::render::view::screenshot::Screenshot::image) renders the
+    /// captured frame back into the image, so it needs [TextureUsages::RENDER_ATTACHMENT].
+    /// [TextureUsages::COPY_SRC] is added too, which is also what
+    /// [readback](crate::readback) needs.
+    ///
+    /// Note that `Screenshot::image` only captures what a camera renders into the image. To
+    /// save what is shown on screen use `Screenshot::primary_window()`, which needs no extra
+    /// usages, and to get the pixels of a buffer drawn on the CPU read the [Image] data directly.
+    pub fn screenshottable(mut self) -> Self {
+        self.usage |= TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+        self
+    }
+}
+
 impl From<UVec2> for CreateImageParams {
     fn from(size: UVec2) -> Self {
         Self {
@@ -277,6 +579,31 @@ pub fn create_image(params: CreateImageParams) -> Image {
     image
 }
 
+/// Creates a compatible [Image] with `layers` array layers of the same size.
+///
+/// It's meant for [compute shaders](crate::compute_shader) that simulate several fields
+/// at once, see [ComputeShader::layered](crate::compute_shader::ComputeShader::layered).
+/// The image is viewed as a 2D texture array, so it can't be displayed by a [Sprite].
+///
+/// The image data is set to 0, the layers are stored one after the other.
+///
+/// # Panics
+/// - Same as [create_image].
+/// - If `layers` is 0.
+pub fn create_layered_image(params: CreateImageParams, layers: u32) -> Image {
+    assert_ne!(layers, 0);
+
+    let mut image = create_image(params);
+    let mut size = image.texture_descriptor.size;
+    size.depth_or_array_layers = layers;
+    image.resize(size);
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        ..Default::default()
+    });
+    image
+}
+
 /// [Plugin group](PluginGroup) that adds the complete `bevy_pixel_buffer`
 /// suite of plugins:
 /// - [PixelBufferPlugin]
@@ -301,18 +628,110 @@ pub struct PixelBufferPlugin;
 
 impl Plugin for PixelBufferPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PreUpdate, fill)
-            .add_systems(PreUpdate, (resize, sprite_custom_size).after(fill));
+        app.init_resource::<PixelBufferActive>()
+            .init_resource::<crate::gizmos::PixelGizmosStorage>()
+            .add_systems(PreUpdate, fill.run_if(pixel_buffer_active))
+            .add_systems(
+                PreUpdate,
+                (resize::<Sprite>, display_size::<Sprite>)
+                    .after(fill)
+                    .run_if(pixel_buffer_active),
+            )
+            .add_systems(
+                PreUpdate,
+                clear_each_frame
+                    .after(resize::<Sprite>)
+                    .run_if(pixel_buffer_active),
+            )
+            .add_systems(
+                PostUpdate,
+                crate::gizmos::flush_pixel_gizmos.run_if(pixel_buffer_active),
+            )
+            .add_systems(
+                PostUpdate,
+                crate::visibility::update_culled
+                    .after(bevy::render::view::VisibilitySystems::CheckVisibility)
+                    .run_if(pixel_buffer_active),
+            )
+            .add_observer(crate::readback::on_readback_complete)
+            .add_event::<crate::readback::CaptureRequest>()
+            .add_event::<crate::readback::CaptureComplete>()
+            .add_systems(Last, crate::readback::request_captures)
+            .add_observer(crate::readback::on_capture_complete);
+
+        #[cfg(feature = "image")]
+        app.add_systems(
+            Last,
+            (
+                crate::export::record_png_sequence.run_if(pixel_buffer_active),
+                crate::export::auto_save_on_exit,
+            ),
+        );
+    }
+}
+
+/// Global switch to pause the systems of the pixel buffer plugins.
+///
+/// When it's `false`, the buffers are not resized, their visibility is not updated,
+/// [pixel gizmos](crate::gizmos) are not drawn and [compute shaders](crate::compute_shader)
+/// are not dispatched. Useful for a paused game state. It's `true` by default.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_buffer::prelude::*;
+/// fn toggle_pause(keys: Res<ButtonInput<KeyCode>>, mut active: ResMut<PixelBufferActive>) {
+///     if keys.just_pressed(KeyCode::KeyP) {
+///         active.0 = !active.0;
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(toggle_pause);
+/// ```
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut)]
+pub struct PixelBufferActive(pub bool);
+
+impl Default for PixelBufferActive {
+    fn default() -> Self {
+        Self(true)
     }
 }
 
+/// Run condition of the systems of the plugins, see [PixelBufferActive].
+///
+/// Active if the resource does not exist.
+pub fn pixel_buffer_active(active: Option<Res<PixelBufferActive>>) -> bool {
+    active.is_none_or(|active| active.0)
+}
+
+/// Clears the pixel buffer to a color every frame, for immediate mode drawing.
+///
+/// With `Some` color, the [PixelBufferPlugin] fills the whole buffer with it in
+/// [PreUpdate], after the buffer has been resized, so the systems in [Update] always
+/// start drawing on a clean frame. With [None], the default, the content is kept between
+/// frames. Buffers that are [Culled](crate::visibility::Culled) are not cleared.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_buffer::prelude::*;
+/// fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+///     PixelBufferBuilder::new()
+///         .spawn(&mut commands, &mut images)
+///         .entity()
+///         .insert(ClearEachFrame(Some(Pixel::BLACK)));
+/// }
+/// # bevy::ecs::system::assert_is_system(setup);
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClearEachFrame(pub Option<Pixel>);
+
 /// Keeps the size in [PixelBuffer] in sync with the size of the underlying image.
 #[allow(clippy::type_complexity)]
-fn resize(
-    pixel_buffer: Query<(&Sprite, &PixelBuffer), Or<(Changed<PixelBuffer>, Added<Sprite>)>>,
+pub(crate) fn resize<D: PixelBufferDisplay>(
+    pixel_buffer: Query<(&D, &PixelBuffer), Or<(Changed<PixelBuffer>, Added<D>)>>,
     mut images: ResMut<Assets<Image>>,
 ) {
-    for (image_handle, pb) in pixel_buffer.iter() {
+    for (display, pb) in pixel_buffer.iter() {
         let PixelBuffer { size, .. } = pb;
 
         if size.size.x == 0 || size.size.y == 0 || size.pixel_size.x == 0 || size.pixel_size.y == 0
@@ -321,81 +740,149 @@ fn resize(
             return;
         }
 
-        let image = images.get(&image_handle.image).expect("pixel buffer image");
+        let image = images.get(display.image()).expect("pixel buffer image");
 
         if size.size != image.size() {
-            let image = images
-                .get_mut(&image_handle.image)
-                .expect("pixel buffer image");
+            let image = images.get_mut(display.image()).expect("pixel buffer image");
 
             info!("Resizing image to: {:?}", size);
+            let old = pb
+                .fill
+                .preserve_content
+                .then(|| (image.size(), crate::blend::image_pixels(image).to_vec()));
             image.resize(Extent3d {
                 width: size.size.x,
                 height: size.size.y,
                 depth_or_array_layers: 1,
             });
+            if let Some((old_size, old_pixels)) = old {
+                copy_top_left(image, &old_pixels, old_size);
+            }
         }
     }
 }
 
+/// Copies the old content of a resized image to the top left corner
+fn copy_top_left(image: &mut Image, old_pixels: &[Pixel], old_size: UVec2) {
+    let mut frame = Frame::get(image);
+    frame.raw_mut().fill(Pixel::TRANSPARENT);
+    if old_size.x == 0 {
+        return;
+    }
+    let width = old_size.x.min(frame.size().x) as usize;
+    for (y, old_row) in old_pixels
+        .chunks_exact(old_size.x as usize)
+        .enumerate()
+        .take(frame.size().y as usize)
+    {
+        let row = frame.row_mut(y as u32).expect("row inside the frame");
+        row[..width].copy_from_slice(&old_row[..width]);
+    }
+}
+
 /// Changes the size of the pixel buffer to match the fill
-fn fill(
+pub(crate) fn fill(
     mut pixel_buffer: Query<&mut PixelBuffer>,
-    primary_window: Query<&Window, With<PrimaryWindow>>,
+    windows: Query<(&Window, Has<PrimaryWindow>)>,
 ) {
     for mut pb in pixel_buffer.iter_mut() {
-        if let Some(fill_area) = get_fill_area(&pb, primary_window.get_single().ok()) {
-            let PixelBuffer { size, fill } = pb.as_ref();
-
-            let new_buffer_size = fill_area.as_uvec2() / size.pixel_size;
-            // Truncate to the fill multiple
-            let new_buffer_size = (new_buffer_size / fill.multiple) * fill.multiple;
-
-            if new_buffer_size != size.size {
-                pb.size.size = new_buffer_size;
-            }
+        let window = target_window(&pb.fill, &windows);
+        let window_size = window.map(|window| Vec2::new(window.width(), window.height()));
+        let scale_factor = window.map_or(1.0, Window::scale_factor);
+        let new_size = pb.fill_target_size(window_size, scale_factor);
+        // Make sure to not implicitly deref as mut
+        if new_size != pb.as_ref().size {
+            pb.size = new_size;
         }
     }
 }
 
-/// Changes the sprite custom size
+/// Changes the display size, the sprite custom size for [Sprite]
 #[allow(clippy::type_complexity)]
-fn sprite_custom_size(
-    mut pixel_buffer: Query<(&PixelBuffer, &mut Sprite)>,
-    primary_window: Query<&Window, With<PrimaryWindow>>,
+pub(crate) fn display_size<D: PixelBufferDisplay>(
+    mut pixel_buffer: Query<(&PixelBuffer, &mut D)>,
+    windows: Query<(&Window, Has<PrimaryWindow>)>,
 ) {
-    for (pb, mut sprite) in pixel_buffer.iter_mut() {
-        let mut new_size = pb.size.screen_size().as_vec2();
+    for (pb, mut display) in pixel_buffer.iter_mut() {
+        if !pb.auto_display_size {
+            continue;
+        }
+        let window = target_window(&pb.fill, &windows);
+        let scale_factor = window.map_or(1.0, Window::scale_factor);
+        let mut new_size = pb.size.screen_size().as_vec2() / pb.fill.scale(scale_factor);
 
         // if the sprite needs to stretch
         if pb.fill.stretch {
             // set its size to the fill area
-            if let Some(fill_area) = get_fill_area(pb, primary_window.get_single().ok()) {
+            if let Some(fill_area) = get_fill_area(pb, window) {
                 new_size = fill_area;
             }
         }
 
-        let new_size = Some(new_size);
         // Make sure to not implicitly deref as mut
-        if new_size != sprite.as_ref().custom_size {
-            info!("Resizing sprite to: {:?}", new_size);
-            sprite.custom_size = new_size;
+        if Some(new_size) != display.as_ref().display_size() {
+            info!("Resizing display to: {:?}", new_size);
+            display.set_display_size(new_size);
+        }
+    }
+}
+
+/// Window filled by a [Fill], its target window or the primary window
+fn target_window<'a>(
+    fill: &Fill,
+    windows: &'a Query<(&Window, Has<PrimaryWindow>)>,
+) -> Option<&'a Window> {
+    match fill.window {
+        Some(entity) => windows.get(entity).ok().map(|(window, _)| window),
+        None => windows
+            .iter()
+            .find_map(|(window, primary)| primary.then_some(window)),
+    }
+}
+
+/// Clears the buffers with [ClearEachFrame]
+#[allow(clippy::type_complexity)]
+fn clear_each_frame(
+    pixel_buffers: Query<(&ClearEachFrame, &Sprite), (With<PixelBuffer>, Without<Culled>)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (clear, sprite) in pixel_buffers.iter() {
+        let Some(color) = clear.0 else {
+            continue;
+        };
+        if let Some(image) = images.get_mut(&sprite.image) {
+            Frame::get(image).raw_mut().fill(color);
         }
     }
 }
 
+/// Size of a buffer that fills an area
+pub(crate) fn fill_buffer_size(fill_area: Vec2, size: &PixelBufferSize, fill: &Fill) -> UVec2 {
+    let new_buffer_size = fill_area.as_uvec2() / size.pixel_size;
+    // Truncate to the fill multiple
+    (new_buffer_size / fill.multiple) * fill.multiple
+}
+
 pub(crate) fn get_fill_area(pb: &PixelBuffer, window: Option<&Window>) -> Option<Vec2> {
-    match pb.fill.kind {
+    fill_area(
+        &pb.fill,
+        window.map(|window| Vec2::new(window.width(), window.height())),
+    )
+}
+
+fn fill_area(fill: &Fill, window_size: Option<Vec2>) -> Option<Vec2> {
+    let area = match fill.kind {
         FillKind::None => None,
-        FillKind::Window => window.map(|window| Vec2::new(window.width(), window.height())),
+        FillKind::Window => window_size,
         FillKind::Custom(custom_size) => Some(custom_size),
-    }
+    };
+    area.map(|area| fill.margin.inner_area(area))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::bundle::PixelBufferBundle;
+    use crate::{bundle::PixelBufferBundle, frame::GetFrameFromImages};
 
     #[test]
     fn do_resize_image() {
@@ -405,7 +892,7 @@ mod tests {
             .add_plugins(bevy::asset::AssetPlugin::default())
             .add_plugins(bevy::render::texture::ImagePlugin::default());
 
-        app.add_systems(Update, resize);
+        app.add_systems(Update, resize::<Sprite>);
 
         let initial_size = UVec2::new(5, 5);
         let set_size = UVec2::new(10, 10);
@@ -421,6 +908,7 @@ mod tests {
                 pixel_buffer: PixelBuffer {
                     size: PixelBufferSize::size(set_size),
                     fill: Fill::none(),
+                    ..Default::default()
                 },
                 sprite: Sprite::from_image(image.clone())
             })
@@ -436,6 +924,64 @@ mod tests {
         assert_eq!(set_size, image_size);
     }
 
+    #[test]
+    fn resize_preserving_content() {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default());
+
+        app.add_systems(Update, resize::<Sprite>);
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let mut image = create_image(UVec2::new(3, 2).into());
+        Frame::get(&mut image).per_pixel(|pos, _| Pixel::from([pos.x as u8, pos.y as u8, 0, 255]));
+        let image = images.add(image);
+
+        let pb_id = app
+            .world_mut()
+            .spawn(PixelBufferBundle {
+                pixel_buffer: PixelBuffer {
+                    size: PixelBufferSize::size((4, 4)),
+                    fill: Fill::none().with_preserve_content(true),
+                    ..Default::default()
+                },
+                sprite: Sprite::from_image(image.clone()),
+            })
+            .id();
+        app.update();
+
+        let pixel = |x, y| Pixel::from([x, y, 0, 255]);
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let frame = images.frame(&image);
+        assert_eq!(frame.size(), UVec2::new(4, 4));
+        assert_eq!(
+            frame.row(1).unwrap(),
+            &[pixel(0, 1), pixel(1, 1), pixel(2, 1), Pixel::TRANSPARENT]
+        );
+        assert!(frame
+            .row(2)
+            .unwrap()
+            .iter()
+            .all(|p| *p == Pixel::TRANSPARENT));
+
+        // shrink
+        app.world_mut().get_mut::<PixelBuffer>(pb_id).unwrap().size = PixelBufferSize::size((2, 1));
+        app.update();
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let frame = images.frame(&image);
+        assert_eq!(frame.raw(), &[pixel(0, 0), pixel(1, 0)]);
+
+        // nothing to copy from an empty image
+        let mut image = create_image(UVec2::new(2, 2).into());
+        copy_top_left(&mut image, &[], UVec2::new(0, 2));
+        assert!(Frame::get(&mut image)
+            .raw()
+            .iter()
+            .all(|p| *p == Pixel::TRANSPARENT));
+    }
+
     #[test]
     fn do_resize_sprite() {
         let mut app = App::new();
@@ -444,7 +990,7 @@ mod tests {
             .add_plugins(bevy::asset::AssetPlugin::default())
             .add_plugins(bevy::render::texture::ImagePlugin::default());
 
-        app.add_systems(Update, sprite_custom_size);
+        app.add_systems(Update, display_size::<Sprite>);
 
         let set_size = UVec2::new(10, 10);
 
@@ -457,6 +1003,7 @@ mod tests {
                 pixel_buffer: PixelBuffer {
                     size: PixelBufferSize::size(set_size),
                     fill: Fill::none(),
+                    ..Default::default()
                 },
                 sprite: Sprite::from_image(image),
             })
@@ -469,6 +1016,129 @@ mod tests {
 
         assert!(sprite.custom_size.is_some());
         assert_eq!(size.screen_size(), sprite.custom_size.unwrap().as_uvec2());
+
+        // managed by the user
+        let custom_size = Some(Vec2::new(1.0, 2.0));
+        app.world_mut()
+            .get_mut::<Sprite>(pb_id)
+            .unwrap()
+            .custom_size = custom_size;
+        app.world_mut()
+            .get_mut::<PixelBuffer>(pb_id)
+            .unwrap()
+            .auto_display_size = false;
+        app.update();
+        let sprite = app.world().get::<Sprite>(pb_id).unwrap();
+        assert_eq!(sprite.custom_size, custom_size);
+    }
+
+    #[test]
+    fn toggle_fill() {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default());
+
+        app.add_systems(Update, (fill, resize::<Sprite>).chain());
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let image = images.add(create_image(UVec2::new(5, 5).into()));
+
+        let pb_id = app
+            .world_mut()
+            .spawn(PixelBufferBundle {
+                pixel_buffer: PixelBuffer {
+                    size: PixelBufferSize::size((5, 5)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                sprite: Sprite::from_image(image.clone()),
+            })
+            .id();
+        app.update();
+
+        let set_fill = |app: &mut App, fill: Fill| {
+            let mut pb = app.world_mut().get_mut::<PixelBuffer>(pb_id).unwrap();
+            pb.set_fill(fill);
+            let size = pb.size.size;
+            app.update();
+            let image_size = app
+                .world()
+                .resource::<Assets<Image>>()
+                .get(&image)
+                .unwrap()
+                .size();
+            assert_eq!(
+                app.world().get::<PixelBuffer>(pb_id).unwrap().size.size,
+                size
+            );
+            assert_eq!(image_size, size);
+            size
+        };
+
+        assert_eq!(
+            set_fill(&mut app, Fill::custom((8.0, 6.0))),
+            UVec2::new(8, 6)
+        );
+        // fixed again, keeps the last size
+        assert_eq!(set_fill(&mut app, Fill::none()), UVec2::new(8, 6));
+        assert_eq!(
+            set_fill(&mut app, Fill::custom((3.0, 3.0))),
+            UVec2::new(3, 3)
+        );
+    }
+
+    #[test]
+    fn fill_margin() {
+        let area = Vec2::new(100.0, 50.0);
+        assert_eq!(FillMargin::default().inner_area(area), area);
+        assert_eq!(
+            FillMargin::Fraction(Vec2::new(0.1, 0.25)).inner_area(area),
+            Vec2::new(80.0, 25.0)
+        );
+        assert_eq!(
+            FillMargin::Logical(Vec2::new(10.0, 30.0)).inner_area(area),
+            Vec2::new(80.0, 0.0)
+        );
+
+        let pb = PixelBuffer {
+            size: PixelBufferSize::pixel_size((1, 1)),
+            fill: Fill::custom(area)
+                .with_margin(FillMargin::Logical(Vec2::new(5.0, 5.0)))
+                .with_scaling_multiple(8),
+            ..Default::default()
+        };
+        assert_eq!(pb.fill_target_size(None, 1.0).size, UVec2::new(88, 40));
+    }
+
+    #[test]
+    fn do_swap_image() {
+        let mut images = Assets::<Image>::default();
+        let size = UVec2::new(4, 4);
+        let front = images.add(create_image(size.into()));
+        let back = images.add(create_image(size.into()));
+        let small = images.add(create_image(UVec2::new(2, 2).into()));
+
+        let pb = PixelBuffer {
+            size: PixelBufferSize::size(size),
+            fill: Fill::none(),
+            ..Default::default()
+        };
+        let mut sprite = Sprite::from_image(front.clone());
+
+        let previous = pb.swap_image(&mut sprite, &images, back.clone()).unwrap();
+        assert_eq!(previous, front);
+        assert_eq!(sprite.image, back);
+
+        assert_eq!(
+            pb.swap_image(&mut sprite, &images, small),
+            Err(SwapImageError::SizeMismatch {
+                expected: size,
+                found: UVec2::new(2, 2)
+            })
+        );
+        assert_eq!(sprite.image, back);
     }
 
     #[test]
@@ -493,6 +1163,7 @@ mod tests {
                 pixel_buffer: PixelBuffer {
                     size: PixelBufferSize::size(set_size),
                     fill: Fill::custom(fill_area),
+                    ..Default::default()
                 },
                 sprite: Sprite::from_image(image.clone()),
             })
@@ -503,4 +1174,178 @@ mod tests {
         let size = app.world().get::<PixelBuffer>(pb_id).unwrap().size.size;
         assert_eq!(size, UVec2::new(10, 10));
     }
+
+    #[test]
+    fn fill_target_window() {
+        let mut app = App::new();
+        app.add_systems(Update, fill);
+
+        let mut spawn_window = |width: f32, height: f32| {
+            let mut window = Window::default();
+            window.resolution.set(width, height);
+            app.world_mut().spawn(window).id()
+        };
+        let primary = spawn_window(40.0, 20.0);
+        let second = spawn_window(100.0, 60.0);
+        app.world_mut().entity_mut(primary).insert(PrimaryWindow);
+
+        let mut spawn_buffer = |fill: Fill| {
+            app.world_mut()
+                .spawn(PixelBuffer {
+                    size: PixelBufferSize::pixel_size((2, 2)),
+                    fill,
+                    ..Default::default()
+                })
+                .id()
+        };
+        let on_primary = spawn_buffer(Fill::window());
+        let on_second = spawn_buffer(Fill::none().with_window(second));
+        let on_closed = spawn_buffer(Fill::window().with_window(Entity::PLACEHOLDER));
+
+        app.update();
+        let size = |app: &App, entity| app.world().get::<PixelBuffer>(entity).unwrap().size.size;
+        assert_eq!(size(&app, on_primary), UVec2::new(20, 10));
+        assert_eq!(size(&app, on_second), UVec2::new(50, 30));
+        assert_eq!(size(&app, on_closed), PixelBufferSize::new().size);
+    }
+
+    #[test]
+    fn inactive_fill() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_plugins(PixelBufferPlugin)
+            .insert_resource(PixelBufferActive(false))
+            .add_systems(Update, |mut gizmos: crate::gizmos::PixelGizmos| {
+                gizmos.point(IVec2::ZERO, Pixel::RED)
+            });
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(create_image(UVec2::new(5, 5).into()));
+        let pb_id = app
+            .world_mut()
+            .spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((5, 5)),
+                    fill: Fill::custom((10.0, 10.0)),
+                    ..Default::default()
+                },
+                Sprite::from_image(image.clone()),
+                crate::gizmos::PixelGizmosTarget,
+            ))
+            .id();
+
+        app.update();
+        let size = app.world().get::<PixelBuffer>(pb_id).unwrap().size.size;
+        assert_eq!(size, UVec2::new(5, 5));
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        assert_eq!(images.frame(&image).raw()[0], Pixel::TRANSPARENT);
+
+        app.world_mut().resource_mut::<PixelBufferActive>().0 = true;
+        app.update();
+        let size = app.world().get::<PixelBuffer>(pb_id).unwrap().size.size;
+        assert_eq!(size, UVec2::new(10, 10));
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        assert_eq!(images.frame(&image).size(), UVec2::new(10, 10));
+        assert_eq!(images.frame(&image).raw()[0], Pixel::RED);
+    }
+
+    #[test]
+    fn clear_every_frame() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_systems(Update, clear_each_frame);
+
+        let mut spawn = |clear: Option<Pixel>| {
+            let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+            let mut image = create_image(UVec2::new(2, 2).into());
+            Frame::get(&mut image).raw_mut().fill(Pixel::WHITE);
+            let image = images.add(image);
+            app.world_mut().spawn((
+                PixelBuffer {
+                    size: PixelBufferSize::size((2, 2)),
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image.clone()),
+                ClearEachFrame(clear),
+            ));
+            image
+        };
+        let cleared = spawn(Some(Pixel::RED));
+        let kept = spawn(None);
+        app.update();
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        assert_eq!(images.frame(&cleared).raw(), &[Pixel::RED; 4]);
+        assert_eq!(images.frame(&kept).raw(), &[Pixel::WHITE; 4]);
+    }
+
+    #[test]
+    fn screenshottable_usages() {
+        let image = create_image(CreateImageParams::from(UVec2::new(4, 4)).screenshottable());
+        let usage = image.texture_descriptor.usage;
+        assert!(usage.contains(TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC));
+        assert!(usage.contains(CreateImageParams::default().usage));
+    }
+
+    #[test]
+    fn layered_image() {
+        let image = create_layered_image(UVec2::new(4, 2).into(), 3);
+        assert_eq!(image.texture_descriptor.size.depth_or_array_layers, 3);
+        assert_eq!(image.size(), UVec2::new(4, 2));
+        assert_eq!(image.data.len(), 4 * 2 * 3 * 4);
+    }
+
+    #[test]
+    fn non_square_pixels() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_systems(
+                Update,
+                (fill, resize::<Sprite>, display_size::<Sprite>).chain(),
+            );
+
+        let size = PixelBufferSize {
+            size: UVec2::new(10, 10),
+            pixel_size: UVec2::new(2, 3),
+        };
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        let image = images.add(create_image(size.size.into()));
+        let pb_id = app
+            .world_mut()
+            .spawn((
+                PixelBuffer {
+                    size,
+                    fill: Fill::none(),
+                    ..Default::default()
+                },
+                Sprite::from_image(image.clone()),
+            ))
+            .id();
+
+        app.update();
+        let sprite = app.world().get::<Sprite>(pb_id).unwrap();
+        assert_eq!(sprite.custom_size, Some(Vec2::new(20.0, 30.0)));
+
+        // filling an area keeps the pixels 2x3
+        app.world_mut()
+            .get_mut::<PixelBuffer>(pb_id)
+            .unwrap()
+            .set_fill(Fill::custom((40.0, 45.0)));
+        app.update();
+        let pb = app.world().get::<PixelBuffer>(pb_id).unwrap();
+        assert_eq!(pb.size.size, UVec2::new(20, 15));
+        let sprite = app.world().get::<Sprite>(pb_id).unwrap();
+        assert_eq!(sprite.custom_size, Some(Vec2::new(40.0, 45.0)));
+        let images = app.world().resource::<Assets<Image>>();
+        assert_eq!(images.get(&image).unwrap().size(), UVec2::new(20, 15));
+    }
 }
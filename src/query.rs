@@ -147,15 +147,151 @@ impl<'w, 's> QueryPixelBuffer<'w, 's> {
         &mut self.images
     }
 
+    /// Iterates over the pixel buffers of a specific size, in pixels of the buffer.
+    pub fn iter_sized(&self, size: UVec2) -> impl Iterator<Item = PixelBuffersReadOnlyItem<'_>> {
+        self.query
+            .iter()
+            .filter(move |item| item.pixel_buffer.size.size == size)
+    }
+
     /// Gets the query and images resource
     pub fn split(self) -> (Query<'w, 's, PixelBuffers>, ResMut<'w, Assets<Image>>) {
         (self.query, self.images)
     }
 }
 
+/// System parameter to change the color that tints the displayed pixel buffers.
+///
+/// It changes the [Sprite::color] of the buffers, so it cannot be used in the same
+/// system as [QueryPixelBuffer] or a [PixelBuffers] query, which read the sprite. Use it
+/// in a separate system.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_buffer::prelude::*;
+/// fn fade_out(mut tint: PixelBufferTint, time: Res<Time>) {
+///     let alpha = (1.0 - time.elapsed_secs()).max(0.0);
+///     tint.set_display_tint(Color::WHITE.with_alpha(alpha));
+/// }
+/// # bevy::ecs::system::assert_is_system(fade_out);
+/// ```
+#[derive(SystemParam)]
+pub struct PixelBufferTint<'w, 's> {
+    sprites: Query<'w, 's, &'static mut Sprite, With<PixelBuffer>>,
+}
+
+impl PixelBufferTint<'_, '_> {
+    /// Sets the color that tints the displayed pixel buffer.
+    ///
+    /// When displayed, each channel of the pixels is multiplied by the same channel of the
+    /// tint, including the alpha. White shows the pixels as they are, a darker color
+    /// dims them and an alpha below `1.0` fades them out. The pixel data is not modified,
+    /// so it's a cheap way to flash or fade the whole display.
+    ///
+    /// # Panics
+    /// If there are none or more than one pixel buffers. This method is
+    /// intented to be used when there's only one pixel buffer, see
+    /// [set_entity_display_tint](PixelBufferTint::set_entity_display_tint) otherwise.
+    pub fn set_display_tint(&mut self, color: impl Into<Color>) {
+        self.sprites.single_mut().color = color.into();
+    }
+
+    /// Sets the tint of the pixel buffer of an entity, like
+    /// [set_display_tint](PixelBufferTint::set_display_tint).
+    ///
+    /// Returns `false` if the entity is not a pixel buffer.
+    pub fn set_entity_display_tint(&mut self, entity: Entity, color: impl Into<Color>) -> bool {
+        let Ok(mut sprite) = self.sprites.get_mut(entity) else {
+            return false;
+        };
+        sprite.color = color.into();
+        true
+    }
+}
+
 impl<'w, 's> GetFrame for QueryPixelBuffer<'w, 's> {
     fn frame(&mut self) -> Frame<'_> {
-        let image_handle = &self.query.single().sprite.image;
-        Frame::extract(&mut self.images, &image_handle)
+        let item = self.query.single();
+        let edge_mode = item.pixel_buffer.edge_mode;
+        Frame::extract(&mut self.images, &item.sprite.image).with_edge_mode(edge_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::{frame::AddressMode, pixel::Pixel, pixel_buffer::PixelBufferSize};
+
+    #[test]
+    fn iter_sized() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        let mut spawn = |size: (u32, u32)| {
+            world
+                .spawn((
+                    PixelBuffer {
+                        size: PixelBufferSize::size(size),
+                        ..Default::default()
+                    },
+                    Sprite::default(),
+                ))
+                .id()
+        };
+        let small = [spawn((8, 8)), spawn((8, 8))];
+        let _large = spawn((16, 8));
+
+        let mut state = SystemState::<QueryPixelBuffer>::new(&mut world);
+        let pb = state.get_mut(&mut world);
+        let mut sized: Vec<_> = pb
+            .iter_sized(UVec2::new(8, 8))
+            .map(|item| item.entity)
+            .collect();
+        sized.sort();
+        assert_eq!(sized, small);
+        assert_eq!(pb.iter_sized(UVec2::new(8, 16)).count(), 0);
+    }
+
+    #[test]
+    fn frame_edge_mode() {
+        let mut world = World::new();
+        let image = world
+            .get_resource_or_init::<Assets<Image>>()
+            .add(crate::pixel_buffer::create_image(UVec2::new(4, 4).into()));
+        world.spawn((
+            PixelBuffer {
+                size: PixelBufferSize::size((4, 4)),
+                edge_mode: AddressMode::Wrap,
+                ..Default::default()
+            },
+            Sprite::from_image(image),
+        ));
+
+        let mut state = SystemState::<QueryPixelBuffer>::new(&mut world);
+        let mut pb = state.get_mut(&mut world);
+        let mut frame = pb.frame();
+        assert_eq!(frame.edge_mode(), AddressMode::Wrap);
+        frame.set((3, 3), Pixel::RED).unwrap();
+        assert_eq!(frame.sample(IVec2::new(-1, -1)), Pixel::RED);
+    }
+
+    #[test]
+    fn display_tint() {
+        let mut world = World::new();
+        let pb = world
+            .spawn((PixelBuffer::default(), Sprite::default()))
+            .id();
+        let other = world.spawn(Sprite::default()).id();
+
+        let mut state = SystemState::<PixelBufferTint>::new(&mut world);
+        let mut tint = state.get_mut(&mut world);
+        tint.set_display_tint(Color::BLACK);
+        assert!(!tint.set_entity_display_tint(other, Color::BLACK));
+        state.apply(&mut world);
+
+        assert_eq!(world.get::<Sprite>(pb).unwrap().color, Color::BLACK);
+        assert_eq!(world.get::<Sprite>(other).unwrap().color, Color::WHITE);
     }
 }
@@ -0,0 +1,347 @@
+//! Reading the pixels of a pixel buffer back from the GPU.
+//!
+//! When a pixel buffer is updated on the GPU, for example with a
+//! [ComputeShader](crate::compute_shader::ComputeShader), the CPU side [Image] data is not
+//! updated. Bevy's [Readback] component copies the texture back to the CPU, and this module
+//! adds some conveniences on top of it.
+//!
+//! For the readback to work, the image must have the [TextureUsages::COPY_SRC] usage. It can be
+//! set with [CreateImageParams::usage](crate::pixel_buffer::CreateImageParams::usage).
+//!
+//! To read a buffer back only once, for example to save what a compute shader generated,
+//! send a [CaptureRequest] and read the [CaptureComplete] event, see [CaptureRequest].
+//!
+//! # Example
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy::render::gpu_readback::Readback;
+//! # use bevy_pixel_buffer::readback::OnReadback;
+//! fn setup_readback(mut commands: Commands, pixel_buffer: Single<(Entity, &Sprite)>) {
+//!     let (entity, sprite) = *pixel_buffer;
+//!     commands.entity(entity).insert((
+//!         Readback::texture(sprite.image.clone()),
+//!         OnReadback::new(|data| info!("Read {} bytes", data.len())),
+//!     ));
+//! }
+//! # bevy::ecs::system::assert_is_system(setup_readback);
+//! ```
+
+use bevy::{
+    prelude::*,
+    render::{
+        gpu_readback::{Readback, ReadbackComplete},
+        renderer::RenderDevice,
+    },
+};
+
+use crate::{pixel::Pixel, pixel_buffer::PixelBuffer};
+
+#[allow(unused)] // doc link
+use bevy::render::render_resource::TextureUsages;
+
+/// Callback invoked with the raw pixel data every time a [Readback] of the entity completes.
+///
+/// The data is the same as [Image::data], row by row without the padding of the GPU copy,
+/// the layout of the pixels is [Pixel](crate::pixel::Pixel). Readbacks of buffers are
+/// passed unchanged.
+///
+/// # Scheduling
+/// The callback runs in the main world, on the thread that runs the render app extraction,
+/// with exclusive access to the world. This happens after the main schedule of a frame
+/// has run and once the GPU buffer of the readback has been mapped, so the data is usually
+/// one or two frames old.
+///
+/// The callback is invoked for every completed readback. To only get one, remove the
+/// [Readback] component after the first call.
+#[derive(Component)]
+pub struct OnReadback(pub Box<ReadbackCallback>);
+
+/// Function called by [OnReadback]
+pub type ReadbackCallback = dyn FnMut(&[u8]) + Send + Sync;
+
+impl OnReadback {
+    /// Creates a new callback
+    pub fn new(callback: impl FnMut(&[u8]) + Send + Sync + 'static) -> Self {
+        Self(Box::new(callback))
+    }
+}
+
+/// Observer that runs the [OnReadback] callbacks
+pub(crate) fn on_readback_complete(
+    trigger: Trigger<ReadbackComplete>,
+    mut callbacks: Query<(&mut OnReadback, Option<&Readback>)>,
+    images: Res<Assets<Image>>,
+) {
+    let Ok((mut callback, readback)) = callbacks.get_mut(trigger.entity()) else {
+        return;
+    };
+    let data = &trigger.event().0;
+    let image = match readback {
+        Some(Readback::Texture(image)) => images.get(image),
+        _ => None,
+    };
+    match image {
+        Some(image) => (callback.0)(&unpadded_rows(data, image.size())),
+        None => (callback.0)(data),
+    }
+}
+
+/// Event to read the pixels of a pixel buffer back from the GPU once.
+///
+/// The image of the buffer is read once, and a [CaptureComplete] event is sent with its
+/// pixels, without the overhead of a continuous [Readback]. The image needs the
+/// [TextureUsages::COPY_SRC] usage.
+///
+/// # Latency
+/// The image is copied when the frame in which the request is sent is rendered, after its
+/// [compute shaders](crate::compute_shader) run, so it has their result. The copy is
+/// delivered when the GPU finishes, while the render app extracts a later frame. The
+/// [CaptureComplete] event can usually be read one or two frames after the request, more
+/// if the GPU is busy. Requests for entities that are not pixel buffers are ignored.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_buffer::{prelude::*, readback::{CaptureComplete, CaptureRequest}};
+/// fn request(
+///     keys: Res<ButtonInput<KeyCode>>,
+///     pixel_buffer: Single<Entity, With<PixelBuffer>>,
+///     mut requests: EventWriter<CaptureRequest>,
+/// ) {
+///     if keys.just_pressed(KeyCode::KeyP) {
+///         requests.send(CaptureRequest {
+///             entity: *pixel_buffer,
+///         });
+///     }
+/// }
+///
+/// fn complete(mut captures: EventReader<CaptureComplete>) {
+///     for capture in captures.read() {
+///         info!("Captured {} pixels", capture.pixels().len());
+///     }
+/// }
+/// # bevy::ecs::system::assert_is_system(request);
+/// # bevy::ecs::system::assert_is_system(complete);
+/// ```
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureRequest {
+    /// Pixel buffer to capture
+    pub entity: Entity,
+}
+
+/// Event with the pixels read back for a [CaptureRequest].
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct CaptureComplete {
+    /// Captured pixel buffer
+    pub entity: Entity,
+    /// Size of the image when it was requested
+    pub size: UVec2,
+    /// Pixel data, like [Image::data], row by row without padding
+    pub data: Vec<u8>,
+}
+
+impl CaptureComplete {
+    /// The captured pixels
+    pub fn pixels(&self) -> &[Pixel] {
+        bytemuck::cast_slice(&self.data)
+    }
+}
+
+/// [Readback] of a [CaptureRequest], on its own entity so it doesn't replace one of the
+/// pixel buffer
+#[derive(Component)]
+pub(crate) struct PendingCapture {
+    target: Entity,
+    size: UVec2,
+    /// Another readback can complete before the entity is despawned
+    done: bool,
+}
+
+/// Starts the readbacks of the [CaptureRequest]s
+pub(crate) fn request_captures(
+    mut commands: Commands,
+    mut requests: EventReader<CaptureRequest>,
+    pixel_buffers: Query<&Sprite, With<PixelBuffer>>,
+    images: Res<Assets<Image>>,
+) {
+    for request in requests.read() {
+        let Ok(sprite) = pixel_buffers.get(request.entity) else {
+            continue;
+        };
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        commands.spawn((
+            Readback::texture(sprite.image.clone()),
+            PendingCapture {
+                target: request.entity,
+                size: image.size(),
+                done: false,
+            },
+        ));
+    }
+}
+
+/// Observer that sends the [CaptureComplete] events
+pub(crate) fn on_capture_complete(
+    trigger: Trigger<ReadbackComplete>,
+    mut commands: Commands,
+    mut captures: Query<&mut PendingCapture>,
+    mut complete: EventWriter<CaptureComplete>,
+) {
+    let Ok(mut capture) = captures.get_mut(trigger.entity()) else {
+        return;
+    };
+    if capture.done {
+        return;
+    }
+    capture.done = true;
+    commands.entity(trigger.entity()).despawn();
+    complete.send(CaptureComplete {
+        entity: capture.target,
+        size: capture.size,
+        data: unpadded_rows(&trigger.event().0, capture.size),
+    });
+}
+
+/// Removes the padding at the end of the rows of a texture copied to a buffer
+fn unpadded_rows(data: &[u8], size: UVec2) -> Vec<u8> {
+    let row = size.x as usize * size_of::<Pixel>();
+    let padded_row = RenderDevice::align_copy_bytes_per_row(row);
+    if row == padded_row || data.len() != padded_row * size.y as usize {
+        return data.to_vec();
+    }
+    data.chunks_exact(padded_row)
+        .flat_map(|padded| &padded[..row])
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn callback_receives_data() {
+        let mut app = App::new();
+        app.init_resource::<Assets<Image>>()
+            .add_observer(on_readback_complete);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let entity = app
+            .world_mut()
+            .spawn(OnReadback::new(move |data| {
+                sink.lock().unwrap().extend_from_slice(data)
+            }))
+            .id();
+
+        app.world_mut()
+            .trigger_targets(ReadbackComplete(vec![1, 2, 3, 4]), entity);
+
+        assert_eq!(*received.lock().unwrap(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn callback_data_is_unpadded() {
+        let mut app = App::new();
+        app.init_resource::<Assets<Image>>()
+            .add_observer(on_readback_complete);
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(crate::pixel_buffer::create_image(UVec2::new(3, 2).into()));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let entity = app
+            .world_mut()
+            .spawn((
+                Readback::texture(image),
+                OnReadback::new(move |data| sink.lock().unwrap().extend_from_slice(data)),
+            ))
+            .id();
+
+        // 3 pixels are 12 bytes, padded to 256 per row
+        let mut data = vec![0; 256 * 2];
+        data[..12].fill(1);
+        data[256..268].fill(2);
+        app.world_mut()
+            .trigger_targets(ReadbackComplete(data), entity);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3 * 2 * 4);
+        assert!(received[..12].iter().all(|&b| b == 1));
+        assert!(received[12..].iter().all(|&b| b == 2));
+    }
+
+    #[test]
+    fn capture_once() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_event::<CaptureRequest>()
+            .add_event::<CaptureComplete>()
+            .add_systems(Last, request_captures)
+            .add_observer(on_capture_complete);
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(crate::pixel_buffer::create_image(UVec2::new(2, 2).into()));
+        let entity = app
+            .world_mut()
+            .spawn((PixelBuffer::default(), Sprite::from_image(image)))
+            .id();
+
+        app.world_mut().send_event(CaptureRequest { entity });
+        app.update();
+        let readback = app
+            .world_mut()
+            .query_filtered::<Entity, With<Readback>>()
+            .single(app.world());
+
+        // as the render app does, a second readback can complete before the despawn
+        let data = vec![7; 2 * 2 * 4];
+        app.world_mut()
+            .trigger_targets(ReadbackComplete(data.clone()), readback);
+        app.world_mut()
+            .trigger_targets(ReadbackComplete(data.clone()), readback);
+        app.update();
+
+        let captures: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Events<CaptureComplete>>()
+            .drain()
+            .collect();
+        assert_eq!(
+            captures,
+            vec![CaptureComplete {
+                entity,
+                size: UVec2::new(2, 2),
+                data
+            }]
+        );
+        assert!(app.world().get_entity(readback).is_err());
+    }
+
+    #[test]
+    fn unpad() {
+        // 3 pixels are 12 bytes, padded to 256 per row
+        let size = UVec2::new(3, 2);
+        let mut data = vec![0; 256 * 2];
+        data[..12].fill(1);
+        data[256..268].fill(2);
+        let unpadded = unpadded_rows(&data, size);
+        assert_eq!(unpadded.len(), 24);
+        assert!(unpadded[..12].iter().all(|&b| b == 1));
+        assert!(unpadded[12..].iter().all(|&b| b == 2));
+
+        // 64 pixels are already aligned
+        let data = vec![3; 256 * 2];
+        assert_eq!(unpadded_rows(&data, UVec2::new(64, 2)), data);
+    }
+}
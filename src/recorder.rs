@@ -0,0 +1,120 @@
+//! Recording frames in memory, to save them as an animation.
+//!
+//! A [FrameRecorder] keeps [snapshots](Frame::snapshot) of the frames it's given, up to a
+//! maximum, so the memory it uses is bounded. With the `image` feature they can be saved
+//! as an animated PNG with [FrameRecorder::save_apng], to capture an animated effect.
+//!
+//! For long recordings, [PngSequenceRecorder](crate::export::PngSequenceRecorder) writes
+//! every frame to disk instead.
+//!
+//! # Example
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::prelude::*;
+//! #[derive(Resource)]
+//! struct Recording(FrameRecorder);
+//!
+//! fn record(
+//!     mut recording: ResMut<Recording>,
+//!     mut images: ResMut<Assets<Image>>,
+//!     pb: Single<&Sprite, With<PixelBuffer>>,
+//! ) {
+//!     recording.0.record(&images.frame(&pb.image));
+//! }
+//! # bevy::ecs::system::assert_is_system(record);
+//! ```
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::frame::{Frame, OwnedFrame};
+
+/// Snapshots of the last frames, to save them as an animation.
+///
+/// See the [module documentation](crate::recorder).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameRecorder {
+    frames: VecDeque<OwnedFrame>,
+    max_frames: usize,
+}
+
+impl FrameRecorder {
+    /// Keeps up to `max_frames` frames, dropping the oldest ones. The minimum is 1.
+    pub fn new(max_frames: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            max_frames: max_frames.max(1),
+        }
+    }
+
+    /// Adds a snapshot of the frame.
+    ///
+    /// The oldest frame is dropped if there are already
+    /// [max_frames](FrameRecorder::max_frames). An animation has a single size, so the
+    /// previous frames are dropped too if the frame has a different size than them, for
+    /// example after the buffer is resized.
+    pub fn record(&mut self, frame: &Frame) {
+        if self.size().is_some_and(|size| size != frame.size()) {
+            self.frames.clear();
+        }
+        if self.frames.len() == self.max_frames {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame.snapshot());
+    }
+
+    /// Size of the recorded frames, [None] if there are none
+    pub fn size(&self) -> Option<UVec2> {
+        self.frames.front().map(OwnedFrame::size)
+    }
+
+    /// Recorded frames, from the oldest
+    pub fn frames(&self) -> impl ExactSizeIterator<Item = &OwnedFrame> {
+        self.frames.iter()
+    }
+
+    /// Number of recorded frames
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// If there are no recorded frames
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Maximum number of frames that are kept
+    pub fn max_frames(&self) -> usize {
+        self.max_frames
+    }
+
+    /// Drops all the recorded frames
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel::Pixel;
+
+    #[test]
+    fn bounded() {
+        let mut recorder = FrameRecorder::new(2);
+        for color in [Pixel::RED, Pixel::GREEN, Pixel::BLUE] {
+            let mut pixels = vec![color; 4];
+            recorder.record(&Frame::from_raw_parts(&mut pixels, UVec2::new(2, 2)));
+        }
+        assert_eq!(recorder.len(), 2);
+        let first: Vec<_> = recorder.frames().map(|frame| frame.raw()[0]).collect();
+        assert_eq!(first, vec![Pixel::GREEN, Pixel::BLUE]);
+
+        // a different size starts again
+        let mut pixels = vec![Pixel::WHITE; 3];
+        recorder.record(&Frame::from_raw_parts(&mut pixels, UVec2::new(3, 1)));
+        assert_eq!(recorder.len(), 1);
+        assert_eq!(recorder.size(), Some(UVec2::new(3, 1)));
+    }
+}
@@ -0,0 +1,305 @@
+//! Compact run-length encoding of the pixels of a [Frame].
+//!
+//! Buffers that are mostly a single color, like UI or pixel art, are much smaller
+//! encoded this way than raw, which is useful for save files or sending a canvas
+//! over the network. The format has no dependencies and is very simple:
+//!
+//! | Bytes | Content                                                     |
+//! | ----- | ----------------------------------------------------------- |
+//! | 4     | Magic, `PBRL`                                               |
+//! | 1     | Version, `1`                                                |
+//! | 1     | Pixel format, `0` for [Pixel::FORMAT]                       |
+//! | 4     | Width, little endian `u32`                                  |
+//! | 4     | Height, little endian `u32`                                 |
+//! | ...   | Runs: length as a LEB128 varint followed by 4 bytes of RGBA |
+//!
+//! # Example
+//! ```
+//! # use bevy::math::UVec2;
+//! # use bevy_pixel_buffer::prelude::*;
+//! # let mut pixels = vec![Pixel::BLACK; 10*10];
+//! # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+//! let encoded = frame.to_rle();
+//! assert!(encoded.len() < frame.raw().len() * 4);
+//!
+//! frame.per_pixel(|_, _| Pixel::WHITE);
+//! frame.load_rle(&encoded).unwrap();
+//! assert!(frame.raw().iter().all(|p| *p == Pixel::BLACK));
+//! ```
+
+use std::ops::Range;
+
+use bevy::math::UVec2;
+
+use crate::{
+    frame::{Frame, OwnedFrame},
+    pixel::Pixel,
+};
+
+const MAGIC: &[u8; 4] = b"PBRL";
+const VERSION: u8 = 1;
+const FORMAT_RGBA8: u8 = 0;
+const HEADER_LEN: usize = 14;
+
+/// Error decoding run-length encoded pixels
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RleError {
+    /// The data does not start with the expected header
+    #[error("invalid run-length encoding header")]
+    InvalidHeader,
+    /// The version or pixel format is not supported
+    #[error("unsupported run-length encoding version {version} or format {format}")]
+    Unsupported {
+        /// version in the header
+        version: u8,
+        /// pixel format in the header
+        format: u8,
+    },
+    /// The data ended in the middle of a run
+    #[error("run-length encoded data is truncated")]
+    Truncated,
+    /// The runs do not add up to the size in the header
+    #[error("run-length encoded runs do not match the size (expected {expected} pixels)")]
+    InvalidRuns {
+        /// number of pixels in the header size
+        expected: usize,
+    },
+    /// The encoded size is not the size of the frame
+    #[error("size mismatch (encoded: {encoded:?}, frame: {frame:?})")]
+    SizeMismatch {
+        /// encoded size
+        encoded: UVec2,
+        /// frame size
+        frame: UVec2,
+    },
+}
+
+impl<'a> Frame<'a> {
+    /// Encodes the pixels of the frame. See the [module documentation](crate::rle).
+    pub fn to_rle(&self) -> Vec<u8> {
+        let size = self.size();
+        let mut data = Vec::with_capacity(HEADER_LEN + 16);
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.push(FORMAT_RGBA8);
+        data.extend_from_slice(&size.x.to_le_bytes());
+        data.extend_from_slice(&size.y.to_le_bytes());
+
+        let mut pixels = self.raw().iter();
+        if let Some(&first) = pixels.next() {
+            let mut current = first;
+            let mut len = 1u64;
+            for &pixel in pixels {
+                if pixel == current {
+                    len += 1;
+                } else {
+                    push_run(&mut data, len, current);
+                    current = pixel;
+                    len = 1;
+                }
+            }
+            push_run(&mut data, len, current);
+        }
+        data
+    }
+
+    /// Overwrites the pixels of the frame with run-length encoded data.
+    ///
+    /// The encoded size has to be the same as the frame size. Use [OwnedFrame::from_rle]
+    /// when the size is not known. The frame is left unchanged if the data is invalid.
+    pub fn load_rle(&mut self, data: &[u8]) -> Result<(), RleError> {
+        let encoded = read_header(data)?;
+        if encoded != self.size() {
+            return Err(RleError::SizeMismatch {
+                encoded,
+                frame: self.size(),
+            });
+        }
+        let runs = &data[HEADER_LEN..];
+        let pixels = self.raw_mut();
+        for_each_run(runs, pixels.len(), |_, _| {})?;
+        for_each_run(runs, pixels.len(), |range, pixel| pixels[range].fill(pixel))
+    }
+}
+
+impl OwnedFrame {
+    /// Decodes run-length encoded data into a new frame of the encoded size.
+    /// See the [module documentation](crate::rle).
+    pub fn from_rle(data: &[u8]) -> Result<Self, RleError> {
+        let (size, pixels) = decode(data)?;
+        Ok(OwnedFrame::from_pixels(pixels, size))
+    }
+}
+
+/// Decodes run-length encoded data into its size and pixels.
+///
+/// The data is validated before allocating the pixels, so the size in the header
+/// alone cannot make it allocate.
+pub fn decode(data: &[u8]) -> Result<(UVec2, Vec<Pixel>), RleError> {
+    let size = read_header(data)?;
+    let len = (size.x as usize)
+        .checked_mul(size.y as usize)
+        .ok_or(RleError::InvalidHeader)?;
+    let runs = &data[HEADER_LEN..];
+    for_each_run(runs, len, |_, _| {})?;
+    let mut pixels = Vec::with_capacity(len);
+    for_each_run(runs, len, |range, pixel| pixels.resize(range.end, pixel))?;
+    Ok((size, pixels))
+}
+
+fn push_run(data: &mut Vec<u8>, mut len: u64, pixel: Pixel) {
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            data.push(byte);
+            break;
+        }
+        data.push(byte | 0x80);
+    }
+    data.extend_from_slice(bytemuck::bytes_of(&pixel));
+}
+
+fn read_header(data: &[u8]) -> Result<UVec2, RleError> {
+    if data.len() < HEADER_LEN || &data[..4] != MAGIC {
+        return Err(RleError::InvalidHeader);
+    }
+    let (version, format) = (data[4], data[5]);
+    if version != VERSION || format != FORMAT_RGBA8 {
+        return Err(RleError::Unsupported { version, format });
+    }
+    let width = u32::from_le_bytes(data[6..10].try_into().unwrap());
+    let height = u32::from_le_bytes(data[10..14].try_into().unwrap());
+    Ok(UVec2::new(width, height))
+}
+
+/// Calls `f` with the range of pixels and the color of every run, checking that
+/// the runs add up to `len` pixels.
+fn for_each_run(
+    mut runs: &[u8],
+    len: usize,
+    mut f: impl FnMut(Range<usize>, Pixel),
+) -> Result<(), RleError> {
+    let invalid = RleError::InvalidRuns { expected: len };
+    let mut written = 0;
+    while !runs.is_empty() {
+        let mut run = 0u64;
+        let mut shift = 0;
+        loop {
+            let (&byte, rest) = runs.split_first().ok_or(RleError::Truncated)?;
+            runs = rest;
+            if shift >= 64 {
+                return Err(invalid);
+            }
+            run |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        if runs.len() < 4 {
+            return Err(RleError::Truncated);
+        }
+        let pixel: Pixel = bytemuck::pod_read_unaligned(&runs[..4]);
+        runs = &runs[4..];
+
+        let run = usize::try_from(run).map_err(|_| invalid.clone())?;
+        if run == 0 || run > len - written {
+            return Err(invalid);
+        }
+        f(written..written + run, pixel);
+        written += run;
+    }
+
+    if written != len {
+        return Err(invalid);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let size = UVec2::new(300, 2);
+        let mut pixels = vec![Pixel::BLUE; 600];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.set((7, 0), Pixel::RED).unwrap();
+        frame.set((299, 1), Pixel::GREEN).unwrap();
+
+        let encoded = frame.to_rle();
+        // header + 4 runs, one of them with a 2 bytes length
+        assert_eq!(encoded.len(), HEADER_LEN + 4 * 5 + 1);
+
+        let (decoded_size, decoded) = decode(&encoded).unwrap();
+        assert_eq!(decoded_size, size);
+        assert_eq!(decoded, frame.raw());
+
+        let owned = OwnedFrame::from_rle(&encoded).unwrap();
+        assert_eq!(owned.size(), size);
+        assert_eq!(owned.raw(), frame.raw());
+    }
+
+    #[test]
+    fn invalid_data() {
+        let mut pixels = vec![Pixel::BLUE; 4];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 2));
+        let encoded = frame.to_rle();
+
+        assert_eq!(decode(&encoded[..5]), Err(RleError::InvalidHeader));
+        assert_eq!(
+            decode(&encoded[..encoded.len() - 1]),
+            Err(RleError::Truncated)
+        );
+
+        let mut pixels = vec![Pixel::BLUE; 2];
+        let mut other = Frame::from_raw_parts(&mut pixels, UVec2::new(1, 2));
+        assert!(matches!(
+            other.load_rle(&encoded),
+            Err(RleError::SizeMismatch { .. })
+        ));
+
+        let mut too_long = encoded.clone();
+        too_long.extend_from_slice(&[1, 0, 0, 0, 0]);
+        assert_eq!(
+            frame.load_rle(&too_long),
+            Err(RleError::InvalidRuns { expected: 4 })
+        );
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let mut pixels = vec![Pixel::BLUE; 4];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(2, 2));
+        let header = frame.to_rle()[..HEADER_LEN].to_vec();
+
+        // a run of u64::MAX pixels
+        let mut huge_run = header.clone();
+        push_run(&mut huge_run, u64::MAX, Pixel::RED);
+        assert_eq!(
+            decode(&huge_run),
+            Err(RleError::InvalidRuns { expected: 4 })
+        );
+
+        // the frame is unchanged when a run after a valid one is too long
+        let mut partial = header;
+        push_run(&mut partial, 2, Pixel::RED);
+        push_run(&mut partial, 3, Pixel::RED);
+        assert!(frame.load_rle(&partial).is_err());
+        assert!(frame.raw().iter().all(|p| *p == Pixel::BLUE));
+
+        // the largest size with a single pixel is rejected without allocating
+        let mut huge_size = MAGIC.to_vec();
+        huge_size.extend_from_slice(&[VERSION, FORMAT_RGBA8]);
+        huge_size.extend_from_slice(&u32::MAX.to_le_bytes());
+        huge_size.extend_from_slice(&u32::MAX.to_le_bytes());
+        push_run(&mut huge_size, 1, Pixel::RED);
+        assert!(matches!(
+            decode(&huge_size),
+            Err(RleError::InvalidRuns { .. })
+        ));
+    }
+}
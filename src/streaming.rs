@@ -0,0 +1,469 @@
+//! Streaming the texture upload of very large pixel buffers in horizontal bands.
+//!
+//! When the [Image] of a pixel buffer is modified, Bevy uploads the whole texture again,
+//! which stalls for huge canvases. A [StreamedUpload] makes the upload of the image a
+//! stream of bands of rows instead. The [StreamedUploadPlugin] writes only the bands
+//! marked with [mark_dirty](StreamedUpload::mark_dirty) from the image data into the
+//! existing texture, at most [bands_per_frame](StreamedUpload::with_bands_per_frame) of
+//! them each frame.
+//!
+//! The pixels are still edited in the image, with a [Frame](crate::frame::Frame) as usual,
+//! so everything else that reads the image sees the changes. While streaming, the image
+//! is not uploaded by Bevy, so changes outside of the marked bands are not shown. When
+//! the image is resized or replaced it is uploaded whole once, and streaming starts again
+//! after it.
+//!
+//! # Latency
+//! With the default settings every changed band is uploaded in the next frame, split in
+//! one write per band. Limiting the bands per frame spreads a full update over
+//! [frames_for_full_update](StreamedUpload::frames_for_full_update) frames, during which
+//! the texture shows part of the old and part of the new content. Bands are uploaded in
+//! order, starting after the last one uploaded, so all of them are eventually updated even
+//! if some change every frame.
+//!
+//! # Example
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::prelude::*;
+//! fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+//!     PixelBufferBuilder::new()
+//!         .with_size(PixelBufferSize::size((8192, 8192)))
+//!         .spawn(&mut commands, &mut images)
+//!         .entity()
+//!         .insert(StreamedUpload::new(256).with_bands_per_frame(4));
+//! }
+//!
+//! fn paint(mut pb: QueryPixelBuffer, mut streamed: Single<&mut StreamedUpload>) {
+//!     let mut frame = pb.frame();
+//!     for y in 10..20 {
+//!         for x in 10..20 {
+//!             frame.set((x, y), Pixel::RED).unwrap();
+//!         }
+//!     }
+//!     // uploads the bands of the rectangle
+//!     streamed.mark_dirty(URect::new(10, 10, 20, 20));
+//! }
+//!
+//! App::new()
+//!     .add_plugins((DefaultPlugins, PixelBufferPlugins, StreamedUploadPlugin))
+//!     .add_systems(Startup, setup)
+//!     .add_systems(Update, paint);
+//! ```
+
+use bevy::{
+    asset::AssetEvents,
+    prelude::*,
+    render::{
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_resource::{Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, TextureAspect},
+        renderer::RenderQueue,
+        texture::GpuImage,
+        Extract, Render, RenderApp, RenderSet,
+    },
+    utils::HashSet,
+};
+
+use crate::{pixel::Pixel, pixel_buffer::pixel_buffer_active};
+
+/// Uploads the image of a pixel buffer to its texture in bands of rows.
+///
+/// Has to be on the entity of the pixel buffer, with its [Sprite]. See the
+/// [module documentation](crate::streaming).
+#[derive(Component, Debug)]
+pub struct StreamedUpload {
+    band_height: u32,
+    bands_per_frame: u32,
+    /// Image the bands are taken from
+    image: AssetId<Image>,
+    /// Size of the image when streaming started
+    size: UVec2,
+    /// If the image is uploaded by bands instead of whole by Bevy
+    streaming: bool,
+    dirty: Vec<bool>,
+    /// Band uploaded after the last one of the previous frame
+    next_band: u32,
+    /// Bands taken this frame, extracted to the render world
+    outgoing: Vec<StreamedBand>,
+}
+
+/// Rows of the image to write into the texture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StreamedBand {
+    y: u32,
+    rows: u32,
+}
+
+impl StreamedUpload {
+    /// Uploads the image in bands of `band_height` rows.
+    ///
+    /// # Panics
+    /// If `band_height` is 0.
+    pub fn new(band_height: u32) -> Self {
+        assert_ne!(band_height, 0, "the band height can't be 0");
+        Self {
+            band_height,
+            bands_per_frame: u32::MAX,
+            image: AssetId::default(),
+            size: UVec2::ZERO,
+            streaming: false,
+            dirty: Vec::new(),
+            next_band: 0,
+            outgoing: Vec::new(),
+        }
+    }
+
+    /// Limits the number of bands uploaded each frame, all the changed bands are uploaded
+    /// by default.
+    ///
+    /// # Panics
+    /// If `bands_per_frame` is 0.
+    pub fn with_bands_per_frame(mut self, bands_per_frame: u32) -> Self {
+        assert_ne!(
+            bands_per_frame, 0,
+            "at least one band has to be uploaded per frame"
+        );
+        self.bands_per_frame = bands_per_frame;
+        self
+    }
+
+    /// If the image is uploaded in bands. It's `false` until the image has been uploaded
+    /// whole once, and again for a frame or two after it's resized.
+    pub fn is_streaming(&self) -> bool {
+        self.streaming
+    }
+
+    /// Number of rows in a band, the last band can have less
+    pub fn band_height(&self) -> u32 {
+        self.band_height
+    }
+
+    /// Number of bands the image is split in
+    pub fn band_count(&self) -> u32 {
+        self.dirty.len() as u32
+    }
+
+    /// Number of bands that changed and are not uploaded yet
+    pub fn dirty_bands(&self) -> u32 {
+        self.dirty.iter().filter(|dirty| **dirty).count() as u32
+    }
+
+    /// Number of frames needed to upload all the bands
+    pub fn frames_for_full_update(&self) -> u32 {
+        self.band_count().div_ceil(self.bands_per_frame)
+    }
+
+    /// Marks the bands of a region of the image to be uploaded. `rect.max` is exclusive.
+    pub fn mark_dirty(&mut self, rect: URect) {
+        let max_y = rect.max.y.min(self.size.y);
+        if rect.min.y >= max_y || rect.min.x >= rect.max.x.min(self.size.x) {
+            return;
+        }
+        let bands = rect.min.y / self.band_height..max_y.div_ceil(self.band_height);
+        self.dirty[bands.start as usize..bands.end as usize].fill(true);
+    }
+
+    /// Marks all the bands to be uploaded
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.fill(true);
+    }
+
+    /// Starts again with an image, that has to be uploaded whole before streaming
+    fn reset(&mut self, image: AssetId<Image>, size: UVec2) {
+        self.image = image;
+        self.size = size;
+        self.streaming = false;
+        self.dirty = vec![false; size.y.div_ceil(self.band_height) as usize];
+        self.next_band = 0;
+        self.outgoing.clear();
+    }
+
+    /// Moves the next dirty bands to `outgoing`
+    fn take_bands(&mut self) {
+        self.outgoing.clear();
+        let count = self.band_count();
+        let first = self.next_band;
+        let mut taken = 0;
+        for i in 0..count {
+            if taken == self.bands_per_frame {
+                break;
+            }
+            let band = (first + i) % count;
+            if !std::mem::take(&mut self.dirty[band as usize]) {
+                continue;
+            }
+            let y = band * self.band_height;
+            let rows = self.band_height.min(self.size.y - y);
+            self.outgoing.push(StreamedBand { y, rows });
+            taken += 1;
+            self.next_band = (band + 1) % count;
+        }
+    }
+}
+
+/// Plugin that uploads the bands of [StreamedUpload]s.
+///
+/// It's not part of [PixelBufferPlugins](crate::pixel_buffer::PixelBufferPlugins), add it
+/// to use streaming. See the [module documentation](crate::streaming).
+pub struct StreamedUploadPlugin;
+
+impl Plugin for StreamedUploadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Last,
+            take_streamed_bands
+                .after(AssetEvents)
+                .run_if(pixel_buffer_active),
+        );
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ExtractedStreamedBands>()
+                .add_systems(ExtractSchedule, extract_streamed_bands)
+                .add_systems(Render, upload_streamed_bands.in_set(RenderSet::Prepare));
+        } else {
+            warn!("Can't build StreamedUploadPlugin: RenderApp sub app not found.")
+        }
+    }
+}
+
+/// Switches the images between being uploaded whole by Bevy and streamed, and takes the
+/// bands to stream.
+///
+/// Images without [RenderAssetUsages::RENDER_WORLD] are not extracted by Bevy when they
+/// change, so the texture is kept and only the bands are written to it.
+fn take_streamed_bands(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+    mut streamed: Query<(&mut StreamedUpload, &Sprite)>,
+) {
+    let changed: HashSet<_> = image_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (mut streamed, sprite) in streamed.iter_mut() {
+        let id = sprite.image.id();
+        let Some(image) = images.get(id) else {
+            continue;
+        };
+        if id != streamed.image || image.size() != streamed.size {
+            streamed.reset(id, image.size());
+        }
+        if streamed.streaming {
+            streamed.take_bands();
+            continue;
+        }
+
+        let uploaded = image.asset_usage.contains(RenderAssetUsages::RENDER_WORLD);
+        if !uploaded {
+            // resized while streaming, upload it whole once
+            let image = images.get_mut(id).unwrap();
+            image.asset_usage.insert(RenderAssetUsages::RENDER_WORLD);
+        } else if !changed.contains(&id) {
+            // the last change has been uploaded, the texture is up to date
+            let image = images.get_mut(id).unwrap();
+            image.asset_usage.remove(RenderAssetUsages::RENDER_WORLD);
+            streamed.streaming = true;
+        }
+    }
+}
+
+/// Rows of an image extracted to the render world
+struct ExtractedBand {
+    image: AssetId<Image>,
+    y: u32,
+    size: UVec2,
+    data: Vec<u8>,
+}
+
+#[derive(Resource, Default)]
+struct ExtractedStreamedBands(Vec<ExtractedBand>);
+
+fn extract_streamed_bands(
+    mut extracted: ResMut<ExtractedStreamedBands>,
+    streamed: Extract<Query<(&StreamedUpload, &Sprite)>>,
+    images: Extract<Res<Assets<Image>>>,
+) {
+    extracted.0.clear();
+    for (streamed, sprite) in streamed.iter() {
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        let row = streamed.size.x as usize * size_of::<Pixel>();
+        for band in streamed.outgoing.iter() {
+            let start = band.y as usize * row;
+            let Some(data) = image.data.get(start..start + band.rows as usize * row) else {
+                continue;
+            };
+            extracted.0.push(ExtractedBand {
+                image: sprite.image.id(),
+                y: band.y,
+                size: UVec2::new(streamed.size.x, band.rows),
+                data: data.to_vec(),
+            });
+        }
+    }
+}
+
+fn upload_streamed_bands(
+    extracted: Res<ExtractedStreamedBands>,
+    images: Res<RenderAssets<GpuImage>>,
+    queue: Res<RenderQueue>,
+) {
+    for band in extracted.0.iter() {
+        let Some(image) = images.get(band.image) else {
+            continue;
+        };
+        // the texture has not been uploaded with the new size yet
+        if image.size.x != band.size.x || band.y + band.size.y > image.size.y {
+            continue;
+        }
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &image.texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: 0,
+                    y: band.y,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            &band.data,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(band.size.x * size_of::<Pixel>() as u32),
+                rows_per_image: Some(band.size.y),
+            },
+            Extent3d {
+                width: band.size.x,
+                height: band.size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::Frame;
+
+    fn taken_rows(streamed: &StreamedUpload) -> Vec<u32> {
+        streamed.outgoing.iter().map(|band| band.y).collect()
+    }
+
+    #[test]
+    fn stream_dirty_bands() {
+        let mut streamed = StreamedUpload::new(4).with_bands_per_frame(2);
+        streamed.reset(AssetId::default(), UVec2::new(4, 10));
+        assert_eq!(streamed.band_count(), 3);
+        assert_eq!(streamed.frames_for_full_update(), 2);
+
+        // all the bands, over 2 frames
+        streamed.mark_all_dirty();
+        streamed.take_bands();
+        assert_eq!(taken_rows(&streamed), [0, 4]);
+        streamed.take_bands();
+        assert_eq!(streamed.outgoing, [StreamedBand { y: 8, rows: 2 }]);
+        streamed.take_bands();
+        assert!(streamed.outgoing.is_empty());
+
+        // only the bands of the region
+        streamed.mark_dirty(URect::new(1, 3, 2, 5));
+        assert_eq!(streamed.dirty_bands(), 2);
+        streamed.take_bands();
+        assert_eq!(taken_rows(&streamed), [0, 4]);
+
+        // bands that change every frame don't starve the others
+        streamed.mark_all_dirty();
+        streamed.take_bands();
+        assert_eq!(taken_rows(&streamed), [8, 0]);
+        streamed.mark_dirty(URect::new(0, 0, 4, 1));
+        streamed.take_bands();
+        assert_eq!(taken_rows(&streamed), [4, 0]);
+
+        // outside of the pixels
+        streamed.mark_dirty(URect::new(0, 10, 4, 20));
+        assert_eq!(streamed.dirty_bands(), 0);
+    }
+
+    #[test]
+    fn stream_image() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(bevy::asset::AssetPlugin::default())
+            .add_plugins(bevy::render::texture::ImagePlugin::default())
+            .add_plugins(StreamedUploadPlugin);
+
+        let image = app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(crate::pixel_buffer::create_image(UVec2::new(2, 4).into()));
+        let entity = app
+            .world_mut()
+            .spawn((Sprite::from_image(image.clone()), StreamedUpload::new(2)))
+            .id();
+        let usage = |app: &App| {
+            let images = app.world().resource::<Assets<Image>>();
+            images.get(&image).unwrap().asset_usage
+        };
+
+        // the image is uploaded whole by Bevy first
+        app.update();
+        let streamed = app.world().get::<StreamedUpload>(entity).unwrap();
+        assert!(!streamed.is_streaming());
+        assert_eq!(streamed.band_count(), 2);
+        assert!(usage(&app).contains(RenderAssetUsages::RENDER_WORLD));
+
+        // then only the marked bands
+        app.update();
+        assert!(app
+            .world()
+            .get::<StreamedUpload>(entity)
+            .unwrap()
+            .is_streaming());
+        assert!(!usage(&app).contains(RenderAssetUsages::RENDER_WORLD));
+
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        Frame::extract(&mut images, &image)
+            .set((1, 3), Pixel::RED)
+            .unwrap();
+        let mut streamed = app.world_mut().get_mut::<StreamedUpload>(entity).unwrap();
+        streamed.mark_dirty(URect::new(1, 3, 2, 4));
+        app.update();
+        let streamed = app.world().get::<StreamedUpload>(entity).unwrap();
+        assert_eq!(streamed.outgoing, [StreamedBand { y: 2, rows: 2 }]);
+
+        // a resized image is uploaded whole again
+        app.world_mut()
+            .resource_mut::<Assets<Image>>()
+            .get_mut(&image)
+            .unwrap()
+            .resize(Extent3d {
+                width: 2,
+                height: 6,
+                depth_or_array_layers: 1,
+            });
+        app.update();
+        let streamed = app.world().get::<StreamedUpload>(entity).unwrap();
+        assert!(!streamed.is_streaming());
+        assert_eq!(streamed.band_count(), 3);
+        assert!(usage(&app).contains(RenderAssetUsages::RENDER_WORLD));
+        app.update();
+        assert!(!app
+            .world()
+            .get::<StreamedUpload>(entity)
+            .unwrap()
+            .is_streaming());
+        app.update();
+        assert!(app
+            .world()
+            .get::<StreamedUpload>(entity)
+            .unwrap()
+            .is_streaming());
+    }
+}
@@ -0,0 +1,417 @@
+//! Drawing text with an embedded bitmap font.
+//!
+//! The font covers printable ASCII, other characters are drawn as `?`. Each glyph is
+//! [GLYPH_SIZE] pixels and text advances [ADVANCE] pixels per character and per line,
+//! leaving one pixel of spacing. Drawing is clipped to the frame.
+//!
+//! # Example
+//! ```
+//! # use bevy::math::{IVec2, URect, UVec2};
+//! # use bevy_pixel_buffer::prelude::*;
+//! # let mut pixels = vec![Pixel::BLACK; 64*32];
+//! # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(64, 32));
+//! frame.draw_text(IVec2::new(1, 1), "FPS: 60", Pixel::WHITE);
+//! let lines = frame.draw_text_wrapped(URect::new(0, 10, 64, 32), "some longer text", Pixel::RED);
+//! assert_eq!(lines, 2);
+//! ```
+
+use bevy::math::{IVec2, URect, UVec2};
+
+use crate::{frame::Frame, pixel::Pixel};
+
+/// Size of a glyph in pixels
+pub const GLYPH_SIZE: UVec2 = UVec2::new(5, 7);
+
+/// Distance between the origins of two consecutive characters and two consecutive lines
+pub const ADVANCE: UVec2 = UVec2::new(6, 8);
+
+/// Glyphs for ASCII 32..=126, 5 columns each with the top row in the lowest bit
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Columns of the glyph of a character
+fn glyph(c: char) -> &'static [u8; 5] {
+    let index = match c {
+        ' '..='~' => c as usize - ' ' as usize,
+        _ => '?' as usize - ' ' as usize,
+    };
+    &FONT[index]
+}
+
+impl<'a> Frame<'a> {
+    /// Draws text with its top left corner at `position`.
+    ///
+    /// New lines start below the first one, at the same `x`. See the
+    /// [module documentation](crate::text).
+    pub fn draw_text(&mut self, position: IVec2, text: &str, pixel: impl Into<Pixel>) {
+        let pixel = pixel.into();
+        for (i, line) in text.lines().enumerate() {
+            let y = position.y + (i as u32 * ADVANCE.y) as i32;
+            self.draw_text_line(IVec2::new(position.x, y), line, pixel, None);
+        }
+    }
+
+    /// Draws text with a one pixel outline around the glyphs, to keep it readable over
+    /// busy backgrounds.
+    ///
+    /// The outline extends one pixel beyond the text drawn by [Frame::draw_text] in every
+    /// direction, including diagonally.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLUE; 64*16];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(64, 16));
+    /// frame.draw_text_outlined(IVec2::new(1, 1), "SCORE 42", Pixel::WHITE, Pixel::BLACK);
+    /// ```
+    pub fn draw_text_outlined(
+        &mut self,
+        position: IVec2,
+        text: &str,
+        fill: impl Into<Pixel>,
+        outline: impl Into<Pixel>,
+    ) {
+        let outline = outline.into();
+        for y in -1..=1 {
+            for x in -1..=1 {
+                if x != 0 || y != 0 {
+                    self.draw_text(position + IVec2::new(x, y), text, outline);
+                }
+            }
+        }
+        self.draw_text(position, text, fill);
+    }
+
+    /// Draws runs of text with a different color each, one after the other, for example
+    /// for highlighted words.
+    ///
+    /// Each run starts where the previous one ended. A new line in a run continues the
+    /// text below the first line, at the `x` of `position`, as in [Frame::draw_text].
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy::math::{IVec2, UVec2};
+    /// # use bevy_pixel_buffer::prelude::*;
+    /// # let mut pixels = vec![Pixel::BLACK; 64*16];
+    /// # let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(64, 16));
+    /// frame.draw_text_colored(
+    ///     IVec2::new(1, 1),
+    ///     &[("HP: ", Pixel::WHITE), ("12", Pixel::RED), ("/20", Pixel::WHITE)],
+    /// );
+    /// ```
+    pub fn draw_text_colored(&mut self, position: IVec2, runs: &[(&str, Pixel)]) {
+        let mut cursor = position;
+        for (text, pixel) in runs {
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    cursor = IVec2::new(position.x, cursor.y + ADVANCE.y as i32);
+                }
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                self.draw_text_line(cursor, line, *pixel, None);
+                cursor.x += (line.chars().count() as u32 * ADVANCE.x) as i32;
+            }
+        }
+    }
+
+    /// Draws text inside a rectangle, wrapping the words at its width.
+    ///
+    /// Lines are broken at spaces, words longer than a line are broken anywhere. New lines
+    /// in the text are kept. Lines that don't fit completely in the height of the rectangle
+    /// are not drawn. `rect.max` is exclusive.
+    ///
+    /// Returns the number of lines drawn.
+    pub fn draw_text_wrapped(&mut self, rect: URect, text: &str, pixel: impl Into<Pixel>) -> u32 {
+        let pixel = pixel.into();
+        // a glyph doesn't need the spacing after it
+        let max_chars = ((rect.width() + 1) / ADVANCE.x) as usize;
+        let max_lines = (rect.height() + 1) / ADVANCE.y;
+
+        let mut lines = 0;
+        for line in wrap(text, max_chars).take(max_lines as usize) {
+            let position = rect.min + UVec2::new(0, lines * ADVANCE.y);
+            self.draw_text_line(position.as_ivec2(), line, pixel, Some(rect));
+            lines += 1;
+        }
+        lines
+    }
+
+    /// Draws a single line of text, optionally clipped to a rectangle
+    fn draw_text_line(&mut self, position: IVec2, line: &str, pixel: Pixel, clip: Option<URect>) {
+        for (i, c) in line.chars().enumerate() {
+            let origin = position + IVec2::new((i as u32 * ADVANCE.x) as i32, 0);
+            for (x, column) in glyph(c).iter().enumerate() {
+                for y in 0..GLYPH_SIZE.y {
+                    if column & (1 << y) == 0 {
+                        continue;
+                    }
+                    let location = origin + IVec2::new(x as i32, y as i32);
+                    let inside = clip.is_none_or(|clip| {
+                        location.cmpge(clip.min.as_ivec2()).all()
+                            && location.cmplt(clip.max.as_ivec2()).all()
+                    });
+                    if inside {
+                        self.set_clipped(location, pixel);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Splits the text into lines of at most `max_chars` characters
+fn wrap(text: &str, max_chars: usize) -> impl Iterator<Item = &str> {
+    text.lines().flat_map(move |paragraph| WrapLines {
+        rest: paragraph,
+        max_chars,
+        first: true,
+    })
+}
+
+/// Lines of a paragraph without new lines, see [wrap]
+struct WrapLines<'t> {
+    rest: &'t str,
+    max_chars: usize,
+    /// An empty paragraph is still an empty line
+    first: bool,
+}
+
+impl<'t> Iterator for WrapLines<'t> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.max_chars == 0 {
+            return None;
+        }
+        let first = std::mem::take(&mut self.first);
+        // spaces at the start of the next lines are the break
+        let rest = if first {
+            self.rest
+        } else {
+            self.rest.trim_start_matches(' ')
+        };
+        if rest.is_empty() {
+            self.rest = rest;
+            return first.then_some("");
+        }
+
+        // byte index after `max_chars` characters, or the end
+        let limit = rest
+            .char_indices()
+            .nth(self.max_chars)
+            .map_or(rest.len(), |(i, _)| i);
+        let end = if limit == rest.len() || rest[limit..].starts_with(' ') {
+            limit
+        } else {
+            // break at the last space, if any, or in the middle of the word
+            match rest[..limit].rfind(' ') {
+                Some(space) if space > 0 => space,
+                _ => limit,
+            }
+        };
+
+        let (line, rest) = rest.split_at(end);
+        self.rest = rest;
+        Some(line.trim_end_matches(' '))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_words() {
+        let lines: Vec<_> = wrap("the quick brown fox\n\njumps", 10).collect();
+        assert_eq!(lines, ["the quick", "brown fox", "", "jumps"]);
+
+        let lines: Vec<_> = wrap("abcdefghij klm", 4).collect();
+        assert_eq!(lines, ["abcd", "efgh", "ij", "klm"]);
+    }
+
+    #[test]
+    fn draw_wrapped() {
+        let size = UVec2::new(40, 40);
+        let mut pixels = vec![Pixel::BLACK; 40 * 40];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+
+        // 4 characters per line, 4 lines fit in the height
+        let rect = URect::new(2, 2, 2 + 23, 2 + 31);
+        let text = "one two three four five six";
+        assert_eq!(wrap(text, 4).count(), 7);
+        let lines = frame.draw_text_wrapped(rect, text, Pixel::WHITE);
+        assert_eq!(lines, 4);
+
+        // nothing outside of the rect
+        for (i, pixel) in frame.raw().iter().enumerate() {
+            let location = UVec2::new(i as u32 % size.x, i as u32 / size.x);
+            if *pixel == Pixel::WHITE {
+                assert!(location.cmpge(rect.min).all() && location.cmplt(rect.max).all());
+            }
+        }
+        // the first line starts at the corner of the rect, the left of `o` is in rows 2 to 4
+        assert_eq!(frame.raw()[(2 + 40 * 4) as usize], Pixel::BLACK);
+        assert_eq!(frame.raw()[(2 + 40 * 5) as usize], Pixel::WHITE);
+    }
+
+    #[test]
+    fn draw_text_glyphs() {
+        let size = UVec2::new(12, 16);
+        let mut pixels = vec![Pixel::BLACK; 12 * 16];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.draw_text(IVec2::new(0, 0), "|\n-", Pixel::RED);
+
+        // vertical bar in the middle column, minus in the second line
+        for y in 0..7 {
+            assert_eq!(frame.raw()[(2 + y * 12) as usize], Pixel::RED);
+        }
+        let minus_row = &frame.raw()[(12 * (8 + 3)) as usize..][..5];
+        assert!(minus_row.iter().all(|p| *p == Pixel::RED));
+        assert_eq!(frame.raw().iter().filter(|p| **p == Pixel::RED).count(), 12);
+    }
+
+    #[test]
+    fn draw_colored_runs() {
+        let size = UVec2::new(18, 16);
+        let mut pixels = vec![Pixel::BLACK; 18 * 16];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.draw_text_colored(
+            IVec2::ZERO,
+            &[("|", Pixel::RED), ("|\n", Pixel::BLUE), ("-", Pixel::GREEN)],
+        );
+
+        // the vertical bars are in the middle column of the first two characters
+        for y in 0..7 {
+            assert_eq!(frame.raw()[(2 + y * 18) as usize], Pixel::RED);
+            assert_eq!(frame.raw()[(2 + ADVANCE.x + y * 18) as usize], Pixel::BLUE);
+        }
+        // the minus starts the second line
+        let minus_row = &frame.raw()[(18 * (8 + 3)) as usize..][..5];
+        assert!(minus_row.iter().all(|p| *p == Pixel::GREEN));
+        assert_eq!(frame.raw().iter().filter(|p| **p == Pixel::RED).count(), 7);
+        assert_eq!(frame.raw().iter().filter(|p| **p == Pixel::BLUE).count(), 7);
+    }
+
+    #[test]
+    fn draw_outlined() {
+        let size = UVec2::new(7, 10);
+        let mut pixels = vec![Pixel::BLUE; 7 * 10];
+        let mut frame = Frame::from_raw_parts(&mut pixels, size);
+        frame.draw_text_outlined(IVec2::ONE, "|", Pixel::WHITE, Pixel::BLACK);
+
+        // the bar is in column 3, rows 1 to 7, surrounded by the outline
+        for y in 0..9 {
+            let fill = if (1..8).contains(&y) {
+                Pixel::WHITE
+            } else {
+                Pixel::BLACK
+            };
+            assert_eq!(frame.raw()[(3 + y * 7) as usize], fill);
+            assert_eq!(frame.raw()[(2 + y * 7) as usize], Pixel::BLACK);
+            assert_eq!(frame.raw()[(4 + y * 7) as usize], Pixel::BLACK);
+        }
+        assert_eq!(
+            frame.raw().iter().filter(|p| **p == Pixel::BLACK).count(),
+            20
+        );
+
+        // clipped at the edges
+        frame.draw_text_outlined(IVec2::new(-3, -1), "|", Pixel::WHITE, Pixel::BLACK);
+        assert_eq!(frame.raw()[0], Pixel::BLACK);
+    }
+}
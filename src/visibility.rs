@@ -0,0 +1,117 @@
+//! Skipping work for pixel buffers that are not visible.
+//!
+//! Updating the pixels of a buffer that is off screen, or inside an egui window that
+//! is closed, is wasted work, and so is uploading the image to the GPU. This is opt-in
+//! with the [UpdateWhenVisible] component, because buffers used as offscreen render
+//! targets are never visible.
+//!
+//! The [PixelBufferPlugin](crate::pixel_buffer::PixelBufferPlugin) keeps the [Culled]
+//! marker component in the buffers with [UpdateWhenVisible] that are not visible.
+//! Then:
+//! - Systems that update the pixels should skip buffers with [Culled]. Not modifying
+//!   the image also means it is not uploaded to the GPU.
+//! - [Compute shaders](crate::compute_shader) are not dispatched for buffers with [Culled].
+//!
+//! The visibility is computed at the end of every frame, so it is one frame behind.
+//!
+//! # Example
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_pixel_buffer::prelude::*;
+//! fn update(mut images: ResMut<Assets<Image>>, pixel_buffers: Query<&Sprite, Without<Culled>>) {
+//!     for sprite in pixel_buffers.iter() {
+//!         images.frame(&sprite.image).per_pixel(|_, _| Pixel::random())
+//!     }
+//! }
+//! # bevy::ecs::system::assert_is_system(update);
+//! ```
+
+use bevy::prelude::*;
+
+/// Opt-in to skip updating the pixel buffer while it is not visible.
+///
+/// See the [module documentation](crate::visibility).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateWhenVisible {
+    /// Visible when the sprite is visible in any view, see [ViewVisibility].
+    Sprite,
+    /// Visibility set manually, for example from the open state of the egui window
+    /// that shows the pixel buffer.
+    Manual(bool),
+}
+
+/// Marker component for pixel buffers with [UpdateWhenVisible] that are not visible.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Culled;
+
+/// Inserts or removes [Culled] from the buffers.
+pub(crate) fn update_culled(
+    mut commands: Commands,
+    pixel_buffers: Query<(
+        Entity,
+        &UpdateWhenVisible,
+        Option<&ViewVisibility>,
+        Has<Culled>,
+    )>,
+    mut removed: RemovedComponents<UpdateWhenVisible>,
+) {
+    for (entity, update_when, view_visibility, culled) in pixel_buffers.iter() {
+        let visible = match update_when {
+            UpdateWhenVisible::Sprite => view_visibility.is_some_and(|v| v.get()),
+            UpdateWhenVisible::Manual(visible) => *visible,
+        };
+
+        if visible && culled {
+            commands.entity(entity).remove::<Culled>();
+        } else if !visible && !culled {
+            commands.entity(entity).insert(Culled);
+        }
+    }
+
+    for entity in removed.read() {
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity.remove::<Culled>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn do_update_culled() {
+        let mut app = App::new();
+        app.add_systems(Update, update_culled);
+
+        let manual = app.world_mut().spawn(UpdateWhenVisible::Manual(false)).id();
+        let sprite = app.world_mut().spawn(UpdateWhenVisible::Sprite).id();
+        let visible_sprite = app
+            .world_mut()
+            .spawn((UpdateWhenVisible::Sprite, ViewVisibility::default()))
+            .id();
+        app.world_mut()
+            .get_mut::<ViewVisibility>(visible_sprite)
+            .unwrap()
+            .set();
+        let always = app.world_mut().spawn_empty().id();
+
+        app.update();
+
+        assert!(app.world().get::<Culled>(manual).is_some());
+        assert!(app.world().get::<Culled>(sprite).is_some());
+        assert!(app.world().get::<Culled>(visible_sprite).is_none());
+        assert!(app.world().get::<Culled>(always).is_none());
+
+        *app.world_mut()
+            .get_mut::<UpdateWhenVisible>(manual)
+            .unwrap() = UpdateWhenVisible::Manual(true);
+        app.world_mut()
+            .entity_mut(sprite)
+            .remove::<UpdateWhenVisible>();
+        app.update();
+
+        assert!(app.world().get::<Culled>(manual).is_none());
+        assert!(app.world().get::<Culled>(sprite).is_none());
+    }
+}